@@ -0,0 +1,125 @@
+//! Parsing and printing of amounts, periods and timestamps
+
+use anyhow::{bail, ensure, Context, Result};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Parse a SOL amount such as `1.5` into lamports, without float rounding
+pub fn parse_sol(amount: &str) -> Result<u64> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    ensure!(
+        !(whole.is_empty() && fraction.is_empty()) && fraction.len() <= 9,
+        "invalid SOL amount {amount:?} (at most 9 decimals)"
+    );
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole
+            .parse()
+            .with_context(|| format!("invalid SOL amount {amount:?}"))?
+    };
+    let fraction: u64 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<9}")
+            .parse()
+            .with_context(|| format!("invalid SOL amount {amount:?}"))?
+    };
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|lamports| lamports.checked_add(fraction))
+        .with_context(|| format!("SOL amount {amount:?} is too large"))
+}
+
+/// Parse a period given in seconds, or with an `s`, `m`, `h` or `d` suffix
+pub fn parse_period(period: &str) -> Result<i64> {
+    let (digits, unit) = match period.char_indices().last() {
+        Some((i, unit)) if unit.is_ascii_alphabetic() => (&period[..i], unit),
+        _ => (period, 's'),
+    };
+    let unit_seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        _ => bail!("invalid period {period:?} (use a number of seconds or a s/m/h/d suffix)"),
+    };
+    let value: i64 = digits.parse().with_context(|| format!("invalid period {period:?}"))?;
+    value
+        .checked_mul(unit_seconds)
+        .with_context(|| format!("period {period:?} is too long"))
+}
+
+pub fn format_sol(lamports: u64) -> String {
+    let fraction = format!("{:09}", lamports % LAMPORTS_PER_SOL);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{} SOL", lamports / LAMPORTS_PER_SOL)
+    } else {
+        format!("{}.{fraction} SOL", lamports / LAMPORTS_PER_SOL)
+    }
+}
+
+/// Largest two units of a duration, such as `3d 4h`
+pub fn format_duration(seconds: i64) -> String {
+    let units = [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)];
+    let mut remaining = seconds.unsigned_abs();
+    let parts: Vec<String> = units
+        .iter()
+        .filter_map(|(name, size)| {
+            let count = remaining / size;
+            remaining %= size;
+            (count > 0).then(|| format!("{count}{name}"))
+        })
+        .take(2)
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// A unix timestamp relative to the cluster's current time
+pub fn format_time(timestamp: i64, now: i64) -> String {
+    if timestamp > now {
+        format!("{timestamp} (in {})", format_duration(timestamp - now))
+    } else {
+        format!("{timestamp} ({} ago)", format_duration(now - timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sol_without_rounding() {
+        assert_eq!(parse_sol("1").unwrap(), 1_000_000_000);
+        assert_eq!(parse_sol("1.5").unwrap(), 1_500_000_000);
+        assert_eq!(parse_sol("0.000000001").unwrap(), 1);
+        assert_eq!(parse_sol(".25").unwrap(), 250_000_000);
+        assert!(parse_sol("0.0000000001").is_err());
+        assert!(parse_sol("1.2.3").is_err());
+        assert!(parse_sol(".").is_err());
+        assert!(parse_sol("99999999999").is_err());
+    }
+
+    #[test]
+    fn parses_periods() {
+        assert_eq!(parse_period("3600").unwrap(), 3_600);
+        assert_eq!(parse_period("90m").unwrap(), 5_400);
+        assert_eq!(parse_period("30d").unwrap(), 2_592_000);
+        assert!(parse_period("2w").is_err());
+        assert!(parse_period("d").is_err());
+    }
+
+    #[test]
+    fn formats_amounts_and_times() {
+        assert_eq!(format_sol(1_500_000_000), "1.5 SOL");
+        assert_eq!(format_sol(2_000_000_000), "2 SOL");
+        assert_eq!(format_duration(3 * 86_400 + 4 * 3_600 + 59), "3d 4h");
+        assert_eq!(format_time(1_000, 940), "1000 (in 1m)");
+        assert_eq!(format_time(1_000, 1_000), "1000 (0s ago)");
+    }
+}
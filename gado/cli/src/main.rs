@@ -0,0 +1,539 @@
+//! `gado`: manage an estate plan from the command line.
+//!
+//! Transactions are signed with a local keypair file and sent to `--url`.
+//! For offline signing, `--sign-only --blockhash <HASH>` prints the signed
+//! transaction instead of sending it, and `gado broadcast` sends it later.
+//! Commands that would read a mint or a designation over RPC take them as
+//! flags instead (`--token-program`, `--custody`), which `--sign-only` requires.
+//! `--dump-tx --blockhash <HASH>` prints the unsigned transaction for signing
+//! elsewhere. Neither offline mode fetches a blockhash, so both require one.
+
+mod format;
+
+use std::path::PathBuf;
+
+use anyhow::{bail, ensure, Context, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use gado_client::rpc::{Filter, RpcClient};
+use gado_client::state::{HEIR_OFFSET, OWNER_OFFSET};
+use gado_client::timing::{self, Designation};
+use gado_client::{
+    instructions, pda, state, AccountDeserialize, Hash, Instruction, MintRiskPolicy, Pubkey, SolHeir, TokenCustody,
+    TokenHeir, UserProfile,
+};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::format::{format_duration, format_sol, format_time, parse_period, parse_sol};
+
+#[derive(Parser)]
+#[command(name = "gado", version, about = "Manage a Gado estate plan")]
+struct Cli {
+    /// JSON-RPC endpoint of the cluster
+    #[arg(long, short = 'u', global = true, default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Keypair file that signs and pays for transactions
+    #[arg(long, short = 'k', global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Sign the transaction and print it (base64) instead of sending it
+    #[arg(long, global = true, requires = "blockhash")]
+    sign_only: bool,
+
+    /// Print the unsigned transaction (base64) instead of signing and sending it
+    #[arg(long, global = true, conflicts_with = "sign_only", requires = "blockhash")]
+    dump_tx: bool,
+
+    /// Recent blockhash to build the transaction with, instead of fetching one
+    #[arg(long, global = true)]
+    blockhash: Option<Hash>,
+
+    /// With --dump-tx, build the transaction for this key instead of the keypair's
+    #[arg(long, global = true, requires = "dump_tx")]
+    signer: Option<Pubkey>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create your estate profile
+    Init,
+    /// Bequeath SOL, held in escrow until claimed or cancelled
+    AddSolHeir {
+        #[arg(long)]
+        heir: Pubkey,
+        /// Amount in SOL, such as 1.5
+        #[arg(long, value_parser = parse_sol)]
+        amount: u64,
+        /// Inactivity before the heir may claim: seconds, or with a s/m/h/d suffix
+        #[arg(long, value_parser = parse_period)]
+        period: i64,
+        /// Index distinguishing several bequests to the same heir
+        #[arg(long, default_value_t = 0)]
+        tranche: u16,
+    },
+    /// Bequeath tokens from your associated token account, held in escrow
+    AddTokenHeir {
+        #[arg(long)]
+        heir: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Amount in base units
+        #[arg(long)]
+        amount: u64,
+        /// Inactivity before the heir may claim: seconds, or with a s/m/h/d suffix
+        #[arg(long, value_parser = parse_period)]
+        period: i64,
+        #[arg(long, default_value_t = 0)]
+        tranche: u16,
+        /// What to do if the mint's issuer can freeze or drain the escrow
+        #[arg(long, value_enum, default_value_t = Policy::Reject)]
+        mint_risk_policy: Policy,
+        #[command(flatten)]
+        token_program: TokenProgramArg,
+    },
+    /// Record a heartbeat, vetoing any pending claim on your estate
+    Ping,
+    /// Print an estate profile
+    Status {
+        /// Estate owner (defaults to you)
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// As an heir, request a claim, or finalize one once its notice period is over
+    Claim {
+        /// Owner of the estate
+        #[arg(long)]
+        owner: Pubkey,
+        #[command(flatten)]
+        designation: DesignationArgs,
+        /// Finalize a requested claim instead of requesting one
+        #[arg(long)]
+        finalize: bool,
+    },
+    /// Cancel one of your designations and take back what it holds
+    Cancel {
+        #[arg(long)]
+        heir: Pubkey,
+        #[command(flatten)]
+        designation: DesignationArgs,
+    },
+    /// List SOL and token designations you made, or with --as-heir, those naming you
+    List {
+        #[arg(long)]
+        as_heir: bool,
+    },
+    /// Grow an estate profile from an older layout (anyone may pay)
+    MigrateProfile {
+        /// Estate owner (defaults to you)
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        /// Migrated designation of the owner whose heartbeat seeds the profile's
+        /// last activity; repeat for each designation
+        #[arg(long = "designation")]
+        designations: Vec<Pubkey>,
+    },
+    /// Grow a SOL or token designation from an older layout (anyone may pay)
+    MigrateHeir {
+        /// Address of the designation
+        address: Pubkey,
+    },
+    /// Send a transaction printed by --sign-only
+    Broadcast {
+        /// Base64-encoded signed transaction
+        transaction: String,
+    },
+}
+
+/// Which SOL or token designation a command applies to
+#[derive(Args)]
+struct DesignationArgs {
+    /// Mint of a token designation; omit for SOL
+    #[arg(long)]
+    mint: Option<Pubkey>,
+    #[arg(long, default_value_t = 0)]
+    tranche: u16,
+    /// How the token designation holds its tokens; read from it if omitted
+    #[arg(long, value_enum, requires = "mint")]
+    custody: Option<Custody>,
+    #[command(flatten)]
+    token_program: TokenProgramArg,
+}
+
+#[derive(Args)]
+struct TokenProgramArg {
+    /// Token program of the mint; read from the mint's owner if omitted
+    #[arg(long)]
+    token_program: Option<Pubkey>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Policy {
+    Reject,
+    Warn,
+    Allow,
+}
+
+impl From<Policy> for MintRiskPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Reject => MintRiskPolicy::Reject,
+            Policy::Warn => MintRiskPolicy::Warn,
+            Policy::Allow => MintRiskPolicy::Allow,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Custody {
+    Escrow,
+    Delegate,
+    Share,
+}
+
+impl From<Custody> for TokenCustody {
+    fn from(custody: Custody) -> Self {
+        match custody {
+            Custody::Escrow => TokenCustody::Escrow,
+            Custody::Delegate => TokenCustody::Delegate,
+            Custody::Share => TokenCustody::Share,
+        }
+    }
+}
+
+struct Session {
+    cli: Cli,
+    rpc: RpcClient,
+    /// `None` when only building a `--dump-tx` transaction for `--signer`
+    keypair: Option<Keypair>,
+    me: Pubkey,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new(cli.url.clone());
+
+    if let Command::Broadcast { transaction } = &cli.command {
+        return broadcast(&rpc, transaction);
+    }
+
+    let keypair = match cli.signer {
+        Some(_) => None,
+        None => Some(read_keypair(&cli.keypair)?),
+    };
+    let me = cli
+        .signer
+        .unwrap_or_else(|| keypair.as_ref().map(Signer::pubkey).unwrap_or_default());
+    let session = Session { cli, rpc, keypair, me };
+    session.run()
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(std::env::var("HOME").context("HOME is not set")?).join(rest),
+        None => PathBuf::from(path),
+    };
+    read_keypair_file(&path).map_err(|err| anyhow::anyhow!("cannot read keypair {}: {err}", path.display()))
+}
+
+fn broadcast(rpc: &RpcClient, transaction: &str) -> Result<()> {
+    let bytes = BASE64_STANDARD
+        .decode(transaction.trim())
+        .context("transaction is not base64")?;
+    let decoded: Transaction = bincode::deserialize(&bytes).context("not a serialized transaction")?;
+    ensure!(decoded.is_signed(), "transaction is missing signatures");
+    let signature = rpc.send_and_confirm_transaction(&bytes)?;
+    println!("Signature: {signature}");
+    Ok(())
+}
+
+impl Session {
+    fn run(&self) -> Result<()> {
+        let me = &self.me;
+        match &self.cli.command {
+            Command::Init => self.execute(&[instructions::initialize_user(me)]),
+            Command::AddSolHeir {
+                heir,
+                amount,
+                period,
+                tranche,
+            } => self.execute(&[instructions::add_sol_heir(me, heir, *tranche, *amount, *period)]),
+            Command::AddTokenHeir {
+                heir,
+                mint,
+                amount,
+                period,
+                tranche,
+                mint_risk_policy,
+                token_program,
+            } => {
+                let token_program = self.token_program(mint, token_program)?;
+                self.execute(&[instructions::add_token_heir(
+                    me,
+                    heir,
+                    mint,
+                    &token_program,
+                    *tranche,
+                    *amount,
+                    *period,
+                    (*mint_risk_policy).into(),
+                )])
+            }
+            Command::Ping => self.execute(&[instructions::heartbeat(me)]),
+            Command::Status { owner } => self.status(owner.as_ref().unwrap_or(me)),
+            Command::Claim {
+                owner,
+                designation,
+                finalize,
+            } => self.claim(owner, designation, *finalize),
+            Command::Cancel { heir, designation } => self.cancel(heir, designation),
+            Command::List { as_heir } => self.list(*as_heir),
+            Command::MigrateProfile { owner, designations } => self.execute(&[instructions::migrate_user_profile(
+                owner.as_ref().unwrap_or(me),
+                me,
+                designations,
+            )]),
+            Command::MigrateHeir { address } => self.execute(&[instructions::migrate_heir_account(address, me)]),
+            Command::Broadcast { .. } => unreachable!("handled before a keypair is read"),
+        }
+    }
+
+    /// Sign and send `instructions`, or print the transaction as the flags ask
+    fn execute(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = match self.cli.blockhash {
+            Some(blockhash) => blockhash,
+            None => self.rpc.get_latest_blockhash()?,
+        };
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&self.me));
+        transaction.message.recent_blockhash = blockhash;
+
+        if self.cli.dump_tx {
+            println!("{}", BASE64_STANDARD.encode(bincode::serialize(&transaction)?));
+            return Ok(());
+        }
+
+        let keypair = self.keypair.as_ref().context("a keypair is needed to sign")?;
+        transaction.try_sign(&[keypair], blockhash)?;
+        let serialized = bincode::serialize(&transaction)?;
+        if self.cli.sign_only {
+            println!("{}", BASE64_STANDARD.encode(serialized));
+            return Ok(());
+        }
+
+        let signature = self.rpc.send_and_confirm_transaction(&serialized)?;
+        println!("Signature: {signature}");
+        Ok(())
+    }
+
+    fn token_program(&self, mint: &Pubkey, arg: &TokenProgramArg) -> Result<Pubkey> {
+        if let Some(token_program) = arg.token_program {
+            return Ok(token_program);
+        }
+        ensure!(
+            !self.cli.sign_only,
+            "--sign-only needs --token-program for a token designation"
+        );
+        let account = self
+            .rpc
+            .get_account(mint)?
+            .with_context(|| format!("mint {mint} not found"))?;
+        Ok(account.owner)
+    }
+
+    fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        match self.rpc.get_account(address)? {
+            Some(account) => Ok(Some(
+                state::deserialize(&account.data).with_context(|| format!("cannot read account {address}"))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Custody mode of a token designation, from `--custody` or read from the designation
+    fn custody(
+        &self,
+        owner: &Pubkey,
+        heir: &Pubkey,
+        mint: &Pubkey,
+        designation: &DesignationArgs,
+    ) -> Result<TokenCustody> {
+        if let Some(custody) = designation.custody {
+            return Ok(custody.into());
+        }
+        ensure!(
+            !self.cli.sign_only,
+            "--sign-only needs --custody for a token designation"
+        );
+        let address = pda::token_heir(owner, heir, mint, designation.tranche);
+        let token_heir: TokenHeir = self
+            .fetch(&address)?
+            .with_context(|| format!("no token designation at {address}"))?;
+        Ok(token_heir.custody)
+    }
+
+    fn claim(&self, owner: &Pubkey, designation: &DesignationArgs, finalize: bool) -> Result<()> {
+        let me = &self.me;
+        let DesignationArgs {
+            mint,
+            tranche,
+            token_program,
+            ..
+        } = designation;
+        let instruction = match (mint, finalize) {
+            (None, false) => instructions::request_sol_claim(owner, me, *tranche),
+            (None, true) => instructions::finalize_sol_claim(owner, me, *tranche),
+            (Some(mint), false) => instructions::request_token_claim(owner, me, mint, *tranche),
+            (Some(mint), true) => {
+                let token_program = self.token_program(mint, token_program)?;
+                match self.custody(owner, me, mint, designation)? {
+                    TokenCustody::Escrow => {
+                        instructions::finalize_token_claim(owner, me, mint, &token_program, *tranche)
+                    }
+                    TokenCustody::Delegate => {
+                        instructions::finalize_delegated_token_claim(owner, me, mint, &token_program, *tranche)
+                    }
+                    TokenCustody::Share => {
+                        instructions::finalize_token_share_claim(owner, me, mint, &token_program, *tranche)
+                    }
+                }
+            }
+        };
+        self.execute(&[instruction])
+    }
+
+    fn cancel(&self, heir: &Pubkey, designation: &DesignationArgs) -> Result<()> {
+        let me = &self.me;
+        let DesignationArgs {
+            mint,
+            tranche,
+            token_program,
+            ..
+        } = designation;
+        let Some(mint) = mint else {
+            return self.execute(&[instructions::cancel_sol_heir(me, heir, *tranche)]);
+        };
+        let token_program = self.token_program(mint, token_program)?;
+        let instruction = match self.custody(me, heir, mint, designation)? {
+            TokenCustody::Escrow => instructions::cancel_token_heir(me, heir, mint, &token_program, *tranche),
+            TokenCustody::Delegate => {
+                instructions::cancel_delegated_token_heir(me, heir, mint, &token_program, *tranche)
+            }
+            TokenCustody::Share => instructions::cancel_token_share_heir(me, heir, mint, &token_program, *tranche),
+        };
+        self.execute(&[instruction])
+    }
+
+    fn status(&self, owner: &Pubkey) -> Result<()> {
+        let address = pda::user_profile(owner);
+        let Some(profile) = self.fetch::<UserProfile>(&address)? else {
+            bail!("{owner} has no estate profile; create one with `gado init`");
+        };
+        let now = self.rpc.get_clock()?.unix_timestamp;
+
+        println!("Estate profile {address}");
+        println!("  Owner:             {}", profile.owner);
+        println!("  Designations:      {}", profile.total_inheritances);
+        println!("  Last activity:     {}", format_time(profile.last_activity, now));
+        println!(
+            "  Claim notice:      {}",
+            format_duration(profile.claim_notice_period())
+        );
+        println!("  Crank bounty:      {} bps", profile.crank_bounty_bps);
+        Ok(())
+    }
+
+    fn list(&self, as_heir: bool) -> Result<()> {
+        let offset = if as_heir { HEIR_OFFSET } else { OWNER_OFFSET };
+        let now = self.rpc.get_clock()?.unix_timestamp;
+        let mut profiles: Vec<(Pubkey, Option<UserProfile>)> = Vec::new();
+        let mut profile_of = |owner: Pubkey| -> Result<Option<UserProfile>> {
+            if let Some((_, profile)) = profiles.iter().find(|(key, _)| *key == owner) {
+                return Ok(profile.clone());
+            }
+            let profile = self.fetch::<UserProfile>(&pda::user_profile(&owner))?;
+            profiles.push((owner, profile.clone()));
+            Ok(profile)
+        };
+
+        let mut found = 0;
+        for (address, account) in self
+            .rpc
+            .get_program_accounts(&[Filter::account_type::<SolHeir>(), Filter::pubkey_at(offset, &self.me)])?
+        {
+            let Ok(sol_heir) = state::sol_heir(&account.data) else {
+                println!("SOL   {address}  (legacy layout; run `gado migrate-heir {address}`)");
+                continue;
+            };
+            found += 1;
+            println!("SOL   {address}");
+            println!("  Owner:   {}", sol_heir.owner);
+            println!("  Heir:    {}  (tranche {})", sol_heir.heir, sol_heir.tranche);
+            println!("  Amount:  {}", format_sol(sol_heir.amount));
+            print_timing(profile_of(sol_heir.owner)?.as_ref(), &sol_heir, now);
+        }
+        for (address, account) in self
+            .rpc
+            .get_program_accounts(&[Filter::account_type::<TokenHeir>(), Filter::pubkey_at(offset, &self.me)])?
+        {
+            let Ok(token_heir) = state::token_heir(&account.data) else {
+                println!("TOKEN {address}  (legacy layout; run `gado migrate-heir {address}`)");
+                continue;
+            };
+            found += 1;
+            println!("TOKEN {address}");
+            println!("  Owner:   {}", token_heir.owner);
+            println!("  Heir:    {}  (tranche {})", token_heir.heir, token_heir.tranche);
+            println!("  Mint:    {}", token_heir.token_mint);
+            match token_heir.custody {
+                TokenCustody::Escrow => {
+                    println!("  Amount:  {} base units in escrow", token_heir.amount)
+                }
+                TokenCustody::Delegate => {
+                    println!("  Amount:  {} base units by delegation", token_heir.amount)
+                }
+                TokenCustody::Share => println!(
+                    "  Amount:  {}.{:02}% of the balance",
+                    token_heir.share_bps / 100,
+                    token_heir.share_bps % 100
+                ),
+            }
+            print_timing(profile_of(token_heir.owner)?.as_ref(), &token_heir, now);
+        }
+
+        if found == 0 {
+            println!("No designations found");
+        }
+        Ok(())
+    }
+}
+
+/// Claim state of a designation, as of the cluster time `now`
+fn print_timing(profile: Option<&UserProfile>, designation: &impl Designation, now: i64) {
+    println!(
+        "  Period:  {}",
+        format_duration(designation.inactivity_period_seconds())
+    );
+    let Some(profile) = profile else {
+        println!("  Claim:   unknown (owner profile missing)");
+        return;
+    };
+    let claimable_at = timing::claimable_at(profile, designation);
+    if timing::claim_pending(profile, designation) {
+        println!(
+            "  Claim:   requested {}; finalizable {}",
+            format_time(designation.claim_requested_at(), now),
+            format_time(claimable_at, now)
+        );
+    } else {
+        println!(
+            "  Claim:   requestable {}; finalizable {} at the earliest",
+            format_time(timing::requestable_at(profile, designation), now),
+            format_time(claimable_at, now)
+        );
+    }
+}
@@ -0,0 +1,1124 @@
+//! One builder per program instruction. Builders derive every PDA and
+//! associated token account from the keys that identify the designation, and
+//! use the same account order as the program.
+//!
+//! Token builders take the mint's `token_program` (SPL Token or Token-2022).
+//! For a Token-2022 mint with a transfer hook, append the hook program, its
+//! extra-account-metas account and the accounts it lists to
+//! `Instruction::accounts` of any builder that moves the tokens.
+
+use anchor_lang::prelude::{Id, ProgramData};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, Owner, ToAccountMetas};
+use anchor_spl::associated_token;
+use anchor_spl::stake::Stake;
+use gado::{accounts, instruction as args, MintRiskPolicy};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: gado::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn with_remaining_accounts(mut ix: Instruction, remaining_accounts: impl IntoIterator<Item = AccountMeta>) -> Instruction {
+    ix.accounts.extend(remaining_accounts);
+    ix
+}
+
+/// `[mint, from, to]` per mint, the remaining-account layout of NFT bundles
+/// and token baskets. The designation's escrow is the `from` or `to` side.
+/// Mints are writable when `releasing` an escrow, which harvests its withheld
+/// Token-2022 transfer fees into the mint.
+fn mint_triples(
+    mints: &[Pubkey],
+    from: &Pubkey,
+    to: &Pubkey,
+    token_program: &Pubkey,
+    releasing: bool,
+) -> Vec<AccountMeta> {
+    mints
+        .iter()
+        .flat_map(|mint| {
+            [
+                AccountMeta { pubkey: *mint, is_signer: false, is_writable: releasing },
+                AccountMeta::new(pda::associated_token_account(from, mint, token_program), false),
+                AccountMeta::new(pda::associated_token_account(to, mint, token_program), false),
+            ]
+        })
+        .collect()
+}
+
+// ===============================================
+// OWNER: PROFILE AND DESIGNATIONS
+// ===============================================
+
+pub fn initialize_user(owner: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeUser {
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        args::InitializeUser {},
+    )
+}
+
+pub fn add_sol_heir(owner: &Pubkey, heir: &Pubkey, tranche: u16, amount: u64, inactivity_period_seconds: i64) -> Instruction {
+    let sol_heir = pda::sol_heir(owner, heir, tranche);
+    build(
+        accounts::AddSolHeir {
+            sol_heir,
+            sol_vault: pda::sol_vault(&sol_heir),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            system_program: system_program::ID,
+        },
+        args::AddSolHeir {
+            tranche,
+            amount,
+            inactivity_period_seconds,
+        },
+    )
+}
+
+/// Escrow `amount` of the owner's ATA balance for `heir`
+#[allow(clippy::too_many_arguments)]
+pub fn add_token_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    amount: u64,
+    inactivity_period_seconds: i64,
+    mint_risk_policy: MintRiskPolicy,
+) -> Instruction {
+    let token_heir = pda::token_heir(owner, heir, mint, tranche);
+    build(
+        accounts::AddTokenHeir {
+            token_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            token_mint: *mint,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            escrow_token_account: pda::associated_token_account(&token_heir, mint, token_program),
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::AddTokenHeir {
+            tranche,
+            amount,
+            inactivity_period_seconds,
+            mint_risk_policy,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_delegated_token_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    amount: u64,
+    inactivity_period_seconds: i64,
+    mint_risk_policy: MintRiskPolicy,
+) -> Instruction {
+    build(
+        accounts::AddDelegatedTokenHeir {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            token_mint: *mint,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        args::AddDelegatedTokenHeir {
+            tranche,
+            amount,
+            inactivity_period_seconds,
+            mint_risk_policy,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_token_share_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    share_bps: u16,
+    inactivity_period_seconds: i64,
+    mint_risk_policy: MintRiskPolicy,
+) -> Instruction {
+    build(
+        accounts::AddTokenShareHeir {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            token_estate: pda::token_estate(owner, mint),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            token_mint: *mint,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        args::AddTokenShareHeir {
+            tranche,
+            share_bps,
+            inactivity_period_seconds,
+            mint_risk_policy,
+        },
+    )
+}
+
+/// Bundle the NFTs of `mints`, each held in the owner's ATA
+pub fn add_nft_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    mints: &[Pubkey],
+    inactivity_period_seconds: i64,
+) -> Instruction {
+    let nft_heir = pda::nft_heir(owner, heir, tranche);
+    let ix = build(
+        accounts::AddNftHeir {
+            nft_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::AddNftHeir {
+            tranche,
+            inactivity_period_seconds,
+        },
+    );
+    with_remaining_accounts(ix, mint_triples(mints, owner, &nft_heir, token_program, false))
+}
+
+/// Escrow `(mint, amount)` per entry from the owner's ATAs
+pub fn add_token_basket_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    entries: &[(Pubkey, u64)],
+    inactivity_period_seconds: i64,
+    mint_risk_policy: MintRiskPolicy,
+) -> Instruction {
+    let token_basket_heir = pda::token_basket_heir(owner, heir, tranche);
+    let (mints, amounts): (Vec<Pubkey>, Vec<u64>) = entries.iter().copied().unzip();
+    let ix = build(
+        accounts::AddTokenBasketHeir {
+            token_basket_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::AddTokenBasketHeir {
+            tranche,
+            amounts,
+            inactivity_period_seconds,
+            mint_risk_policy,
+        },
+    );
+    with_remaining_accounts(ix, mint_triples(&mints, owner, &token_basket_heir, token_program, false))
+}
+
+pub fn add_stake_heir(owner: &Pubkey, heir: &Pubkey, stake_account: &Pubkey, inactivity_period_seconds: i64) -> Instruction {
+    build(
+        accounts::AddStakeHeir {
+            stake_heir: pda::stake_heir(owner, heir, stake_account),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            stake_account: *stake_account,
+            clock: sysvar::clock::ID,
+            stake_program: Stake::id(),
+            system_program: system_program::ID,
+        },
+        args::AddStakeHeir {
+            inactivity_period_seconds,
+        },
+    )
+}
+
+pub fn add_program_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    upgradeable_program: &Pubkey,
+    inactivity_period_seconds: i64,
+) -> Instruction {
+    build(
+        accounts::AddProgramHeir {
+            program_heir: pda::program_heir(owner, heir, upgradeable_program),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            heir: *heir,
+            upgradeable_program: *upgradeable_program,
+            program_data: pda::program_data(upgradeable_program),
+            upgradeable_loader: ProgramData::owner(),
+            system_program: system_program::ID,
+        },
+        args::AddProgramHeir {
+            inactivity_period_seconds,
+        },
+    )
+}
+
+// ===============================================
+// OWNER: ACTIVITY AND SETTINGS
+// ===============================================
+
+fn heartbeat_accounts(owner: &Pubkey) -> accounts::Heartbeat {
+    accounts::Heartbeat {
+        user_profile: pda::user_profile(owner),
+        owner: *owner,
+    }
+}
+
+pub fn heartbeat(owner: &Pubkey) -> Instruction {
+    build(heartbeat_accounts(owner), args::Heartbeat {})
+}
+
+pub fn register_liveness_key(owner: &Pubkey, key: &Pubkey, expires_at: i64) -> Instruction {
+    build(
+        accounts::RegisterLivenessKey {
+            liveness_key: pda::liveness_key(owner, key),
+            key: *key,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        args::RegisterLivenessKey { expires_at },
+    )
+}
+
+pub fn revoke_liveness_key(owner: &Pubkey, key: &Pubkey) -> Instruction {
+    build(
+        accounts::RevokeLivenessKey {
+            liveness_key: pda::liveness_key(owner, key),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+        },
+        args::RevokeLivenessKey {},
+    )
+}
+
+/// Heartbeat signed by `key`, a liveness key `owner` registered
+pub fn delegated_heartbeat(owner: &Pubkey, key: &Pubkey) -> Instruction {
+    build(
+        accounts::DelegatedHeartbeat {
+            liveness_key: pda::liveness_key(owner, key),
+            user_profile: pda::user_profile(owner),
+            key: *key,
+        },
+        args::DelegatedHeartbeat {},
+    )
+}
+
+pub fn set_claim_notice_period(owner: &Pubkey, notice_period_seconds: i64) -> Instruction {
+    build(
+        heartbeat_accounts(owner),
+        args::SetClaimNoticePeriod { notice_period_seconds },
+    )
+}
+
+pub fn set_crank_bounty(owner: &Pubkey, bounty_bps: u16) -> Instruction {
+    build(heartbeat_accounts(owner), args::SetCrankBounty { bounty_bps })
+}
+
+pub fn set_guardians(owner: &Pubkey, guardians: Vec<Pubkey>, threshold: u8) -> Instruction {
+    build(
+        accounts::SetGuardians {
+            guardian_set: pda::guardian_set(owner),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        args::SetGuardians { guardians, threshold },
+    )
+}
+
+pub fn update_sol_activity(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    build(
+        accounts::UpdateSolActivity {
+            sol_heir: pda::sol_heir(owner, heir, tranche),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+        },
+        args::UpdateSolActivity {},
+    )
+}
+
+pub fn update_token_activity(owner: &Pubkey, heir: &Pubkey, mint: &Pubkey, tranche: u16) -> Instruction {
+    build(
+        accounts::UpdateTokenActivity {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+        },
+        args::UpdateTokenActivity {},
+    )
+}
+
+fn update_sol_amount_accounts(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> accounts::UpdateSolAmount {
+    let sol_heir = pda::sol_heir(owner, heir, tranche);
+    accounts::UpdateSolAmount {
+        sol_heir,
+        sol_vault: pda::sol_vault(&sol_heir),
+        user_profile: pda::user_profile(owner),
+        owner: *owner,
+        system_program: system_program::ID,
+    }
+}
+
+pub fn increase_sol_amount(owner: &Pubkey, heir: &Pubkey, tranche: u16, amount: u64) -> Instruction {
+    build(
+        update_sol_amount_accounts(owner, heir, tranche),
+        args::IncreaseSolAmount { amount },
+    )
+}
+
+pub fn decrease_sol_amount(owner: &Pubkey, heir: &Pubkey, tranche: u16, amount: u64) -> Instruction {
+    build(
+        update_sol_amount_accounts(owner, heir, tranche),
+        args::DecreaseSolAmount { amount },
+    )
+}
+
+fn update_token_amount_accounts(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+) -> accounts::UpdateTokenAmount {
+    let token_heir = pda::token_heir(owner, heir, mint, tranche);
+    accounts::UpdateTokenAmount {
+        token_heir,
+        user_profile: pda::user_profile(owner),
+        owner: *owner,
+        token_mint: *mint,
+        owner_token_account: pda::associated_token_account(owner, mint, token_program),
+        escrow_token_account: pda::associated_token_account(&token_heir, mint, token_program),
+        token_program: *token_program,
+    }
+}
+
+pub fn increase_token_amount(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    amount: u64,
+) -> Instruction {
+    build(
+        update_token_amount_accounts(owner, heir, mint, token_program, tranche),
+        args::IncreaseTokenAmount { amount },
+    )
+}
+
+pub fn decrease_token_amount(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    amount: u64,
+) -> Instruction {
+    build(
+        update_token_amount_accounts(owner, heir, mint, token_program, tranche),
+        args::DecreaseTokenAmount { amount },
+    )
+}
+
+// ===============================================
+// GUARDIANS AND MIGRATIONS
+// ===============================================
+
+fn guardian_action(owner: &Pubkey, guardians: &[Pubkey], data: impl InstructionData) -> Instruction {
+    let ix = build(
+        accounts::GuardianAction {
+            guardian_set: pda::guardian_set(owner),
+        },
+        data,
+    );
+    with_remaining_accounts(ix, guardians.iter().map(|guardian| AccountMeta::new_readonly(*guardian, true)))
+}
+
+/// Attest `owner`'s death; every key in `guardians` signs
+pub fn attest_deceased(owner: &Pubkey, guardians: &[Pubkey]) -> Instruction {
+    guardian_action(owner, guardians, args::AttestDeceased {})
+}
+
+pub fn freeze_claims(owner: &Pubkey, guardians: &[Pubkey]) -> Instruction {
+    guardian_action(owner, guardians, args::FreezeClaims {})
+}
+
+pub fn unfreeze_claims(owner: &Pubkey, guardians: &[Pubkey]) -> Instruction {
+    guardian_action(owner, guardians, args::UnfreezeClaims {})
+}
+
+/// `heir_accounts` are the owner's `SolHeir`/`TokenHeir` accounts whose legacy
+/// per-heir heartbeats seed the profile's `last_activity`
+pub fn migrate_user_profile(owner: &Pubkey, payer: &Pubkey, heir_accounts: &[Pubkey]) -> Instruction {
+    let ix = build(
+        accounts::MigrateUserProfile {
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        args::MigrateUserProfile {},
+    );
+    with_remaining_accounts(ix, heir_accounts.iter().map(|account| AccountMeta::new_readonly(*account, false)))
+}
+
+/// The vault is only used by legacy SOL designations, which move their escrow into it
+pub fn migrate_heir_account(heir_account: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateHeirAccount {
+            heir_account: *heir_account,
+            sol_vault: pda::sol_vault(heir_account),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        args::MigrateHeirAccount {},
+    )
+}
+
+// ===============================================
+// HEIR: CLAIMS
+// ===============================================
+
+pub fn request_sol_claim(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    build(
+        accounts::RequestSolClaim {
+            sol_heir: pda::sol_heir(owner, heir, tranche),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            heir: *heir,
+        },
+        args::RequestSolClaim {},
+    )
+}
+
+pub fn finalize_sol_claim(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    let sol_heir = pda::sol_heir(owner, heir, tranche);
+    build(
+        accounts::FinalizeSolClaim {
+            sol_heir,
+            sol_vault: pda::sol_vault(&sol_heir),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            heir: *heir,
+            system_program: system_program::ID,
+        },
+        args::FinalizeSolClaim {},
+    )
+}
+
+/// Request a claim on a token designation of any custody mode
+pub fn request_token_claim(owner: &Pubkey, heir: &Pubkey, mint: &Pubkey, tranche: u16) -> Instruction {
+    build(
+        accounts::RequestTokenClaim {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            heir: *heir,
+        },
+        args::RequestTokenClaim {},
+    )
+}
+
+pub fn finalize_token_claim(owner: &Pubkey, heir: &Pubkey, mint: &Pubkey, token_program: &Pubkey, tranche: u16) -> Instruction {
+    let token_heir = pda::token_heir(owner, heir, mint, tranche);
+    build(
+        accounts::FinalizeTokenClaim {
+            token_heir,
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            escrow_token_account: pda::associated_token_account(&token_heir, mint, token_program),
+            heir_token_account: pda::associated_token_account(heir, mint, token_program),
+            token_mint: *mint,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::FinalizeTokenClaim {},
+    )
+}
+
+pub fn finalize_delegated_token_claim(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+) -> Instruction {
+    build(
+        accounts::FinalizeDelegatedTokenClaim {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            heir_token_account: pda::associated_token_account(heir, mint, token_program),
+            token_mint: *mint,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::FinalizeDelegatedTokenClaim {},
+    )
+}
+
+pub fn finalize_token_share_claim(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+) -> Instruction {
+    build(
+        accounts::FinalizeTokenShareClaim {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            token_estate: pda::token_estate(owner, mint),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            heir_token_account: pda::associated_token_account(heir, mint, token_program),
+            token_mint: *mint,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::FinalizeTokenShareClaim {},
+    )
+}
+
+pub fn request_nft_claim(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    build(
+        accounts::RequestNftClaim {
+            nft_heir: pda::nft_heir(owner, heir, tranche),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            heir: *heir,
+        },
+        args::RequestNftClaim {},
+    )
+}
+
+/// `mints` in the order the bundle lists them (`NftHeir::mints`)
+pub fn finalize_nft_claim(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    mints: &[Pubkey],
+) -> Instruction {
+    let nft_heir = pda::nft_heir(owner, heir, tranche);
+    let ix = build(
+        accounts::FinalizeNftClaim {
+            nft_heir,
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::FinalizeNftClaim {},
+    );
+    with_remaining_accounts(ix, mint_triples(mints, &nft_heir, heir, token_program, true))
+}
+
+pub fn request_token_basket_claim(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    build(
+        accounts::RequestTokenBasketClaim {
+            token_basket_heir: pda::token_basket_heir(owner, heir, tranche),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            heir: *heir,
+        },
+        args::RequestTokenBasketClaim {},
+    )
+}
+
+/// `mints` in the basket's order (`TokenBasketHeir::entries`)
+pub fn finalize_token_basket_claim(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    mints: &[Pubkey],
+) -> Instruction {
+    let token_basket_heir = pda::token_basket_heir(owner, heir, tranche);
+    let ix = build(
+        accounts::FinalizeTokenBasketClaim {
+            token_basket_heir,
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            heir: *heir,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::FinalizeTokenBasketClaim {},
+    );
+    with_remaining_accounts(ix, mint_triples(mints, &token_basket_heir, heir, token_program, true))
+}
+
+pub fn request_stake_claim(owner: &Pubkey, heir: &Pubkey, stake_account: &Pubkey) -> Instruction {
+    build(
+        accounts::RequestStakeClaim {
+            stake_heir: pda::stake_heir(owner, heir, stake_account),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            stake_account: *stake_account,
+            heir: *heir,
+        },
+        args::RequestStakeClaim {},
+    )
+}
+
+pub fn finalize_stake_claim(owner: &Pubkey, heir: &Pubkey, stake_account: &Pubkey) -> Instruction {
+    build(
+        accounts::FinalizeStakeClaim {
+            stake_heir: pda::stake_heir(owner, heir, stake_account),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            stake_account: *stake_account,
+            heir: *heir,
+            clock: sysvar::clock::ID,
+            stake_program: Stake::id(),
+        },
+        args::FinalizeStakeClaim {},
+    )
+}
+
+pub fn request_program_claim(owner: &Pubkey, heir: &Pubkey, upgradeable_program: &Pubkey) -> Instruction {
+    build(
+        accounts::RequestProgramClaim {
+            program_heir: pda::program_heir(owner, heir, upgradeable_program),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            heir: *heir,
+        },
+        args::RequestProgramClaim {},
+    )
+}
+
+pub fn finalize_program_claim(owner: &Pubkey, heir: &Pubkey, upgradeable_program: &Pubkey) -> Instruction {
+    build(
+        accounts::FinalizeProgramClaim {
+            program_heir: pda::program_heir(owner, heir, upgradeable_program),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            upgradeable_program: *upgradeable_program,
+            program_data: pda::program_data(upgradeable_program),
+            heir: *heir,
+            upgradeable_loader: ProgramData::owner(),
+        },
+        args::FinalizeProgramClaim {},
+    )
+}
+
+// ===============================================
+// ANYONE: CRANKS AND CLEANUP
+// ===============================================
+
+/// Request or finalize a SOL claim on the heir's behalf, signed by `cranker`
+pub fn crank_sol_claim(owner: &Pubkey, heir: &Pubkey, tranche: u16, cranker: &Pubkey) -> Instruction {
+    let sol_heir = pda::sol_heir(owner, heir, tranche);
+    build(
+        accounts::CrankSolClaim {
+            sol_heir,
+            sol_vault: pda::sol_vault(&sol_heir),
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            heir: *heir,
+            cranker: *cranker,
+            system_program: system_program::ID,
+        },
+        args::CrankSolClaim {},
+    )
+}
+
+/// Request or finalize an escrowed token claim on the heir's behalf, signed by `cranker`
+pub fn crank_token_claim(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    cranker: &Pubkey,
+) -> Instruction {
+    let token_heir = pda::token_heir(owner, heir, mint, tranche);
+    build(
+        accounts::CrankTokenClaim {
+            token_heir,
+            user_profile: pda::user_profile(owner),
+            guardian_set: pda::guardian_set(owner),
+            owner: *owner,
+            escrow_token_account: pda::associated_token_account(&token_heir, mint, token_program),
+            heir_token_account: pda::associated_token_account(heir, mint, token_program),
+            cranker_token_account: pda::associated_token_account(cranker, mint, token_program),
+            token_mint: *mint,
+            heir: *heir,
+            cranker: *cranker,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args::CrankTokenClaim {},
+    )
+}
+
+pub fn close_claimed_sol_heir(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    let sol_heir = pda::sol_heir(owner, heir, tranche);
+    build(
+        accounts::CloseClaimedSolHeir {
+            sol_heir,
+            sol_vault: pda::sol_vault(&sol_heir),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        args::CloseClaimedSolHeir {},
+    )
+}
+
+pub fn close_claimed_token_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+) -> Instruction {
+    let token_heir = pda::token_heir(owner, heir, mint, tranche);
+    build(
+        accounts::CloseClaimedTokenHeir {
+            token_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            escrow_token_account: pda::associated_token_account(&token_heir, mint, token_program),
+            token_program: *token_program,
+        },
+        args::CloseClaimedTokenHeir {},
+    )
+}
+
+// ===============================================
+// OWNER: CANCELLATIONS
+// ===============================================
+
+pub fn cancel_sol_heir(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Instruction {
+    let sol_heir = pda::sol_heir(owner, heir, tranche);
+    build(
+        accounts::CancelSolHeir {
+            sol_heir,
+            sol_vault: pda::sol_vault(&sol_heir),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        args::CancelSolHeir {},
+    )
+}
+
+pub fn cancel_token_heir(owner: &Pubkey, heir: &Pubkey, mint: &Pubkey, token_program: &Pubkey, tranche: u16) -> Instruction {
+    let token_heir = pda::token_heir(owner, heir, mint, tranche);
+    build(
+        accounts::CancelTokenHeir {
+            token_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            token_mint: *mint,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            escrow_token_account: pda::associated_token_account(&token_heir, mint, token_program),
+            token_program: *token_program,
+        },
+        args::CancelTokenHeir {},
+    )
+}
+
+pub fn cancel_delegated_token_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+) -> Instruction {
+    build(
+        accounts::CancelDelegatedTokenHeir {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            token_mint: *mint,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            token_program: *token_program,
+        },
+        args::CancelDelegatedTokenHeir {},
+    )
+}
+
+pub fn cancel_token_share_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+) -> Instruction {
+    build(
+        accounts::CancelTokenShareHeir {
+            token_heir: pda::token_heir(owner, heir, mint, tranche),
+            token_estate: pda::token_estate(owner, mint),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            token_mint: *mint,
+            owner_token_account: pda::associated_token_account(owner, mint, token_program),
+            token_program: *token_program,
+        },
+        args::CancelTokenShareHeir {},
+    )
+}
+
+/// `mints` in the order the bundle lists them (`NftHeir::mints`)
+pub fn cancel_nft_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    mints: &[Pubkey],
+) -> Instruction {
+    let nft_heir = pda::nft_heir(owner, heir, tranche);
+    let ix = build(
+        accounts::CancelNftHeir {
+            nft_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            token_program: *token_program,
+        },
+        args::CancelNftHeir {},
+    );
+    with_remaining_accounts(ix, mint_triples(mints, &nft_heir, owner, token_program, true))
+}
+
+/// `mints` in the basket's order (`TokenBasketHeir::entries`)
+pub fn cancel_token_basket_heir(
+    owner: &Pubkey,
+    heir: &Pubkey,
+    token_program: &Pubkey,
+    tranche: u16,
+    mints: &[Pubkey],
+) -> Instruction {
+    let token_basket_heir = pda::token_basket_heir(owner, heir, tranche);
+    let ix = build(
+        accounts::CancelTokenBasketHeir {
+            token_basket_heir,
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            token_program: *token_program,
+        },
+        args::CancelTokenBasketHeir {},
+    );
+    with_remaining_accounts(ix, mint_triples(mints, &token_basket_heir, owner, token_program, true))
+}
+
+pub fn cancel_stake_heir(owner: &Pubkey, heir: &Pubkey, stake_account: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelStakeHeir {
+            stake_heir: pda::stake_heir(owner, heir, stake_account),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            stake_account: *stake_account,
+            clock: sysvar::clock::ID,
+            stake_program: Stake::id(),
+        },
+        args::CancelStakeHeir {},
+    )
+}
+
+pub fn cancel_program_heir(owner: &Pubkey, heir: &Pubkey, upgradeable_program: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelProgramHeir {
+            program_heir: pda::program_heir(owner, heir, upgradeable_program),
+            user_profile: pda::user_profile(owner),
+            owner: *owner,
+            upgradeable_program: *upgradeable_program,
+            program_data: pda::program_data(upgradeable_program),
+            upgradeable_loader: ProgramData::owner(),
+        },
+        args::CancelProgramHeir {},
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+    use anchor_spl::token;
+
+    fn keys() -> (Pubkey, Pubkey, Pubkey) {
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique())
+    }
+
+    fn signers(ix: &Instruction) -> Vec<Pubkey> {
+        ix.accounts.iter().filter(|meta| meta.is_signer).map(|meta| meta.pubkey).collect()
+    }
+
+    #[test]
+    fn encodes_discriminator_and_arguments() {
+        let (owner, heir, _) = keys();
+        let ix = add_sol_heir(&owner, &heir, 2, 5_000, 86_400);
+
+        assert_eq!(ix.program_id, gado::ID);
+        assert_eq!(&ix.data[..8], args::AddSolHeir::DISCRIMINATOR);
+        let decoded = args::AddSolHeir::deserialize(&mut &ix.data[8..]).unwrap();
+        assert_eq!(decoded.tranche, 2);
+        assert_eq!(decoded.amount, 5_000);
+        assert_eq!(decoded.inactivity_period_seconds, 86_400);
+    }
+
+    #[test]
+    fn derives_designation_accounts() {
+        let (owner, heir, _) = keys();
+        let ix = add_sol_heir(&owner, &heir, 2, 5_000, 86_400);
+        let sol_heir = pda::sol_heir(&owner, &heir, 2);
+
+        let expected = [
+            AccountMeta::new(sol_heir, false),
+            AccountMeta::new(pda::sol_vault(&sol_heir), false),
+            AccountMeta::new(pda::user_profile(&owner), false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new_readonly(heir, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        assert_eq!(ix.accounts, expected);
+    }
+
+    #[test]
+    fn escrows_are_the_designation_ata() {
+        let (owner, heir, mint) = keys();
+        let ix = add_token_heir(&owner, &heir, &mint, &token::ID, 0, 10, 86_400, MintRiskPolicy::Reject);
+        let token_heir = pda::token_heir(&owner, &heir, &mint, 0);
+
+        assert_eq!(ix.accounts[0].pubkey, token_heir);
+        assert_eq!(ix.accounts[5].pubkey, pda::associated_token_account(&owner, &mint, &token::ID));
+        assert_eq!(ix.accounts[6].pubkey, pda::associated_token_account(&token_heir, &mint, &token::ID));
+        assert_eq!(ix.accounts[7].pubkey, token::ID);
+    }
+
+    #[test]
+    fn claims_are_signed_by_the_heir_and_cranks_by_the_cranker() {
+        let (owner, heir, mint) = keys();
+        let cranker = Pubkey::new_unique();
+
+        assert_eq!(signers(&request_sol_claim(&owner, &heir, 0)), [heir]);
+        assert_eq!(signers(&finalize_token_claim(&owner, &heir, &mint, &token::ID, 0)), [heir]);
+        assert_eq!(signers(&crank_sol_claim(&owner, &heir, 0, &cranker)), [cranker]);
+        assert_eq!(signers(&crank_token_claim(&owner, &heir, &mint, &token::ID, 0, &cranker)), [cranker]);
+        assert_eq!(signers(&cancel_sol_heir(&owner, &heir, 0)), [owner]);
+    }
+
+    #[test]
+    fn escrow_releases_write_to_the_mint() {
+        let (owner, heir, mint) = keys();
+        let writes_mint = |ix: Instruction| ix.accounts.iter().any(|meta| meta.pubkey == mint && meta.is_writable);
+
+        // Closing an escrow harvests its withheld Token-2022 fees into the mint
+        let token_2022 = anchor_spl::token_2022::ID;
+        assert!(writes_mint(finalize_token_claim(&owner, &heir, &mint, &token_2022, 0)));
+        assert!(writes_mint(crank_token_claim(&owner, &heir, &mint, &token_2022, 0, &Pubkey::new_unique())));
+        assert!(writes_mint(cancel_token_heir(&owner, &heir, &mint, &token_2022, 0)));
+        let add = add_token_heir(&owner, &heir, &mint, &token_2022, 0, 1, 86_400, MintRiskPolicy::Reject);
+        assert!(!writes_mint(add));
+    }
+
+    #[test]
+    fn lists_one_triple_per_nft() {
+        let (owner, heir, _) = keys();
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let nft_heir = pda::nft_heir(&owner, &heir, 0);
+
+        let add = add_nft_heir(&owner, &heir, &token::ID, 0, &mints, 86_400);
+        let remaining = &add.accounts[7..];
+        assert_eq!(remaining.len(), 6);
+        assert_eq!(remaining[3], AccountMeta::new_readonly(mints[1], false));
+        assert_eq!(remaining[4].pubkey, pda::associated_token_account(&owner, &mints[1], &token::ID));
+        assert_eq!(remaining[5].pubkey, pda::associated_token_account(&nft_heir, &mints[1], &token::ID));
+
+        // Claims move each NFT from the escrow to the heir
+        let finalize = finalize_nft_claim(&owner, &heir, &token::ID, 0, &mints);
+        let remaining = &finalize.accounts[8..];
+        assert_eq!(remaining[0], AccountMeta::new(mints[0], false));
+        assert_eq!(remaining[1].pubkey, pda::associated_token_account(&nft_heir, &mints[0], &token::ID));
+        assert_eq!(remaining[2].pubkey, pda::associated_token_account(&heir, &mints[0], &token::ID));
+    }
+
+    #[test]
+    fn passes_basket_amounts_in_entry_order() {
+        let (owner, heir, _) = keys();
+        let entries = [(Pubkey::new_unique(), 7), (Pubkey::new_unique(), 9)];
+        let ix = add_token_basket_heir(&owner, &heir, &token::ID, 1, &entries, 86_400, MintRiskPolicy::Allow);
+
+        let decoded = args::AddTokenBasketHeir::deserialize(&mut &ix.data[8..]).unwrap();
+        assert_eq!(decoded.amounts, [7, 9]);
+        assert_eq!(ix.accounts[7].pubkey, entries[0].0);
+        assert_eq!(ix.accounts[10].pubkey, entries[1].0);
+    }
+
+    #[test]
+    fn guardians_sign_as_remaining_accounts() {
+        let (owner, first, second) = keys();
+        let ix = attest_deceased(&owner, &[first, second]);
+
+        assert_eq!(ix.accounts[0], AccountMeta::new(pda::guardian_set(&owner), false));
+        assert_eq!(signers(&ix), [first, second]);
+        assert!(ix.accounts[1..].iter().all(|meta| !meta.is_writable));
+    }
+
+    #[test]
+    fn program_designations_pass_the_program_data() {
+        let (owner, heir, program) = keys();
+        let ix = finalize_program_claim(&owner, &heir, &program);
+
+        assert_eq!(ix.accounts[5], AccountMeta::new(pda::program_data(&program), false));
+        assert_eq!(ix.accounts[7].pubkey, ProgramData::owner());
+    }
+}
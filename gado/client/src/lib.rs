@@ -0,0 +1,27 @@
+//! Rust client for the `gado` program.
+//!
+//! - [`pda`] derives every account address the program uses.
+//! - [`instructions`] builds one [`Instruction`] per program instruction,
+//!   deriving the accounts it needs.
+//! - [`state`] deserializes program accounts.
+//! - [`timing`] computes when a designation's claim opens.
+//! - `rpc` (behind the `rpc` feature) is a small blocking JSON-RPC client.
+//!
+//! Without the `rpc` feature nothing here talks to the network; pair it with
+//! any RPC client.
+
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod state;
+pub mod timing;
+
+pub use anchor_lang::solana_program::hash::Hash;
+pub use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+pub use anchor_lang::solana_program::pubkey::Pubkey;
+pub use anchor_lang::AccountDeserialize;
+pub use gado::{
+    BasketEntry, DesignationKind, GuardianSet, LivenessKey, MintRiskPolicy, NftHeir, ProgramHeir, SolHeir, StakeHeir,
+    TokenBasketHeir, TokenCustody, TokenEstate, TokenHeir, UserProfile, ID,
+};
@@ -0,0 +1,134 @@
+//! Program-derived addresses, mirroring the seeds in the program's account
+//! constraints. Tranche 0 keeps the address designations had before tranches
+//! existed.
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use gado::tranche_seed;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &gado::ID).0
+}
+
+pub fn user_profile(owner: &Pubkey) -> Pubkey {
+    find(&[b"user_profile", owner.as_ref()])
+}
+
+pub fn guardian_set(owner: &Pubkey) -> Pubkey {
+    find(&[b"guardian_set", owner.as_ref()])
+}
+
+pub fn liveness_key(owner: &Pubkey, key: &Pubkey) -> Pubkey {
+    find(&[b"liveness_key", owner.as_ref(), key.as_ref()])
+}
+
+pub fn sol_heir(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Pubkey {
+    find(&[b"sol_heir", owner.as_ref(), heir.as_ref(), &tranche_seed(tranche)])
+}
+
+/// Data-less vault holding a SOL designation's lamports
+pub fn sol_vault(sol_heir: &Pubkey) -> Pubkey {
+    find(&[b"sol_vault", sol_heir.as_ref()])
+}
+
+/// Token designation of any custody mode (escrow, delegate or share)
+pub fn token_heir(owner: &Pubkey, heir: &Pubkey, mint: &Pubkey, tranche: u16) -> Pubkey {
+    find(&[b"token_heir", owner.as_ref(), heir.as_ref(), mint.as_ref(), &tranche_seed(tranche)])
+}
+
+/// Shared delegate for an owner's share designations of one mint
+pub fn token_estate(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"token_estate", owner.as_ref(), mint.as_ref()])
+}
+
+pub fn nft_heir(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Pubkey {
+    find(&[b"nft_heir", owner.as_ref(), heir.as_ref(), &tranche_seed(tranche)])
+}
+
+pub fn token_basket_heir(owner: &Pubkey, heir: &Pubkey, tranche: u16) -> Pubkey {
+    find(&[b"token_basket_heir", owner.as_ref(), heir.as_ref(), &tranche_seed(tranche)])
+}
+
+pub fn stake_heir(owner: &Pubkey, heir: &Pubkey, stake_account: &Pubkey) -> Pubkey {
+    find(&[b"stake_heir", owner.as_ref(), heir.as_ref(), stake_account.as_ref()])
+}
+
+pub fn program_heir(owner: &Pubkey, heir: &Pubkey, upgradeable_program: &Pubkey) -> Pubkey {
+    find(&[b"program_heir", owner.as_ref(), heir.as_ref(), upgradeable_program.as_ref()])
+}
+
+/// `authority`'s associated token account for `mint` under `token_program`.
+/// Escrows are the designation's ATA, so this also derives them.
+pub fn associated_token_account(authority: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(authority, mint, token_program)
+}
+
+/// Program data account of a BPF-upgradeable program
+pub fn program_data(upgradeable_program: &Pubkey) -> Pubkey {
+    solana_loader_v3_interface::get_program_data_address(upgradeable_program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tranche_zero_keeps_the_legacy_address() {
+        let owner = Pubkey::new_unique();
+        let heir = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let legacy = Pubkey::find_program_address(&[b"sol_heir", owner.as_ref(), heir.as_ref()], &gado::ID).0;
+        assert_eq!(sol_heir(&owner, &heir, 0), legacy);
+        assert_ne!(sol_heir(&owner, &heir, 1), legacy);
+
+        let legacy = Pubkey::find_program_address(
+            &[b"token_heir", owner.as_ref(), heir.as_ref(), mint.as_ref()],
+            &gado::ID,
+        )
+        .0;
+        assert_eq!(token_heir(&owner, &heir, &mint, 0), legacy);
+        assert_ne!(token_heir(&owner, &heir, &mint, 1), token_heir(&owner, &heir, &mint, 2));
+    }
+
+    #[test]
+    fn tranches_are_little_endian() {
+        let owner = Pubkey::new_unique();
+        let heir = Pubkey::new_unique();
+
+        let expected = Pubkey::find_program_address(
+            &[b"nft_heir", owner.as_ref(), heir.as_ref(), &[0x02, 0x01]],
+            &gado::ID,
+        )
+        .0;
+        assert_eq!(nft_heir(&owner, &heir, 0x0102), expected);
+    }
+
+    #[test]
+    fn addresses_are_distinct_per_seed_prefix() {
+        let owner = Pubkey::new_unique();
+        let heir = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let addresses = [
+            user_profile(&owner),
+            guardian_set(&owner),
+            liveness_key(&owner, &other),
+            sol_heir(&owner, &heir, 0),
+            sol_vault(&sol_heir(&owner, &heir, 0)),
+            token_heir(&owner, &heir, &other, 0),
+            token_estate(&owner, &other),
+            nft_heir(&owner, &heir, 0),
+            token_basket_heir(&owner, &heir, 0),
+            stake_heir(&owner, &heir, &other),
+            program_heir(&owner, &heir, &other),
+        ];
+        for (i, a) in addresses.iter().enumerate() {
+            assert!(!a.is_on_curve());
+            for b in &addresses[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert_ne!(user_profile(&owner), user_profile(&other));
+    }
+}
@@ -0,0 +1,327 @@
+//! Minimal blocking JSON-RPC client covering what the CLI and keeper need
+//! from a cluster. Enabled by the `rpc` feature.
+
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::Discriminator;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde_json::{json, Value};
+
+#[derive(Debug)]
+pub enum RpcError {
+    /// The request never got a JSON-RPC answer
+    Transport(String),
+    /// The node answered with a JSON-RPC error
+    Rpc { code: i64, message: String },
+    /// The answer did not have the expected shape
+    InvalidResponse(String),
+    /// A submitted transaction landed but failed
+    TransactionFailed { signature: String, err: String },
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcError::Transport(err) => write!(f, "RPC transport error: {err}"),
+            RpcError::Rpc { code, message } => write!(f, "RPC error {code}: {message}"),
+            RpcError::InvalidResponse(err) => write!(f, "invalid RPC response: {err}"),
+            RpcError::TransactionFailed { signature, err } => {
+                write!(f, "transaction {signature} failed: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+pub type Result<T> = std::result::Result<T, RpcError>;
+
+/// An account as returned by the node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// `getProgramAccounts` filter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    DataSize(usize),
+    Memcmp { offset: usize, bytes: Vec<u8> },
+}
+
+impl Filter {
+    /// Accounts of type `T`
+    pub fn account_type<T: Discriminator>() -> Self {
+        Filter::Memcmp {
+            offset: 0,
+            bytes: T::DISCRIMINATOR.to_vec(),
+        }
+    }
+
+    /// Accounts holding `key` at `offset`, such as `state::OWNER_OFFSET`
+    pub fn pubkey_at(offset: usize, key: &Pubkey) -> Self {
+        Filter::Memcmp {
+            offset,
+            bytes: key.to_bytes().to_vec(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Filter::DataSize(size) => json!({ "dataSize": size }),
+            Filter::Memcmp { offset, bytes } => json!({
+                "memcmp": { "offset": offset, "bytes": BASE64_STANDARD.encode(bytes), "encoding": "base64" }
+            }),
+        }
+    }
+}
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcClient {
+    /// Client for the node at `url`, reading at `confirmed` commitment
+    pub fn new(url: impl Into<String>) -> Self {
+        let config = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build();
+        Self {
+            url: url.into(),
+            agent: ureq::Agent::new_with_config(config),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response = self
+            .agent
+            .post(&self.url)
+            .send_json(&request)
+            .map_err(|err| RpcError::Transport(err.to_string()))?;
+        let body: Value = response
+            .body_mut()
+            .read_json()
+            .map_err(|err| RpcError::Transport(err.to_string()))?;
+        parse_response(body)
+    }
+
+    /// `None` if the account does not exist
+    pub fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => parse_account(value).map(Some),
+        }
+    }
+
+    /// Every account of the `gado` program matching all `filters`
+    pub fn get_program_accounts(&self, filters: &[Filter]) -> Result<Vec<(Pubkey, Account)>> {
+        let filters: Vec<Value> = filters.iter().map(Filter::to_json).collect();
+        let result = self.call(
+            "getProgramAccounts",
+            json!([gado::ID.to_string(), { "encoding": "base64", "commitment": "confirmed", "filters": filters }]),
+        )?;
+        let entries = result
+            .as_array()
+            .ok_or_else(|| RpcError::InvalidResponse("expected an array of accounts".into()))?;
+        entries
+            .iter()
+            .map(|entry| Ok((parse_pubkey(&entry["pubkey"])?, parse_account(&entry["account"])?)))
+            .collect()
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| RpcError::InvalidResponse("missing blockhash".into()))?;
+        Hash::from_str(blockhash).map_err(|err| RpcError::InvalidResponse(err.to_string()))
+    }
+
+    /// The cluster's `Clock` sysvar; its `unix_timestamp` is what the program
+    /// compares inactivity periods against
+    pub fn get_clock(&self) -> Result<Clock> {
+        let account = self
+            .get_account(&sysvar::clock::ID)?
+            .ok_or_else(|| RpcError::InvalidResponse("clock sysvar missing".into()))?;
+        parse_clock(&account.data)
+    }
+
+    /// Submit a serialized, signed transaction and return its signature
+    pub fn send_transaction(&self, transaction: &[u8]) -> Result<String> {
+        let result = self.call(
+            "sendTransaction",
+            json!([BASE64_STANDARD.encode(transaction), { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RpcError::InvalidResponse("expected a signature".into()))
+    }
+
+    /// Poll until `signature` is confirmed, failing if the transaction failed
+    /// or is still unconfirmed after `timeout`
+    pub fn confirm_transaction(&self, signature: &str, timeout: Duration) -> Result<()> {
+        let poll = Duration::from_millis(500);
+        let mut waited = Duration::ZERO;
+        loop {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(RpcError::TransactionFailed {
+                        signature: signature.to_string(),
+                        err: status["err"].to_string(),
+                    });
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(());
+                }
+            }
+            if waited >= timeout {
+                return Err(RpcError::Transport(format!(
+                    "transaction {signature} unconfirmed after {timeout:?}"
+                )));
+            }
+            thread::sleep(poll);
+            waited += poll;
+        }
+    }
+
+    /// `send_transaction` followed by `confirm_transaction`
+    pub fn send_and_confirm_transaction(&self, transaction: &[u8]) -> Result<String> {
+        let signature = self.send_transaction(transaction)?;
+        self.confirm_transaction(&signature, Duration::from_secs(60))?;
+        Ok(signature)
+    }
+}
+
+fn parse_response(mut body: Value) -> Result<Value> {
+    if let Some(error) = body.get("error") {
+        return Err(RpcError::Rpc {
+            code: error["code"].as_i64().unwrap_or_default(),
+            message: error["message"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+    match body.get_mut("result") {
+        Some(result) => Ok(result.take()),
+        None => Err(RpcError::InvalidResponse("neither result nor error".into())),
+    }
+}
+
+fn parse_pubkey(value: &Value) -> Result<Pubkey> {
+    value
+        .as_str()
+        .and_then(|key| Pubkey::from_str(key).ok())
+        .ok_or_else(|| RpcError::InvalidResponse(format!("invalid public key {value}")))
+}
+
+fn parse_account(value: &Value) -> Result<Account> {
+    let data = value["data"][0]
+        .as_str()
+        .and_then(|data| BASE64_STANDARD.decode(data).ok())
+        .ok_or_else(|| RpcError::InvalidResponse("account data is not base64".into()))?;
+    Ok(Account {
+        lamports: value["lamports"]
+            .as_u64()
+            .ok_or_else(|| RpcError::InvalidResponse("missing lamports".into()))?,
+        owner: parse_pubkey(&value["owner"])?,
+        data,
+    })
+}
+
+/// The clock sysvar is five little-endian 8-byte fields
+fn parse_clock(data: &[u8]) -> Result<Clock> {
+    let field = |index: usize| -> Result<[u8; 8]> {
+        data.get(index * 8..index * 8 + 8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| RpcError::InvalidResponse("clock sysvar too short".into()))
+    };
+    Ok(Clock {
+        slot: u64::from_le_bytes(field(0)?),
+        epoch_start_timestamp: i64::from_le_bytes(field(1)?),
+        epoch: u64::from_le_bytes(field(2)?),
+        leader_schedule_epoch: u64::from_le_bytes(field(3)?),
+        unix_timestamp: i64::from_le_bytes(field(4)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_filters() {
+        let filter = Filter::Memcmp {
+            offset: 8,
+            bytes: vec![1, 2, 3],
+        };
+        assert_eq!(
+            filter.to_json(),
+            json!({ "memcmp": { "offset": 8, "bytes": "AQID", "encoding": "base64" } })
+        );
+        assert_eq!(Filter::DataSize(42).to_json(), json!({ "dataSize": 42 }));
+    }
+
+    #[test]
+    fn surfaces_rpc_errors() {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "Invalid params" } });
+        match parse_response(body) {
+            Err(RpcError::Rpc { code, message }) => {
+                assert_eq!(code, -32602);
+                assert_eq!(message, "Invalid params");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_accounts() {
+        let owner = Pubkey::new_unique();
+        let value = json!({
+            "lamports": 1_000,
+            "owner": owner.to_string(),
+            "data": [BASE64_STANDARD.encode([7, 8, 9]), "base64"],
+            "executable": false,
+        });
+        let account = parse_account(&value).unwrap();
+        assert_eq!(
+            account,
+            Account {
+                lamports: 1_000,
+                owner,
+                data: vec![7, 8, 9]
+            }
+        );
+    }
+
+    #[test]
+    fn parses_the_clock_sysvar() {
+        let data: Vec<u8> = [5u64, 6, 7, 8, 1_700_000_000]
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect();
+        let clock = parse_clock(&data).unwrap();
+        assert_eq!(clock.slot, 5);
+        assert_eq!(clock.unix_timestamp, 1_700_000_000);
+        assert!(parse_clock(&data[..39]).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Deserializers for program accounts, from raw account data as returned by
+//! `getAccountInfo`. The discriminator is checked, so passing the wrong kind
+//! of account fails. Accounts still on a layout older than the program's fail
+//! too until `migrate_user_profile` or `migrate_heir_account` grows them.
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::{AccountDeserialize, Result};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::{self, state::Mint};
+use gado::{SolHeir, TokenHeir, UserProfile};
+
+/// Offset of `owner` in every designation account, after the discriminator
+pub const OWNER_OFFSET: usize = 8;
+/// Offset of `heir` in every designation account
+pub const HEIR_OFFSET: usize = OWNER_OFFSET + 32;
+/// Offset of `is_claimed` in a `SolHeir`, after amount, period and last activity
+pub const SOL_HEIR_IS_CLAIMED_OFFSET: usize = HEIR_OFFSET + 32 + 8 + 8 + 8;
+/// Offset of `is_claimed` in a `TokenHeir`, after mint, amount, period and last activity
+pub const TOKEN_HEIR_IS_CLAIMED_OFFSET: usize = HEIR_OFFSET + 32 + 32 + 8 + 8 + 8;
+
+/// Deserialize any `gado` account type
+pub fn deserialize<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn user_profile(data: &[u8]) -> Result<UserProfile> {
+    deserialize(data)
+}
+
+pub fn sol_heir(data: &[u8]) -> Result<SolHeir> {
+    deserialize(data)
+}
+
+pub fn token_heir(data: &[u8]) -> Result<TokenHeir> {
+    deserialize(data)
+}
+
+/// Whether a mint owned by `token_program` names a transfer hook program.
+/// Moving its tokens needs the hook's extra accounts, which bundles, baskets
+/// and cranks do not pass.
+pub fn mint_has_transfer_hook(token_program: &Pubkey, data: &[u8]) -> bool {
+    if *token_program != spl_token_2022::ID {
+        return false;
+    }
+    StateWithExtensions::<Mint>::unpack(data)
+        .and_then(|mint| mint.get_extension::<TransferHook>().copied())
+        .is_ok_and(|hook| Option::<Pubkey>::from(hook.program_id).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use gado::TokenCustody;
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn sample_sol_heir() -> SolHeir {
+        SolHeir {
+            owner: Pubkey::new_unique(),
+            heir: Pubkey::new_unique(),
+            amount: 1_500_000_000,
+            inactivity_period_seconds: 86_400,
+            last_activity: 1_700_000_000,
+            is_claimed: false,
+            bump: 254,
+            tranche: 3,
+            claim_requested_at: 0,
+        }
+    }
+
+    #[test]
+    fn reads_a_user_profile() {
+        let profile = UserProfile {
+            owner: Pubkey::new_unique(),
+            total_inheritances: 4,
+            bump: 253,
+            last_activity: 1_700_000_000,
+            claim_notice_period_seconds: 7 * 86_400,
+            crank_bounty_bps: 50,
+        };
+        let data = serialize(&profile);
+        assert_eq!(data.len(), UserProfile::SPACE);
+
+        let read = user_profile(&data).unwrap();
+        assert_eq!(read.owner, profile.owner);
+        assert_eq!(read.total_inheritances, 4);
+        assert_eq!(read.last_activity, 1_700_000_000);
+        assert_eq!(read.claim_notice_period_seconds, 7 * 86_400);
+        assert_eq!(read.crank_bounty_bps, 50);
+    }
+
+    #[test]
+    fn reads_a_sol_heir() {
+        let designation = sample_sol_heir();
+        let read = sol_heir(&serialize(&designation)).unwrap();
+        assert_eq!(read.heir, designation.heir);
+        assert_eq!(read.amount, 1_500_000_000);
+        assert_eq!(read.tranche, 3);
+    }
+
+    #[test]
+    fn reads_a_token_heir() {
+        let designation = TokenHeir {
+            owner: Pubkey::new_unique(),
+            heir: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            amount: 0,
+            inactivity_period_seconds: 86_400,
+            last_activity: 1_700_000_000,
+            is_claimed: false,
+            bump: 255,
+            tranche: 0,
+            claim_requested_at: 1_700_100_000,
+            custody: TokenCustody::Share,
+            share_bps: 2_500,
+        };
+        let data = serialize(&designation);
+        assert_eq!(data.len(), TokenHeir::SPACE);
+
+        let read = token_heir(&data).unwrap();
+        assert_eq!(read.token_mint, designation.token_mint);
+        assert!(read.custody == TokenCustody::Share);
+        assert_eq!(read.share_bps, 2_500);
+        assert_eq!(read.claim_requested_at, 1_700_100_000);
+    }
+
+    #[test]
+    fn offsets_match_the_layouts() {
+        let mut designation = sample_sol_heir();
+        designation.is_claimed = true;
+        let data = serialize(&designation);
+        assert_eq!(&data[OWNER_OFFSET..OWNER_OFFSET + 32], designation.owner.as_ref());
+        assert_eq!(&data[HEIR_OFFSET..HEIR_OFFSET + 32], designation.heir.as_ref());
+        assert_eq!(data[SOL_HEIR_IS_CLAIMED_OFFSET], 1);
+
+        let designation = TokenHeir {
+            owner: Pubkey::new_unique(),
+            heir: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            amount: u64::MAX,
+            inactivity_period_seconds: -1,
+            last_activity: -1,
+            is_claimed: true,
+            bump: 0,
+            tranche: 0,
+            claim_requested_at: 0,
+            custody: TokenCustody::Escrow,
+            share_bps: 0,
+        };
+        let data = serialize(&designation);
+        assert_eq!(&data[HEIR_OFFSET..HEIR_OFFSET + 32], designation.heir.as_ref());
+        assert_eq!(data[TOKEN_HEIR_IS_CLAIMED_OFFSET], 1);
+        assert_eq!(data[TOKEN_HEIR_IS_CLAIMED_OFFSET + 1], 0);
+    }
+
+    #[test]
+    fn rejects_another_account_type() {
+        let data = serialize(&sample_sol_heir());
+        assert!(token_heir(&data).is_err());
+        assert!(user_profile(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_a_legacy_layout() {
+        // A SolHeir from before tranches and claim requests existed
+        let data = serialize(&sample_sol_heir());
+        assert!(sol_heir(&data[..data.len() - 10]).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! When a designation's claim opens, following the program's own checks.
+//! Guardians are not modelled: a quorum attesting the owner's death makes a
+//! claim final at once, and a freeze blocks it indefinitely.
+
+use gado::{NftHeir, ProgramHeir, SolHeir, StakeHeir, TokenBasketHeir, TokenHeir, UserProfile};
+
+/// The timestamps every designation account carries
+pub trait Designation {
+    fn last_activity(&self) -> i64;
+    fn inactivity_period_seconds(&self) -> i64;
+    /// 0 if no request is pending
+    fn claim_requested_at(&self) -> i64;
+}
+
+macro_rules! impl_designation {
+    ($($account:ty),*) => {
+        $(impl Designation for $account {
+            fn last_activity(&self) -> i64 {
+                self.last_activity
+            }
+
+            fn inactivity_period_seconds(&self) -> i64 {
+                self.inactivity_period_seconds
+            }
+
+            fn claim_requested_at(&self) -> i64 {
+                self.claim_requested_at
+            }
+        })*
+    };
+}
+
+impl_designation!(SolHeir, TokenHeir, NftHeir, TokenBasketHeir, StakeHeir, ProgramHeir);
+
+/// Whether a claim request is pending and the owner has not vetoed it with
+/// activity since
+pub fn claim_pending(user_profile: &UserProfile, designation: &impl Designation) -> bool {
+    designation.claim_requested_at() > user_profile.last_activity_for(designation.last_activity())
+}
+
+/// Earliest time at which the heir may request the claim, assuming the owner
+/// stays inactive
+pub fn requestable_at(user_profile: &UserProfile, designation: &impl Designation) -> i64 {
+    user_profile.last_activity_for(designation.last_activity()) + designation.inactivity_period_seconds() + 1
+}
+
+/// Earliest time at which the claim can be finalized, assuming the owner stays
+/// inactive: the end of the notice period after the pending request, or after
+/// the earliest possible request if none is pending
+pub fn claimable_at(user_profile: &UserProfile, designation: &impl Designation) -> i64 {
+    let requested_at = if claim_pending(user_profile, designation) {
+        designation.claim_requested_at()
+    } else {
+        requestable_at(user_profile, designation)
+    };
+    requested_at + user_profile.claim_notice_period()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::pubkey::Pubkey;
+
+    const DAY: i64 = 86_400;
+
+    fn profile(last_activity: i64) -> UserProfile {
+        UserProfile {
+            owner: Pubkey::new_unique(),
+            total_inheritances: 1,
+            bump: 255,
+            last_activity,
+            claim_notice_period_seconds: 7 * DAY,
+            crank_bounty_bps: 0,
+        }
+    }
+
+    fn designation(last_activity: i64, claim_requested_at: i64) -> SolHeir {
+        SolHeir {
+            owner: Pubkey::new_unique(),
+            heir: Pubkey::new_unique(),
+            amount: 1,
+            inactivity_period_seconds: 30 * DAY,
+            last_activity,
+            is_claimed: false,
+            bump: 255,
+            tranche: 0,
+            claim_requested_at,
+        }
+    }
+
+    #[test]
+    fn opens_after_inactivity_and_notice() {
+        let profile = profile(1_000 * DAY);
+        let heir = designation(0, 0);
+
+        assert!(!claim_pending(&profile, &heir));
+        assert_eq!(requestable_at(&profile, &heir), 1_030 * DAY + 1);
+        assert_eq!(claimable_at(&profile, &heir), 1_037 * DAY + 1);
+    }
+
+    #[test]
+    fn counts_from_a_pending_request() {
+        let profile = profile(1_000 * DAY);
+        let heir = designation(0, 1_040 * DAY);
+
+        assert!(claim_pending(&profile, &heir));
+        assert_eq!(claimable_at(&profile, &heir), 1_047 * DAY);
+    }
+
+    #[test]
+    fn owner_activity_vetoes_a_request() {
+        let profile = profile(1_050 * DAY);
+        let heir = designation(0, 1_040 * DAY);
+
+        assert!(!claim_pending(&profile, &heir));
+        assert_eq!(claimable_at(&profile, &heir), 1_087 * DAY + 1);
+    }
+
+    #[test]
+    fn uses_the_newer_of_the_two_activity_timestamps() {
+        // Designations migrated from per-heir heartbeats may be ahead of the profile
+        let profile = profile(0);
+        let heir = designation(1_000 * DAY, 0);
+
+        assert_eq!(requestable_at(&profile, &heir), 1_030 * DAY + 1);
+    }
+
+    #[test]
+    fn migrated_profiles_use_the_minimum_notice_period() {
+        let mut profile = profile(1_000 * DAY);
+        profile.claim_notice_period_seconds = 0;
+        let heir = designation(0, 0);
+
+        assert_eq!(
+            claimable_at(&profile, &heir),
+            1_030 * DAY + 1 + gado::MIN_CLAIM_NOTICE_PERIOD_SECONDS
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! What the keeper needs from a cluster
+
+use gado_client::rpc::{Account, Filter, Result, RpcClient};
+use gado_client::{Hash, Pubkey};
+use solana_transaction::Transaction;
+
+/// A cluster the keeper reads from and sends cranks to. Errors follow
+/// [`RpcError`](gado_client::rpc::RpcError): only `Transport` errors are
+/// retried, and a crank the program rejects is `TransactionFailed`.
+pub trait Cluster {
+    /// Current `Clock::unix_timestamp`, the time the program checks against
+    fn unix_timestamp(&self) -> Result<i64>;
+
+    /// `None` if the account does not exist
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>>;
+
+    /// Accounts of the `gado` program matching all `filters`
+    fn program_accounts(&self, filters: &[Filter]) -> Result<Vec<(Pubkey, Account)>>;
+
+    fn latest_blockhash(&self) -> Result<Hash>;
+
+    /// Send a signed transaction and wait for it to be confirmed
+    fn send_transaction(&self, transaction: &Transaction) -> Result<String>;
+}
+
+impl Cluster for RpcClient {
+    fn unix_timestamp(&self) -> Result<i64> {
+        Ok(self.get_clock()?.unix_timestamp)
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        self.get_account(address)
+    }
+
+    fn program_accounts(&self, filters: &[Filter]) -> Result<Vec<(Pubkey, Account)>> {
+        self.get_program_accounts(filters)
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        self.get_latest_blockhash()
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<String> {
+        let serialized = bincode::serialize(transaction).expect("transactions always serialize");
+        self.send_and_confirm_transaction(&serialized)
+    }
+}
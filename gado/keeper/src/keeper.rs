@@ -0,0 +1,325 @@
+//! Finding designations that are due and cranking them
+
+use std::collections::HashMap;
+use std::fmt;
+
+use gado_client::rpc::{Account, Filter, Result};
+use gado_client::state::{self, SOL_HEIR_IS_CLAIMED_OFFSET, TOKEN_HEIR_IS_CLAIMED_OFFSET};
+use gado_client::timing::{self, Designation};
+use gado_client::{instructions, pda, Instruction, Pubkey, SolHeir, TokenCustody, TokenHeir, UserProfile};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::cluster::Cluster;
+use crate::retry::Backoff;
+
+/// What a crank does to a designation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Request the claim, starting the notice period during which the owner
+    /// can veto it
+    RequestClaim,
+    /// Pay the heir once the notice period is over
+    FinalizeClaim,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::RequestClaim => f.write_str("request"),
+            Step::FinalizeClaim => f.write_str("finalize"),
+        }
+    }
+}
+
+/// The crank due on `designation` at cluster time `now`, if any, following
+/// the program's checks. Guardians are not modelled (see [`timing`]).
+pub fn plan(user_profile: &UserProfile, designation: &impl Designation, now: i64) -> Option<Step> {
+    if timing::claim_pending(user_profile, designation) {
+        (now >= timing::claimable_at(user_profile, designation)).then_some(Step::FinalizeClaim)
+    } else {
+        (now >= timing::requestable_at(user_profile, designation)).then_some(Step::RequestClaim)
+    }
+}
+
+/// A crank that is due
+#[derive(Clone, Debug)]
+pub struct Action {
+    pub designation: Pubkey,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    /// `None` for a SOL designation
+    pub mint: Option<Pubkey>,
+    pub step: Step,
+    pub instruction: Instruction,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.mint.is_some() { "token" } else { "SOL" };
+        write!(
+            f,
+            "{} {kind} claim {} (owner {}, heir {})",
+            self.step, self.designation, self.owner, self.heir
+        )
+    }
+}
+
+/// Counts from one pass of [`Keeper::run_once`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub due: usize,
+    pub submitted: usize,
+    pub failed: usize,
+}
+
+pub struct Keeper<C> {
+    cluster: C,
+    cranker: Keypair,
+    backoff: Backoff,
+    dry_run: bool,
+}
+
+impl<C: Cluster> Keeper<C> {
+    /// Keeper signing and paying for cranks with `cranker`, which also
+    /// receives the owners' crank bounties
+    pub fn new(cluster: C, cranker: Keypair) -> Self {
+        Self {
+            cluster,
+            cranker,
+            backoff: Backoff::default(),
+            dry_run: false,
+        }
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Log the cranks that are due instead of sending them
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn cluster(&self) -> &C {
+        &self.cluster
+    }
+
+    /// Every crank due now on an unclaimed SOL or escrowed token designation
+    pub fn scan(&self) -> Result<Vec<Action>> {
+        let now = self.backoff.retry(|| self.cluster.unix_timestamp())?;
+        let cranker = self.cranker.pubkey();
+        let mut profiles = HashMap::new();
+        let mut actions = Vec::new();
+
+        for (address, account) in self.unclaimed(Filter::account_type::<SolHeir>(), SOL_HEIR_IS_CLAIMED_OFFSET)? {
+            // Legacy layouts are skipped until migrate_heir_account grows them
+            let Ok(sol_heir) = state::sol_heir(&account.data) else {
+                continue;
+            };
+            let Some(profile) = self.profile(&mut profiles, &sol_heir.owner)? else {
+                continue;
+            };
+            if let Some(step) = plan(&profile, &sol_heir, now) {
+                actions.push(Action {
+                    designation: address,
+                    owner: sol_heir.owner,
+                    heir: sol_heir.heir,
+                    mint: None,
+                    step,
+                    instruction: instructions::crank_sol_claim(
+                        &sol_heir.owner,
+                        &sol_heir.heir,
+                        sol_heir.tranche,
+                        &cranker,
+                    ),
+                });
+            }
+        }
+
+        let mut token_programs = HashMap::new();
+        for (address, account) in self.unclaimed(Filter::account_type::<TokenHeir>(), TOKEN_HEIR_IS_CLAIMED_OFFSET)? {
+            let Ok(token_heir) = state::token_heir(&account.data) else {
+                continue;
+            };
+            if token_heir.custody != TokenCustody::Escrow {
+                continue;
+            }
+            let Some(profile) = self.profile(&mut profiles, &token_heir.owner)? else {
+                continue;
+            };
+            let Some(step) = plan(&profile, &token_heir, now) else {
+                continue;
+            };
+            let Some(token_program) = self.token_program(&mut token_programs, &token_heir.token_mint)? else {
+                continue;
+            };
+            actions.push(Action {
+                designation: address,
+                owner: token_heir.owner,
+                heir: token_heir.heir,
+                mint: Some(token_heir.token_mint),
+                step,
+                instruction: instructions::crank_token_claim(
+                    &token_heir.owner,
+                    &token_heir.heir,
+                    &token_heir.token_mint,
+                    &token_program,
+                    token_heir.tranche,
+                    &cranker,
+                ),
+            });
+        }
+
+        Ok(actions)
+    }
+
+    /// Sign and send one crank with a fresh blockhash, retrying transport
+    /// failures. A retry after a crank landed unconfirmed fails harmlessly,
+    /// since the program rejects the repeated step.
+    pub fn crank(&self, action: &Action) -> Result<String> {
+        self.backoff.retry(|| {
+            let blockhash = self.cluster.latest_blockhash()?;
+            let mut transaction =
+                Transaction::new_with_payer(std::slice::from_ref(&action.instruction), Some(&self.cranker.pubkey()));
+            transaction.sign(&[&self.cranker], blockhash);
+            self.cluster.send_transaction(&transaction)
+        })
+    }
+
+    /// Scan once and send every crank that is due (or log it, in a dry run).
+    /// A failed crank is logged and does not stop the others.
+    pub fn run_once(&self) -> Result<Summary> {
+        let actions = self.scan()?;
+        let mut summary = Summary {
+            due: actions.len(),
+            ..Summary::default()
+        };
+        for action in &actions {
+            if self.dry_run {
+                println!("dry run: would {action}");
+                continue;
+            }
+            match self.crank(action) {
+                Ok(signature) => {
+                    println!("{action}: {signature}");
+                    summary.submitted += 1;
+                }
+                Err(err) => {
+                    eprintln!("{action} failed: {err}");
+                    summary.failed += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Accounts of the type `account_type` selects whose `is_claimed` is false
+    fn unclaimed(&self, account_type: Filter, is_claimed_offset: usize) -> Result<Vec<(Pubkey, Account)>> {
+        let filters = [
+            account_type,
+            Filter::Memcmp {
+                offset: is_claimed_offset,
+                bytes: vec![0],
+            },
+        ];
+        self.backoff.retry(|| self.cluster.program_accounts(&filters))
+    }
+
+    fn profile(
+        &self,
+        profiles: &mut HashMap<Pubkey, Option<UserProfile>>,
+        owner: &Pubkey,
+    ) -> Result<Option<UserProfile>> {
+        if let Some(profile) = profiles.get(owner) {
+            return Ok(profile.clone());
+        }
+        let account = self.backoff.retry(|| self.cluster.account(&pda::user_profile(owner)))?;
+        let profile = account.and_then(|account| state::user_profile(&account.data).ok());
+        profiles.insert(*owner, profile.clone());
+        Ok(profile)
+    }
+
+    /// The mint's owner, which is its token program. `None` for a missing
+    /// mint, or one with a transfer hook, whose extra accounts cranks do not
+    /// forward.
+    fn token_program(
+        &self,
+        token_programs: &mut HashMap<Pubkey, Option<Pubkey>>,
+        mint: &Pubkey,
+    ) -> Result<Option<Pubkey>> {
+        if let Some(token_program) = token_programs.get(mint) {
+            return Ok(*token_program);
+        }
+        let token_program = self
+            .backoff
+            .retry(|| self.cluster.account(mint))?
+            .filter(|account| !state::mint_has_transfer_hook(&account.owner, &account.data))
+            .map(|account| account.owner);
+        token_programs.insert(*mint, token_program);
+        Ok(token_program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn profile(last_activity: i64) -> UserProfile {
+        UserProfile {
+            owner: Pubkey::new_unique(),
+            total_inheritances: 1,
+            bump: 255,
+            last_activity,
+            claim_notice_period_seconds: 7 * DAY,
+            crank_bounty_bps: 0,
+        }
+    }
+
+    fn designation(claim_requested_at: i64) -> SolHeir {
+        SolHeir {
+            owner: Pubkey::new_unique(),
+            heir: Pubkey::new_unique(),
+            amount: 1,
+            inactivity_period_seconds: 30 * DAY,
+            last_activity: 0,
+            is_claimed: false,
+            bump: 255,
+            tranche: 0,
+            claim_requested_at,
+        }
+    }
+
+    #[test]
+    fn requests_once_the_owner_is_inactive() {
+        let profile = profile(100 * DAY);
+        let heir = designation(0);
+
+        assert_eq!(plan(&profile, &heir, 130 * DAY), None);
+        assert_eq!(plan(&profile, &heir, 130 * DAY + 1), Some(Step::RequestClaim));
+    }
+
+    #[test]
+    fn finalizes_after_the_notice_period() {
+        let profile = profile(100 * DAY);
+        let heir = designation(131 * DAY);
+
+        assert_eq!(plan(&profile, &heir, 138 * DAY - 1), None);
+        assert_eq!(plan(&profile, &heir, 138 * DAY), Some(Step::FinalizeClaim));
+    }
+
+    #[test]
+    fn requests_again_after_a_veto() {
+        // The owner was active after the request, so it no longer counts
+        let profile = profile(135 * DAY);
+        let heir = designation(131 * DAY);
+
+        assert_eq!(plan(&profile, &heir, 140 * DAY), None);
+        assert_eq!(plan(&profile, &heir, 165 * DAY + 1), Some(Step::RequestClaim));
+    }
+}
@@ -0,0 +1,24 @@
+//! Keeper for the `gado` program: finds SOL and escrowed token designations
+//! whose owner has gone quiet and cranks them with `crank_sol_claim` and
+//! `crank_token_claim`.
+//!
+//! Cranking is two-step, like a heir's own claim. The first crank requests
+//! the claim, which warns the owner: any activity during the notice period
+//! vetoes it. Once the notice period is over, the next crank pays the heir.
+//!
+//! - [`cluster`] is everything the keeper reads from and sends to a cluster.
+//!   It is implemented for the JSON-RPC client; tests implement it in
+//!   process, and `program-tests` over LiteSVM running the program itself.
+//! - [`keeper`] scans for designations and plans and submits cranks.
+//! - [`retry`] is the backoff applied to transport failures.
+//!
+//! Delegated and share token designations cannot be cranked and are left to
+//! their heirs, as are token mints with a transfer hook.
+
+pub mod cluster;
+pub mod keeper;
+pub mod retry;
+
+pub use cluster::Cluster;
+pub use keeper::{plan, Action, Keeper, Step, Summary};
+pub use retry::Backoff;
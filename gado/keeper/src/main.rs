@@ -0,0 +1,75 @@
+//! `gado-keeper`: crank claimable designations on a schedule.
+
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use gado_client::rpc::RpcClient;
+use gado_keeper::{Backoff, Keeper};
+use solana_keypair::{read_keypair_file, Keypair};
+
+#[derive(Parser)]
+#[command(name = "gado-keeper", version, about = "Crank claimable gado designations")]
+struct Args {
+    /// JSON-RPC endpoint of the cluster
+    #[arg(long, short = 'u', default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Keypair file that pays for cranks and receives crank bounties
+    #[arg(long, short = 'k', default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Seconds between scans
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+
+    /// Scan once and exit
+    #[arg(long)]
+    once: bool,
+
+    /// Log the cranks that are due without sending them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Attempts per RPC call or crank before giving up on transport errors
+    #[arg(long, default_value_t = 5)]
+    attempts: u32,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let cranker = read_keypair(&args.keypair)?;
+    let backoff = Backoff {
+        attempts: args.attempts.max(1),
+        ..Backoff::default()
+    };
+    let keeper = Keeper::new(RpcClient::new(args.url), cranker)
+        .with_backoff(backoff)
+        .dry_run(args.dry_run);
+
+    loop {
+        match keeper.run_once() {
+            Ok(summary) => println!(
+                "scan: {} due, {} submitted, {} failed",
+                summary.due, summary.submitted, summary.failed
+            ),
+            // A failed scan is retried at the next interval
+            Err(err) if !args.once => eprintln!("scan failed: {err}"),
+            Err(err) => return Err(err.into()),
+        }
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(args.interval));
+    }
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(std::env::var("HOME").context("HOME is not set")?).join(rest),
+        None => PathBuf::from(path),
+    };
+    read_keypair_file(&path).map_err(|err| anyhow::anyhow!("cannot read keypair {}: {err}", path.display()))
+}
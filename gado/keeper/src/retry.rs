@@ -0,0 +1,107 @@
+//! Exponential backoff for transport failures
+
+use std::thread;
+use std::time::Duration;
+
+use gado_client::rpc::{Result, RpcError};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    /// Delay before the first retry; doubled before each further retry
+    pub initial: Duration,
+    pub max: Duration,
+    /// Attempts in all, including the first
+    pub attempts: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            attempts: 5,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before retry number `retry` (1 for the first retry)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 1u32.checked_shl(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    /// Run `operation` until it succeeds, fails with anything but a transport
+    /// error, or runs out of attempts
+    pub fn retry<T>(&self, mut operation: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut retry = 0;
+        loop {
+            match operation() {
+                Err(RpcError::Transport(err)) if retry + 1 < self.attempts => {
+                    retry += 1;
+                    let delay = self.delay(retry);
+                    eprintln!("transport error, retrying in {delay:?}: {err}");
+                    thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn immediate(attempts: u32) -> Backoff {
+        Backoff {
+            initial: Duration::ZERO,
+            max: Duration::ZERO,
+            attempts,
+        }
+    }
+
+    #[test]
+    fn doubles_up_to_the_cap() {
+        let backoff = Backoff::default();
+        let delays: Vec<u64> = (1..=8).map(|retry| backoff.delay(retry).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(backoff.delay(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retries_transport_errors_only() {
+        let mut calls = 0;
+        let result = immediate(5).retry(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(RpcError::Transport("timed out".into()))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: Result<()> = immediate(5).retry(|| {
+            calls += 1;
+            Err(RpcError::TransactionFailed {
+                signature: "sig".into(),
+                err: "OwnerStillActive".into(),
+            })
+        });
+        assert!(matches!(result, Err(RpcError::TransactionFailed { .. })));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn gives_up_after_the_last_attempt() {
+        let mut calls = 0;
+        let result: Result<()> = immediate(3).retry(|| {
+            calls += 1;
+            Err(RpcError::Transport("connection refused".into()))
+        });
+        assert!(matches!(result, Err(RpcError::Transport(_))));
+        assert_eq!(calls, 3);
+    }
+}
@@ -0,0 +1,325 @@
+//! The keeper against an in-process cluster: accounts live in memory,
+//! `getProgramAccounts` filters are applied as a node would, and sent
+//! transactions are recorded instead of executed.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anchor_lang::AccountSerialize;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+};
+use anchor_spl::token_2022::spl_token_2022::{self, state::Mint};
+use gado_client::rpc::{Account, Filter, Result, RpcError};
+use gado_client::{instructions, pda, Hash, Pubkey, SolHeir, TokenCustody, TokenHeir, UserProfile};
+use gado_keeper::{Backoff, Cluster, Keeper, Step, Summary};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const DAY: i64 = 86_400;
+
+#[derive(Default)]
+struct InProcessCluster {
+    now: i64,
+    accounts: BTreeMap<Pubkey, Account>,
+    sent: RefCell<Vec<Transaction>>,
+    /// Sends that fail with a transport error before one goes through
+    dropped_sends: Cell<u32>,
+}
+
+impl InProcessCluster {
+    fn store<T: AccountSerialize>(&mut self, address: Pubkey, account: &T) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        self.accounts.insert(
+            address,
+            Account {
+                lamports: 1_000_000,
+                owner: gado_client::ID,
+                data,
+            },
+        );
+    }
+}
+
+impl Cluster for InProcessCluster {
+    fn unix_timestamp(&self) -> Result<i64> {
+        Ok(self.now)
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.accounts.get(address).cloned())
+    }
+
+    fn program_accounts(&self, filters: &[Filter]) -> Result<Vec<(Pubkey, Account)>> {
+        let matches = |account: &Account| {
+            account.owner == gado_client::ID
+                && filters.iter().all(|filter| match filter {
+                    Filter::DataSize(size) => account.data.len() == *size,
+                    Filter::Memcmp { offset, bytes } => account.data.get(*offset..*offset + bytes.len()) == Some(bytes),
+                })
+        };
+        Ok(self
+            .accounts
+            .iter()
+            .filter(|(_, account)| matches(account))
+            .map(|(address, account)| (*address, account.clone()))
+            .collect())
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(Hash::new_unique())
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> Result<String> {
+        if self.dropped_sends.get() > 0 {
+            self.dropped_sends.set(self.dropped_sends.get() - 1);
+            return Err(RpcError::Transport("connection reset".into()));
+        }
+        assert!(transaction.is_signed());
+        self.sent.borrow_mut().push(transaction.clone());
+        Ok(transaction.signatures[0].to_string())
+    }
+}
+
+struct Estate {
+    owner: Pubkey,
+}
+
+impl Estate {
+    /// An owner last active at `last_activity`, with a 7-day notice period
+    fn new(cluster: &mut InProcessCluster, last_activity: i64) -> Self {
+        let owner = Pubkey::new_unique();
+        let profile = UserProfile {
+            owner,
+            total_inheritances: 0,
+            bump: 255,
+            last_activity,
+            claim_notice_period_seconds: 7 * DAY,
+            crank_bounty_bps: 100,
+        };
+        cluster.store(pda::user_profile(&owner), &profile);
+        Self { owner }
+    }
+
+    fn sol_heir(&self, cluster: &mut InProcessCluster, period: i64, claim_requested_at: i64) -> SolHeir {
+        let designation = SolHeir {
+            owner: self.owner,
+            heir: Pubkey::new_unique(),
+            amount: 1_000_000_000,
+            inactivity_period_seconds: period,
+            last_activity: 0,
+            is_claimed: false,
+            bump: 255,
+            tranche: 0,
+            claim_requested_at,
+        };
+        cluster.store(pda::sol_heir(&self.owner, &designation.heir, 0), &designation);
+        designation
+    }
+
+    fn token_heir(&self, cluster: &mut InProcessCluster, mint: &Pubkey, custody: TokenCustody) -> TokenHeir {
+        let designation = TokenHeir {
+            owner: self.owner,
+            heir: Pubkey::new_unique(),
+            token_mint: *mint,
+            amount: 500,
+            inactivity_period_seconds: 30 * DAY,
+            last_activity: 0,
+            is_claimed: false,
+            bump: 255,
+            tranche: 1,
+            claim_requested_at: 0,
+            custody,
+            share_bps: 0,
+        };
+        cluster.store(pda::token_heir(&self.owner, &designation.heir, mint, 1), &designation);
+        designation
+    }
+}
+
+fn keeper(cluster: InProcessCluster) -> (Keeper<InProcessCluster>, Pubkey) {
+    let cranker = Keypair::new();
+    let cranker_key = cranker.pubkey();
+    let backoff = Backoff {
+        initial: Duration::ZERO,
+        max: Duration::ZERO,
+        attempts: 3,
+    };
+    (Keeper::new(cluster, cranker).with_backoff(backoff), cranker_key)
+}
+
+#[test]
+fn cranks_designations_whose_owner_went_quiet() {
+    let mut cluster = InProcessCluster {
+        now: 40 * DAY,
+        ..Default::default()
+    };
+    let estate = Estate::new(&mut cluster, 0);
+    let due = estate.sol_heir(&mut cluster, 30 * DAY, 0);
+    estate.sol_heir(&mut cluster, 60 * DAY, 0);
+    let mut claimed = estate.sol_heir(&mut cluster, 30 * DAY, 0);
+    claimed.is_claimed = true;
+    cluster.store(pda::sol_heir(&estate.owner, &claimed.heir, 0), &claimed);
+
+    let token_program = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    cluster.accounts.insert(
+        mint,
+        Account {
+            lamports: 1_000_000,
+            owner: token_program,
+            data: vec![0; 82],
+        },
+    );
+    let escrowed = estate.token_heir(&mut cluster, &mint, TokenCustody::Escrow);
+    estate.token_heir(&mut cluster, &mint, TokenCustody::Share);
+
+    let (keeper, cranker) = keeper(cluster);
+    let summary = keeper.run_once().unwrap();
+    assert_eq!(
+        summary,
+        Summary {
+            due: 2,
+            submitted: 2,
+            failed: 0
+        }
+    );
+
+    let sent = keeper.cluster().sent.borrow();
+    let instructions: Vec<_> = sent
+        .iter()
+        .map(|transaction| transaction.message.instructions.len())
+        .collect();
+    assert_eq!(instructions, [1, 1]);
+    let expected = [
+        instructions::crank_sol_claim(&estate.owner, &due.heir, 0, &cranker),
+        instructions::crank_token_claim(&estate.owner, &escrowed.heir, &mint, &token_program, 1, &cranker),
+    ];
+    for (transaction, expected) in sent.iter().zip(&expected) {
+        let message = &transaction.message;
+        assert_eq!(message.account_keys[0], cranker);
+        let instruction = &message.instructions[0];
+        assert_eq!(
+            message.account_keys[instruction.program_id_index as usize],
+            gado_client::ID
+        );
+        assert_eq!(instruction.data, expected.data);
+        let accounts: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .map(|index| message.account_keys[*index as usize])
+            .collect();
+        let expected_accounts: Vec<Pubkey> = expected.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(accounts, expected_accounts);
+    }
+}
+
+#[test]
+fn skips_mints_with_a_transfer_hook() {
+    let mut cluster = InProcessCluster {
+        now: 40 * DAY,
+        ..Default::default()
+    };
+    let estate = Estate::new(&mut cluster, 0);
+
+    // A Token-2022 mint whose transfers call a hook program
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+    let mut data = vec![0; len];
+    let mut mint_state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    mint_state.init_extension::<TransferHook>(true).unwrap().program_id =
+        Some(Pubkey::new_unique()).try_into().unwrap();
+    mint_state.base = Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    };
+    mint_state.pack_base();
+    mint_state.init_account_type().unwrap();
+    let mint = Pubkey::new_unique();
+    cluster.accounts.insert(
+        mint,
+        Account {
+            lamports: 1_000_000,
+            owner: spl_token_2022::ID,
+            data,
+        },
+    );
+    estate.token_heir(&mut cluster, &mint, TokenCustody::Escrow);
+    let (keeper, _) = keeper(cluster);
+
+    assert!(keeper.scan().unwrap().is_empty());
+}
+
+#[test]
+fn finalizes_once_the_notice_period_is_over() {
+    let mut cluster = InProcessCluster {
+        now: 38 * DAY,
+        ..Default::default()
+    };
+    let estate = Estate::new(&mut cluster, 0);
+    estate.sol_heir(&mut cluster, 30 * DAY, 31 * DAY);
+    let (keeper, _) = keeper(cluster);
+
+    let actions = keeper.scan().unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].step, Step::FinalizeClaim);
+    assert_eq!(actions[0].mint, None);
+}
+
+#[test]
+fn leaves_active_owners_alone() {
+    let mut cluster = InProcessCluster {
+        now: 40 * DAY,
+        ..Default::default()
+    };
+    // The owner was active after the request, vetoing it, and recently enough
+    let estate = Estate::new(&mut cluster, 35 * DAY);
+    estate.sol_heir(&mut cluster, 30 * DAY, 31 * DAY);
+    let (keeper, _) = keeper(cluster);
+
+    assert!(keeper.scan().unwrap().is_empty());
+}
+
+#[test]
+fn dry_run_sends_nothing() {
+    let mut cluster = InProcessCluster {
+        now: 40 * DAY,
+        ..Default::default()
+    };
+    let estate = Estate::new(&mut cluster, 0);
+    estate.sol_heir(&mut cluster, 30 * DAY, 0);
+    let (keeper, _) = keeper(cluster);
+    let keeper = keeper.dry_run(true);
+
+    let summary = keeper.run_once().unwrap();
+    assert_eq!(summary.due, 1);
+    assert_eq!(summary.submitted, 0);
+    assert!(keeper.cluster().sent.borrow().is_empty());
+}
+
+#[test]
+fn retries_dropped_sends() {
+    let mut cluster = InProcessCluster {
+        now: 40 * DAY,
+        ..Default::default()
+    };
+    let estate = Estate::new(&mut cluster, 0);
+    estate.sol_heir(&mut cluster, 30 * DAY, 0);
+    estate.sol_heir(&mut cluster, 30 * DAY, 0);
+    cluster.dropped_sends.set(2);
+    let (keeper, _) = keeper(cluster);
+
+    // Two dropped sends fit in the first crank's three attempts
+    let summary = keeper.run_once().unwrap();
+    assert_eq!(summary.submitted, 2);
+    assert_eq!(keeper.cluster().sent.borrow().len(), 2);
+
+    keeper.cluster().dropped_sends.set(3);
+    let summary = keeper.run_once().unwrap();
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.submitted, 1);
+}
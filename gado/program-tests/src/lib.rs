@@ -0,0 +1,512 @@
+//! In-process tests of the compiled `gado` program on LiteSVM.
+//!
+//! The suite loads `target/deploy/gado.so`, so run it with `anchor run
+//! program-tests`, which builds the program and then runs `cargo test
+//! --manifest-path program-tests/Cargo.toml`. The program is built without
+//! `short-claim-delay`: designations use real inactivity and notice periods,
+//! and the tests [`warp`](TestEnv::warp) the `Clock` across them instead of
+//! sleeping.
+//!
+//! [`TestEnv`] wraps the VM with helpers to send transactions, assert program
+//! errors, read accounts and set up mints, stake accounts and upgradeable
+//! programs. Legacy account layouts that no instruction creates any more are
+//! written directly with [`TestEnv::store`] and [`TestEnv::put`], and
+//! [`events`] decodes what a transaction emitted from its logs.
+//!
+//! [`KeeperCluster`] lets `gado-keeper` crank against the same VM.
+
+use std::cell::{RefCell, RefMut};
+use std::path::PathBuf;
+
+use anchor_lang::prelude::{Clock, ProgramData};
+use anchor_lang::solana_program::instruction::InstructionError;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize, AnchorSerialize, Event, Owner};
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_hook::instruction::initialize as initialize_transfer_hook;
+use anchor_spl::token_interface::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_interface::spl_token_2022::{self, instruction as token_instruction};
+use anchor_spl::token_interface::TokenAccount;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use gado_client::rpc::{self, Filter, RpcError};
+use gado_client::{instructions, pda, Hash, Instruction, Pubkey};
+use gado_keeper::Cluster;
+use litesvm::types::{TransactionMetadata, TransactionResult};
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_signer::Signer;
+use solana_stake_interface::state::{Authorized, Lockup, Meta, StakeStateV2};
+use solana_system_interface::instruction::create_account;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+pub const DAY: i64 = 86_400;
+pub const SOL: u64 = 1_000_000_000;
+/// Cluster time every test starts at
+pub const START_TIME: i64 = 1_700_000_000;
+
+/// SPL Token and Token-2022 program ids
+pub const TOKEN: Pubkey = anchor_spl::token::ID;
+pub const TOKEN_2022: Pubkey = spl_token_2022::ID;
+
+pub struct TestEnv {
+    pub svm: LiteSVM,
+    /// Pays for fixtures, and is the mint and freeze authority of every test mint
+    pub authority: Keypair,
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestEnv {
+    /// A VM with the SPL programs and `gado` loaded, at [`START_TIME`]
+    pub fn new() -> Self {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target/deploy/gado.so");
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(gado::ID, &path)
+            .unwrap_or_else(|err| panic!("cannot load {} (run `anchor build` first): {err:?}", path.display()));
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = START_TIME;
+        svm.set_sysvar(&clock);
+
+        let authority = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 1_000 * SOL).unwrap();
+        Self { svm, authority }
+    }
+
+    // ===============================================
+    // CLOCK
+    // ===============================================
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    /// Move the cluster clock `seconds` forward
+    pub fn warp(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        self.svm.set_sysvar(&clock);
+    }
+
+    // ===============================================
+    // TRANSACTIONS
+    // ===============================================
+
+    /// A fresh keypair holding 100 SOL
+    pub fn funded(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.svm.airdrop(&keypair.pubkey(), 100 * SOL).unwrap();
+        keypair
+    }
+
+    /// A funded owner with an initialized profile
+    pub fn owner(&mut self) -> Keypair {
+        let owner = self.funded();
+        self.execute(instructions::initialize_user(&owner.pubkey()), &[&owner]);
+        owner
+    }
+
+    /// Send `instructions` paid for by the first signer. The blockhash is
+    /// refreshed first, so repeating a transaction is never a duplicate.
+    #[allow(clippy::result_large_err)] // LiteSVM's own result type
+    pub fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(transaction)
+    }
+
+    /// Send `instruction` and panic with the program logs if it fails
+    pub fn execute(&mut self, instruction: Instruction, signers: &[&Keypair]) -> TransactionMetadata {
+        self.send(&[instruction], signers)
+            .unwrap_or_else(|failed| panic!("transaction failed: {:?}\n{}", failed.err, failed.meta.logs.join("\n")))
+    }
+
+    /// Send `instruction` and assert it fails with the custom program error
+    /// `code`, a `gado::ErrorCode` or an Anchor framework error
+    #[track_caller]
+    pub fn assert_error(&mut self, instruction: Instruction, signers: &[&Keypair], code: impl Into<u32>) {
+        let expected = code.into();
+        match self.send(&[instruction], signers) {
+            Ok(_) => panic!("transaction succeeded, expected custom error {expected}"),
+            Err(failed) => match failed.err {
+                TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+                    assert_eq!(actual, expected, "wrong custom error\n{}", failed.meta.logs.join("\n"))
+                }
+                err => panic!(
+                    "expected custom error {expected}, got {err:?}\n{}",
+                    failed.meta.logs.join("\n")
+                ),
+            },
+        }
+    }
+
+    // ===============================================
+    // ACCOUNTS
+    // ===============================================
+
+    /// Deserialize the Anchor account at `address`
+    #[track_caller]
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self
+            .svm
+            .get_account(address)
+            .unwrap_or_else(|| panic!("no account at {address}"));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Whether `address` holds an account (closed accounts hold no lamports)
+    pub fn exists(&self, address: &Pubkey) -> bool {
+        self.svm
+            .get_account(address)
+            .is_some_and(|account| account.lamports > 0)
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_account(address).map_or(0, |account| account.lamports)
+    }
+
+    pub fn data(&self, address: &Pubkey) -> Vec<u8> {
+        self.svm
+            .get_account(address)
+            .map(|account| account.data)
+            .unwrap_or_default()
+    }
+
+    /// Write a rent-exempt account owned by `owner`, bypassing the programs
+    pub fn put(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+    }
+
+    /// Write `account` as a `gado` account at `address`
+    pub fn store<T: AccountSerialize>(&mut self, address: Pubkey, account: &T) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        self.put(address, gado::ID, data);
+    }
+
+    // ===============================================
+    // TOKENS
+    // ===============================================
+
+    /// A mint of `token_program` whose mint authority is [`TestEnv::authority`],
+    /// which is also its freeze authority if `freezable`
+    pub fn mint(&mut self, token_program: &Pubkey, decimals: u8, freezable: bool) -> Pubkey {
+        let mint = Keypair::new();
+        let authority = self.authority.pubkey();
+        let space = spl_token_2022::state::Mint::LEN;
+        let instructions = [
+            create_account(
+                &authority,
+                &mint.pubkey(),
+                self.svm.minimum_balance_for_rent_exemption(space),
+                space as u64,
+                token_program,
+            ),
+            token_instruction::initialize_mint2(
+                token_program,
+                &mint.pubkey(),
+                &authority,
+                freezable.then_some(&authority),
+                decimals,
+            )
+            .unwrap(),
+        ];
+        let payer = self.authority.insecure_clone();
+        self.send(&instructions, &[&payer, &mint]).unwrap();
+        mint.pubkey()
+    }
+
+    /// A Token-2022 mint whose permanent delegate can move any holder's tokens
+    pub fn mint_with_permanent_delegate(&mut self, delegate: &Pubkey) -> Pubkey {
+        self.mint_with_extension(ExtensionType::PermanentDelegate, 6, |mint| {
+            token_instruction::initialize_permanent_delegate(&TOKEN_2022, mint, delegate).unwrap()
+        })
+    }
+
+    /// A Token-2022 mint charging `basis_points` of every transfer, capped at
+    /// `maximum_fee`. Fees are withheld on the receiving account.
+    pub fn mint_with_transfer_fee(&mut self, basis_points: u16, maximum_fee: u64) -> Pubkey {
+        self.mint_with_extension(ExtensionType::TransferFeeConfig, 6, |mint| {
+            initialize_transfer_fee_config(&TOKEN_2022, mint, None, None, basis_points, maximum_fee).unwrap()
+        })
+    }
+
+    /// A Token-2022 mint whose transfers invoke `hook_program`
+    pub fn mint_with_transfer_hook(&mut self, hook_program: &Pubkey, decimals: u8) -> Pubkey {
+        self.mint_with_extension(ExtensionType::TransferHook, decimals, |mint| {
+            initialize_transfer_hook(&TOKEN_2022, mint, None, Some(*hook_program)).unwrap()
+        })
+    }
+
+    /// A Token-2022 mint with one `extension`, set up by `initialize`
+    fn mint_with_extension(
+        &mut self,
+        extension: ExtensionType,
+        decimals: u8,
+        initialize: impl FnOnce(&Pubkey) -> Instruction,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let authority = self.authority.pubkey();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[extension]).unwrap();
+        let instructions = [
+            create_account(
+                &authority,
+                &mint.pubkey(),
+                self.svm.minimum_balance_for_rent_exemption(space),
+                space as u64,
+                &TOKEN_2022,
+            ),
+            initialize(&mint.pubkey()),
+            token_instruction::initialize_mint2(&TOKEN_2022, &mint.pubkey(), &authority, None, decimals).unwrap(),
+        ];
+        let payer = self.authority.insecure_clone();
+        self.send(&instructions, &[&payer, &mint]).unwrap();
+        mint.pubkey()
+    }
+
+    /// `owner`'s associated token account for `mint`, created if needed
+    pub fn token_account(&mut self, owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        let payer = self.authority.insecure_clone();
+        let create = create_associated_token_account_idempotent(&payer.pubkey(), owner, mint, token_program);
+        self.send(&[create], &[&payer]).unwrap();
+        pda::associated_token_account(owner, mint, token_program)
+    }
+
+    /// Mint `amount` into `owner`'s associated token account, returning it
+    pub fn mint_to(&mut self, mint: &Pubkey, token_program: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = self.token_account(owner, mint, token_program);
+        let payer = self.authority.insecure_clone();
+        let mint_to = token_instruction::mint_to(token_program, mint, &account, &payer.pubkey(), &[], amount).unwrap();
+        self.send(&[mint_to], &[&payer]).unwrap();
+        account
+    }
+
+    /// A 0-decimal, supply-1 mint whose single token `owner` holds
+    pub fn nft(&mut self, owner: &Pubkey) -> Pubkey {
+        let mint = self.mint(&TOKEN, 0, false);
+        self.mint_to(&mint, &TOKEN, owner, 1);
+        mint
+    }
+
+    /// Freeze a token account of a freezable test mint
+    pub fn freeze(&mut self, account: &Pubkey, mint: &Pubkey, token_program: &Pubkey) {
+        let payer = self.authority.insecure_clone();
+        let freeze = token_instruction::freeze_account(token_program, account, mint, &payer.pubkey(), &[]).unwrap();
+        self.send(&[freeze], &[&payer]).unwrap();
+    }
+
+    pub fn token_balance(&self, account: &Pubkey) -> u64 {
+        self.account::<TokenAccount>(account).amount
+    }
+
+    // ===============================================
+    // STAKE ACCOUNTS AND PROGRAMS
+    // ===============================================
+
+    /// An initialized, undelegated stake account holding 10 SOL whose staker
+    /// and withdrawer are both `authority`
+    pub fn stake_account(&mut self, authority: &Pubkey, lockup: Lockup) -> Pubkey {
+        let rent_exempt_reserve = self.svm.minimum_balance_for_rent_exemption(StakeStateV2::size_of());
+        let state = StakeStateV2::Initialized(Meta {
+            rent_exempt_reserve,
+            authorized: Authorized {
+                staker: *authority,
+                withdrawer: *authority,
+            },
+            lockup,
+        });
+        self.put_stake_state(state, rent_exempt_reserve + 10 * SOL)
+    }
+
+    /// A stake account that was never initialized
+    pub fn uninitialized_stake_account(&mut self) -> Pubkey {
+        let rent = self.svm.minimum_balance_for_rent_exemption(StakeStateV2::size_of());
+        self.put_stake_state(StakeStateV2::Uninitialized, rent)
+    }
+
+    fn put_stake_state(&mut self, state: StakeStateV2, lamports: u64) -> Pubkey {
+        let mut data = Vec::with_capacity(StakeStateV2::size_of());
+        state.serialize(&mut data).unwrap();
+        data.resize(StakeStateV2::size_of(), 0);
+
+        let address = Pubkey::new_unique();
+        let account = Account {
+            lamports,
+            data,
+            owner: solana_stake_interface::program::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+        address
+    }
+
+    /// `(staker, withdrawer)` of a stake account
+    pub fn stake_authorities(&self, stake_account: &Pubkey) -> (Pubkey, Pubkey) {
+        let state = StakeStateV2::deserialize(&mut self.data(stake_account).as_slice()).unwrap();
+        let meta = state.meta().expect("stake account is initialized");
+        (meta.authorized.staker, meta.authorized.withdrawer)
+    }
+
+    /// A BPF-upgradeable program with `upgrade_authority`, or immutable if `None`.
+    /// The loader's `SetAuthority` only touches the program data, so the program
+    /// has no code and is not marked executable (the VM would try to load it).
+    pub fn upgradeable_program(&mut self, upgrade_authority: Option<Pubkey>) -> Pubkey {
+        let loader = ProgramData::owner();
+        let program = Pubkey::new_unique();
+        let programdata_address = pda::program_data(&program);
+
+        let program_state = UpgradeableLoaderState::Program { programdata_address };
+        let account = Account {
+            lamports: self
+                .svm
+                .minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program()),
+            data: bincode::serialize(&program_state).unwrap(),
+            owner: loader,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(program, account).unwrap();
+
+        let program_data_state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: upgrade_authority,
+        };
+        let mut data = bincode::serialize(&program_data_state).unwrap();
+        data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+        self.put(programdata_address, loader, data);
+        program
+    }
+
+    pub fn upgrade_authority(&self, program: &Pubkey) -> Option<Pubkey> {
+        match bincode::deserialize(&self.data(&pda::program_data(program))).unwrap() {
+            UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address,
+                ..
+            } => upgrade_authority_address,
+            state => panic!("not program data: {state:?}"),
+        }
+    }
+}
+
+/// Replace the account at `old` in `instruction`'s account list with `new`,
+/// to pass an account the builder would not derive
+pub fn swap_account(mut instruction: Instruction, old: &Pubkey, new: &Pubkey) -> Instruction {
+    let meta = instruction
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == *old)
+        .expect("account is in the instruction");
+    meta.pubkey = *new;
+    instruction
+}
+
+/// Decode the `E` events `gado` logged in a transaction
+pub fn events<E: Event>(meta: &TransactionMetadata) -> Vec<E> {
+    meta.logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(E::DISCRIMINATOR))
+        .map(|data| E::try_from_slice(&data[E::DISCRIMINATOR.len()..]).unwrap())
+        .collect()
+}
+
+/// The one `E` event `gado` logged in a transaction
+#[track_caller]
+pub fn event<E: Event>(meta: &TransactionMetadata) -> E {
+    let mut events = events(meta);
+    assert_eq!(events.len(), 1, "expected one event\n{}", meta.logs.join("\n"));
+    events.remove(0)
+}
+
+/// A [`TestEnv`] the keeper can crank against. LiteSVM cannot list a
+/// program's accounts, so `getProgramAccounts` is answered from the addresses
+/// the cluster was created with. The keeper only borrows its cluster, so the
+/// environment sits in a `RefCell` and tests reach it through [`env`](Self::env).
+pub struct KeeperCluster {
+    env: RefCell<TestEnv>,
+    designations: Vec<Pubkey>,
+}
+
+impl KeeperCluster {
+    pub fn new(env: TestEnv, designations: &[Pubkey]) -> Self {
+        Self {
+            env: RefCell::new(env),
+            designations: designations.to_vec(),
+        }
+    }
+
+    pub fn env(&self) -> RefMut<'_, TestEnv> {
+        self.env.borrow_mut()
+    }
+}
+
+impl Cluster for KeeperCluster {
+    fn unix_timestamp(&self) -> rpc::Result<i64> {
+        Ok(self.env().now())
+    }
+
+    fn account(&self, address: &Pubkey) -> rpc::Result<Option<rpc::Account>> {
+        Ok(self.env().svm.get_account(address).map(|account| rpc::Account {
+            lamports: account.lamports,
+            owner: account.owner,
+            data: account.data,
+        }))
+    }
+
+    fn program_accounts(&self, filters: &[Filter]) -> rpc::Result<Vec<(Pubkey, rpc::Account)>> {
+        let matches = |account: &rpc::Account| {
+            account.owner == gado::ID
+                && filters.iter().all(|filter| match filter {
+                    Filter::DataSize(size) => account.data.len() == *size,
+                    Filter::Memcmp { offset, bytes } => account.data.get(*offset..*offset + bytes.len()) == Some(bytes),
+                })
+        };
+        let mut accounts = Vec::new();
+        for address in &self.designations {
+            if let Some(account) = self.account(address)?.filter(matches) {
+                accounts.push((*address, account));
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// A fresh blockhash each time, so a repeated crank is never a duplicate
+    fn latest_blockhash(&self) -> rpc::Result<Hash> {
+        let mut env = self.env();
+        env.svm.expire_blockhash();
+        Ok(env.svm.latest_blockhash())
+    }
+
+    fn send_transaction(&self, transaction: &Transaction) -> rpc::Result<String> {
+        let signature = transaction.signatures[0].to_string();
+        match self.env().svm.send_transaction(transaction.clone()) {
+            Ok(_) => Ok(signature),
+            Err(failed) => Err(RpcError::TransactionFailed {
+                signature,
+                err: format!("{:?}", failed.err),
+            }),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Designations that hold an authority instead of funds: stake accounts and
+//! BPF-upgradeable programs
+
+use gado::ErrorCode;
+use gado_client::{instructions, pda, ProgramHeir, StakeHeir, UserProfile};
+use gado_program_tests::{TestEnv, DAY};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_stake_interface::state::Lockup;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+
+#[test]
+fn stake_claim_hands_both_authorities_to_the_heir() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = env.funded();
+    let stake = env.stake_account(&owner.pubkey(), Lockup::default());
+    let stake_heir = pda::stake_heir(&owner.pubkey(), &heir.pubkey(), &stake);
+
+    env.execute(
+        instructions::add_stake_heir(&owner.pubkey(), &heir.pubkey(), &stake, PERIOD),
+        &[&owner],
+    );
+    assert_eq!(env.account::<StakeHeir>(&stake_heir).stake_account, stake);
+    assert_eq!(env.stake_authorities(&stake), (stake_heir, stake_heir));
+
+    let request = instructions::request_stake_claim(&owner.pubkey(), &heir.pubkey(), &stake);
+    let finalize = instructions::finalize_stake_claim(&owner.pubkey(), &heir.pubkey(), &stake);
+    env.warp(PERIOD);
+    env.assert_error(request.clone(), &[&heir], ErrorCode::OwnerStillActive);
+    env.warp(1);
+    env.execute(request, &[&heir]);
+    env.assert_error(finalize.clone(), &[&heir], ErrorCode::NoticePeriodActive);
+    env.warp(NOTICE);
+    env.execute(finalize, &[&heir]);
+
+    assert_eq!(env.stake_authorities(&stake), (heir.pubkey(), heir.pubkey()));
+    assert!(!env.exists(&stake_heir));
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
+
+#[test]
+fn add_stake_heir_checks_the_stake_account() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let add = |stake| instructions::add_stake_heir(&owner.pubkey(), &heir, &stake, PERIOD);
+
+    let uninitialized = env.uninitialized_stake_account();
+    env.assert_error(add(uninitialized), &[&owner], ErrorCode::InvalidStakeAccount);
+
+    let someone_elses = env.stake_account(&Keypair::new().pubkey(), Lockup::default());
+    env.assert_error(add(someone_elses), &[&owner], ErrorCode::Unauthorized);
+
+    let locked = env.stake_account(
+        &owner.pubkey(),
+        Lockup {
+            unix_timestamp: env.now() + 365 * DAY,
+            epoch: 0,
+            custodian: Keypair::new().pubkey(),
+        },
+    );
+    env.assert_error(add(locked), &[&owner], ErrorCode::StakeLockupInForce);
+    // Lockups expire with the clock
+    env.warp(365 * DAY + 1);
+    env.execute(add(locked), &[&owner]);
+}
+
+#[test]
+fn cancel_stake_heir_returns_both_authorities() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let stake = env.stake_account(&owner.pubkey(), Lockup::default());
+    env.execute(
+        instructions::add_stake_heir(&owner.pubkey(), &heir, &stake, PERIOD),
+        &[&owner],
+    );
+
+    env.execute(
+        instructions::cancel_stake_heir(&owner.pubkey(), &heir, &stake),
+        &[&owner],
+    );
+
+    assert_eq!(env.stake_authorities(&stake), (owner.pubkey(), owner.pubkey()));
+    assert!(!env.exists(&pda::stake_heir(&owner.pubkey(), &heir, &stake)));
+}
+
+#[test]
+fn program_claim_hands_the_upgrade_authority_to_the_heir() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = env.funded();
+    let program = env.upgradeable_program(Some(owner.pubkey()));
+    let program_heir = pda::program_heir(&owner.pubkey(), &heir.pubkey(), &program);
+
+    env.execute(
+        instructions::add_program_heir(&owner.pubkey(), &heir.pubkey(), &program, PERIOD),
+        &[&owner],
+    );
+    assert_eq!(env.account::<ProgramHeir>(&program_heir).upgradeable_program, program);
+    assert_eq!(env.upgrade_authority(&program), Some(program_heir));
+
+    let request = instructions::request_program_claim(&owner.pubkey(), &heir.pubkey(), &program);
+    let finalize = instructions::finalize_program_claim(&owner.pubkey(), &heir.pubkey(), &program);
+    env.warp(PERIOD + 1);
+    env.execute(request.clone(), &[&heir]);
+    env.assert_error(request, &[&heir], ErrorCode::ClaimAlreadyRequested);
+    env.warp(NOTICE - 1);
+    env.assert_error(finalize.clone(), &[&heir], ErrorCode::NoticePeriodActive);
+    env.warp(1);
+    env.execute(finalize, &[&heir]);
+
+    assert_eq!(env.upgrade_authority(&program), Some(heir.pubkey()));
+    assert!(!env.exists(&program_heir));
+}
+
+#[test]
+fn add_program_heir_requires_the_upgrade_authority() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let add = |program| instructions::add_program_heir(&owner.pubkey(), &heir, &program, PERIOD);
+
+    let immutable = env.upgradeable_program(None);
+    env.assert_error(add(immutable), &[&owner], ErrorCode::InvalidUpgradeableProgram);
+
+    let someone_elses = env.upgradeable_program(Some(Keypair::new().pubkey()));
+    env.assert_error(add(someone_elses), &[&owner], ErrorCode::Unauthorized);
+}
+
+#[test]
+fn cancel_program_heir_returns_the_upgrade_authority() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let program = env.upgradeable_program(Some(owner.pubkey()));
+    env.execute(
+        instructions::add_program_heir(&owner.pubkey(), &heir, &program, PERIOD),
+        &[&owner],
+    );
+
+    env.execute(
+        instructions::cancel_program_heir(&owner.pubkey(), &heir, &program),
+        &[&owner],
+    );
+
+    assert_eq!(env.upgrade_authority(&program), Some(owner.pubkey()));
+    assert!(!env.exists(&pda::program_heir(&owner.pubkey(), &heir, &program)));
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
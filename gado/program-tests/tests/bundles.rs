@@ -0,0 +1,286 @@
+//! NFT bundles and token baskets: one designation escrowing several mints
+
+use gado::ErrorCode;
+use gado_client::{instructions, pda, MintRiskPolicy, NftHeir, Pubkey, TokenBasketHeir, UserProfile};
+use gado_program_tests::{swap_account, TestEnv, DAY, TOKEN, TOKEN_2022};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+
+#[test]
+fn nft_bundle_claim_delivers_every_nft() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = env.funded();
+    let mints = [env.nft(&owner.pubkey()), env.nft(&owner.pubkey())];
+    let nft_heir = pda::nft_heir(&owner.pubkey(), &heir.pubkey(), 0);
+
+    env.execute(
+        instructions::add_nft_heir(&owner.pubkey(), &heir.pubkey(), &TOKEN, 0, &mints, PERIOD),
+        &[&owner],
+    );
+    assert_eq!(env.account::<NftHeir>(&nft_heir).mints, mints);
+    for mint in &mints {
+        let owner_account = pda::associated_token_account(&owner.pubkey(), mint, &TOKEN);
+        assert_eq!(env.token_balance(&owner_account), 0);
+        assert_eq!(
+            env.token_balance(&pda::associated_token_account(&nft_heir, mint, &TOKEN)),
+            1
+        );
+    }
+
+    let request = instructions::request_nft_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    env.warp(PERIOD);
+    env.assert_error(request.clone(), &[&heir], ErrorCode::OwnerStillActive);
+    env.warp(1);
+    env.execute(request, &[&heir]);
+    env.warp(NOTICE);
+
+    // The escrows must be passed in the bundle's order
+    let reversed = [mints[1], mints[0]];
+    env.assert_error(
+        instructions::finalize_nft_claim(&owner.pubkey(), &heir.pubkey(), &TOKEN, 0, &reversed),
+        &[&heir],
+        ErrorCode::InvalidMint,
+    );
+    env.assert_error(
+        instructions::finalize_nft_claim(&owner.pubkey(), &heir.pubkey(), &TOKEN, 0, &mints[..1]),
+        &[&heir],
+        ErrorCode::InvalidNftBundle,
+    );
+    env.execute(
+        instructions::finalize_nft_claim(&owner.pubkey(), &heir.pubkey(), &TOKEN, 0, &mints),
+        &[&heir],
+    );
+
+    for mint in &mints {
+        let heir_account = pda::associated_token_account(&heir.pubkey(), mint, &TOKEN);
+        assert_eq!(env.token_balance(&heir_account), 1);
+        assert!(!env.exists(&pda::associated_token_account(&nft_heir, mint, &TOKEN)));
+    }
+    assert!(!env.exists(&nft_heir));
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
+
+#[test]
+fn add_nft_heir_validates_the_bundle() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let nft = env.nft(&owner.pubkey());
+    let add = |mints: &[Pubkey]| instructions::add_nft_heir(&owner.pubkey(), &heir, &TOKEN, 0, mints, PERIOD);
+
+    env.assert_error(add(&[]), &[&owner], ErrorCode::InvalidNftBundle);
+    env.assert_error(add(&[nft, nft]), &[&owner], ErrorCode::InvalidNftBundle);
+
+    let mut incomplete = add(&[nft]);
+    incomplete.accounts.pop();
+    env.assert_error(incomplete, &[&owner], ErrorCode::InvalidNftBundle);
+
+    let fungible = env.mint(&TOKEN, 6, false);
+    env.mint_to(&fungible, &TOKEN, &owner.pubkey(), 1);
+    env.assert_error(add(&[fungible]), &[&owner], ErrorCode::NotAnNft);
+
+    // Claims could not forward a transfer hook's accounts
+    let hooked = env.mint_with_transfer_hook(&Keypair::new().pubkey(), 0);
+    env.mint_to(&hooked, &TOKEN_2022, &owner.pubkey(), 1);
+    env.assert_error(
+        instructions::add_nft_heir(&owner.pubkey(), &heir, &TOKEN_2022, 0, &[hooked], PERIOD),
+        &[&owner],
+        ErrorCode::MintHasTransferHook,
+    );
+
+    // An NFT held by someone else
+    let stranger = Keypair::new().pubkey();
+    let elsewhere = env.nft(&stranger);
+    let add_elsewhere = instructions::add_nft_heir(&owner.pubkey(), &heir, &TOKEN, 0, &[elsewhere], PERIOD);
+    let add_elsewhere = swap_account(
+        add_elsewhere,
+        &pda::associated_token_account(&owner.pubkey(), &elsewhere, &TOKEN),
+        &pda::associated_token_account(&stranger, &elsewhere, &TOKEN),
+    );
+    env.assert_error(add_elsewhere, &[&owner], ErrorCode::Unauthorized);
+}
+
+#[test]
+fn cancel_nft_heir_returns_the_nfts() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let mints = [env.nft(&owner.pubkey()), env.nft(&owner.pubkey())];
+    let nft_heir = pda::nft_heir(&owner.pubkey(), &heir, 0);
+    env.execute(
+        instructions::add_nft_heir(&owner.pubkey(), &heir, &TOKEN, 0, &mints, PERIOD),
+        &[&owner],
+    );
+
+    env.assert_error(
+        instructions::cancel_nft_heir(&owner.pubkey(), &heir, &TOKEN, 0, &mints[1..]),
+        &[&owner],
+        ErrorCode::InvalidNftBundle,
+    );
+    env.execute(
+        instructions::cancel_nft_heir(&owner.pubkey(), &heir, &TOKEN, 0, &mints),
+        &[&owner],
+    );
+
+    for mint in &mints {
+        assert_eq!(
+            env.token_balance(&pda::associated_token_account(&owner.pubkey(), mint, &TOKEN)),
+            1
+        );
+    }
+    assert!(!env.exists(&nft_heir));
+}
+
+#[test]
+fn token_basket_claim_pays_every_entry() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = env.funded();
+    let mints = [env.mint(&TOKEN, 6, false), env.mint(&TOKEN, 9, false)];
+    for mint in &mints {
+        env.mint_to(mint, &TOKEN, &owner.pubkey(), 1_000);
+    }
+    let entries = [(mints[0], 100), (mints[1], 200)];
+    let basket = pda::token_basket_heir(&owner.pubkey(), &heir.pubkey(), 0);
+
+    env.execute(
+        instructions::add_token_basket_heir(
+            &owner.pubkey(),
+            &heir.pubkey(),
+            &TOKEN,
+            0,
+            &entries,
+            PERIOD,
+            MintRiskPolicy::Reject,
+        ),
+        &[&owner],
+    );
+    let designation: TokenBasketHeir = env.account(&basket);
+    let recorded: Vec<(Pubkey, u64)> = designation
+        .entries
+        .iter()
+        .map(|entry| (entry.mint, entry.amount))
+        .collect();
+    assert_eq!(recorded, entries);
+
+    let request = instructions::request_token_basket_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    let finalize = instructions::finalize_token_basket_claim(&owner.pubkey(), &heir.pubkey(), &TOKEN, 0, &mints);
+    env.warp(PERIOD + 1);
+    env.execute(request.clone(), &[&heir]);
+    env.assert_error(request, &[&heir], ErrorCode::ClaimAlreadyRequested);
+    env.assert_error(finalize.clone(), &[&heir], ErrorCode::NoticePeriodActive);
+    env.warp(NOTICE);
+    env.assert_error(
+        instructions::finalize_token_basket_claim(&owner.pubkey(), &heir.pubkey(), &TOKEN, 0, &mints[..1]),
+        &[&heir],
+        ErrorCode::InvalidTokenBasket,
+    );
+    env.execute(finalize, &[&heir]);
+
+    for (mint, amount) in entries {
+        assert_eq!(
+            env.token_balance(&pda::associated_token_account(&heir.pubkey(), &mint, &TOKEN)),
+            amount
+        );
+        assert!(!env.exists(&pda::associated_token_account(&basket, &mint, &TOKEN)));
+    }
+    assert!(!env.exists(&basket));
+}
+
+#[test]
+fn add_token_basket_heir_validates_the_entries() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let mint = env.mint(&TOKEN, 6, false);
+    let freezable = env.mint(&TOKEN, 6, true);
+    for mint in [mint, freezable] {
+        env.mint_to(&mint, &TOKEN, &owner.pubkey(), 1_000);
+    }
+    let add = |entries: &[(Pubkey, u64)]| {
+        instructions::add_token_basket_heir(
+            &owner.pubkey(),
+            &heir,
+            &TOKEN,
+            0,
+            entries,
+            PERIOD,
+            MintRiskPolicy::Reject,
+        )
+    };
+
+    env.assert_error(add(&[]), &[&owner], ErrorCode::InvalidTokenBasket);
+    env.assert_error(add(&[(mint, 1), (mint, 2)]), &[&owner], ErrorCode::InvalidTokenBasket);
+    // One amount more than entries
+    let mut mismatched = add(&[(mint, 1), (freezable, 1)]);
+    mismatched.accounts.truncate(mismatched.accounts.len() - 3);
+    env.assert_error(mismatched, &[&owner], ErrorCode::InvalidTokenBasket);
+
+    env.assert_error(add(&[(mint, 0)]), &[&owner], ErrorCode::InvalidAmount);
+    env.assert_error(add(&[(freezable, 1)]), &[&owner], ErrorCode::MintHasFreezeAuthority);
+
+    // One hooked entry would block the claim of every entry
+    let hooked = env.mint_with_transfer_hook(&Keypair::new().pubkey(), 6);
+    let plain = env.mint(&TOKEN_2022, 6, false);
+    for mint in [hooked, plain] {
+        env.mint_to(&mint, &TOKEN_2022, &owner.pubkey(), 1_000);
+    }
+    env.assert_error(
+        instructions::add_token_basket_heir(
+            &owner.pubkey(),
+            &heir,
+            &TOKEN_2022,
+            0,
+            &[(plain, 1), (hooked, 1)],
+            PERIOD,
+            MintRiskPolicy::Reject,
+        ),
+        &[&owner],
+        ErrorCode::MintHasTransferHook,
+    );
+}
+
+#[test]
+fn cancel_token_basket_heir_returns_every_escrow() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let mints = [env.mint(&TOKEN, 6, false), env.mint(&TOKEN, 6, false)];
+    for mint in &mints {
+        env.mint_to(mint, &TOKEN, &owner.pubkey(), 1_000);
+    }
+    let basket = pda::token_basket_heir(&owner.pubkey(), &heir, 0);
+    env.execute(
+        instructions::add_token_basket_heir(
+            &owner.pubkey(),
+            &heir,
+            &TOKEN,
+            0,
+            &[(mints[0], 300), (mints[1], 400)],
+            PERIOD,
+            MintRiskPolicy::Reject,
+        ),
+        &[&owner],
+    );
+
+    env.execute(
+        instructions::cancel_token_basket_heir(&owner.pubkey(), &heir, &TOKEN, 0, &mints),
+        &[&owner],
+    );
+
+    for mint in &mints {
+        assert_eq!(
+            env.token_balance(&pda::associated_token_account(&owner.pubkey(), mint, &TOKEN)),
+            1_000
+        );
+        assert!(!env.exists(&pda::associated_token_account(&basket, mint, &TOKEN)));
+    }
+    assert!(!env.exists(&basket));
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
@@ -0,0 +1,140 @@
+//! Permissionless cranks: requesting and finalizing claims on the heir's
+//! behalf for the owner's bounty
+
+use gado::ErrorCode;
+use gado_client::{instructions, pda, MintRiskPolicy, UserProfile};
+use gado_program_tests::{TestEnv, DAY, SOL, TOKEN};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+
+#[test]
+fn crank_sol_claim_requests_then_pays_the_heir_and_the_bounty() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let cranker = env.funded();
+    let heir = Keypair::new().pubkey();
+    env.execute(instructions::set_crank_bounty(&owner.pubkey(), 100), &[&owner]);
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), &heir, 0, 10 * SOL, PERIOD),
+        &[&owner],
+    );
+    let sol_heir = pda::sol_heir(&owner.pubkey(), &heir, 0);
+    let crank = instructions::crank_sol_claim(&owner.pubkey(), &heir, 0, &cranker.pubkey());
+
+    env.assert_error(crank.clone(), &[&payer, &cranker], ErrorCode::OwnerStillActive);
+    env.warp(PERIOD + 1);
+    env.execute(crank.clone(), &[&payer, &cranker]);
+    env.assert_error(crank.clone(), &[&payer, &cranker], ErrorCode::NoticePeriodActive);
+    env.warp(NOTICE);
+
+    let before = env.lamports(&cranker.pubkey());
+    env.execute(crank, &[&payer, &cranker]);
+
+    assert_eq!(env.lamports(&heir), 10 * SOL - SOL / 10);
+    assert_eq!(env.lamports(&cranker.pubkey()), before + SOL / 10);
+    assert!(!env.exists(&sol_heir));
+    assert!(!env.exists(&pda::sol_vault(&sol_heir)));
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
+
+#[test]
+fn crank_sol_claim_keeps_a_new_heir_account_rent_exempt() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let cranker = env.funded();
+    let heir = Keypair::new().pubkey();
+    let reserve = env.svm.minimum_balance_for_rent_exemption(0);
+    env.execute(instructions::set_crank_bounty(&owner.pubkey(), 100), &[&owner]);
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), &heir, 0, reserve + 1_000, PERIOD),
+        &[&owner],
+    );
+    let crank = instructions::crank_sol_claim(&owner.pubkey(), &heir, 0, &cranker.pubkey());
+
+    env.warp(PERIOD + 1);
+    env.execute(crank.clone(), &[&payer, &cranker]);
+    env.warp(NOTICE);
+    let before = env.lamports(&cranker.pubkey());
+    env.execute(crank, &[&payer, &cranker]);
+
+    // A full 1% bounty would leave the empty heir account short of rent exemption
+    assert_eq!(env.lamports(&heir), reserve);
+    assert_eq!(env.lamports(&cranker.pubkey()), before + 1_000);
+    assert!(!env.exists(&pda::sol_heir(&owner.pubkey(), &heir, 0)));
+}
+
+#[test]
+fn owner_activity_vetoes_a_cranked_request() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let cranker = env.funded();
+    let heir = Keypair::new().pubkey();
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), &heir, 0, SOL, PERIOD),
+        &[&owner],
+    );
+    let crank = instructions::crank_sol_claim(&owner.pubkey(), &heir, 0, &cranker.pubkey());
+
+    env.warp(PERIOD + 1);
+    env.execute(crank.clone(), &[&cranker]);
+    env.warp(1);
+    env.execute(instructions::heartbeat(&owner.pubkey()), &[&owner]);
+    env.warp(NOTICE);
+
+    // A vetoed request sends the crank back to requesting, which the heartbeat blocks
+    env.assert_error(crank, &[&cranker], ErrorCode::OwnerStillActive);
+    assert_eq!(env.lamports(&heir), 0);
+}
+
+#[test]
+fn crank_token_claim_pays_the_bounty_in_tokens() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let cranker = env.funded();
+    let heir = Keypair::new().pubkey();
+    let mint = env.mint(&TOKEN, 6, false);
+    env.mint_to(&mint, &TOKEN, &owner.pubkey(), 1_000);
+    env.execute(instructions::set_crank_bounty(&owner.pubkey(), 100), &[&owner]);
+    env.execute(
+        instructions::add_token_heir(
+            &owner.pubkey(),
+            &heir,
+            &mint,
+            &TOKEN,
+            0,
+            1_000,
+            PERIOD,
+            MintRiskPolicy::Reject,
+        ),
+        &[&owner],
+    );
+    let token_heir = pda::token_heir(&owner.pubkey(), &heir, &mint, 0);
+    let crank = instructions::crank_token_claim(&owner.pubkey(), &heir, &mint, &TOKEN, 0, &cranker.pubkey());
+
+    env.warp(PERIOD);
+    env.assert_error(crank.clone(), &[&payer, &cranker], ErrorCode::OwnerStillActive);
+    env.warp(1);
+    env.execute(crank.clone(), &[&payer, &cranker]);
+    env.warp(NOTICE - 1);
+    env.assert_error(crank.clone(), &[&payer, &cranker], ErrorCode::NoticePeriodActive);
+    env.warp(1);
+    env.execute(crank, &[&payer, &cranker]);
+
+    assert_eq!(
+        env.token_balance(&pda::associated_token_account(&heir, &mint, &TOKEN)),
+        990
+    );
+    assert_eq!(
+        env.token_balance(&pda::associated_token_account(&cranker.pubkey(), &mint, &TOKEN)),
+        10
+    );
+    assert!(!env.exists(&token_heir));
+    assert!(!env.exists(&pda::associated_token_account(&token_heir, &mint, &TOKEN)));
+}
@@ -0,0 +1,167 @@
+//! The estate-wide profile: heartbeats, liveness keys and claim settings
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use gado::ErrorCode;
+use gado_client::{instructions, pda, LivenessKey, UserProfile};
+use gado_program_tests::{swap_account, TestEnv, DAY};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+#[test]
+fn initialize_user_starts_the_estate_heartbeat() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.owner, owner.pubkey());
+    assert_eq!(profile.total_inheritances, 0);
+    assert_eq!(profile.last_activity, env.now());
+    assert_eq!(profile.claim_notice_period_seconds, 7 * DAY);
+    assert_eq!(profile.crank_bounty_bps, 0);
+
+    // The profile already exists
+    assert!(env
+        .send(&[instructions::initialize_user(&owner.pubkey())], &[&owner])
+        .is_err());
+}
+
+#[test]
+fn heartbeat_records_the_current_time() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    env.warp(90 * DAY);
+
+    env.execute(instructions::heartbeat(&owner.pubkey()), &[&owner]);
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.last_activity, env.now());
+}
+
+#[test]
+fn only_the_owner_can_heartbeat() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let stranger = env.funded();
+
+    let heartbeat = swap_account(
+        instructions::heartbeat(&stranger.pubkey()),
+        &pda::user_profile(&stranger.pubkey()),
+        &pda::user_profile(&owner.pubkey()),
+    );
+    env.assert_error(heartbeat, &[&stranger], AnchorError::ConstraintSeeds);
+}
+
+#[test]
+fn liveness_keys_heartbeat_until_they_expire() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let key = env.funded();
+    let expires_at = env.now() + 60 * DAY;
+
+    env.execute(
+        instructions::register_liveness_key(&owner.pubkey(), &key.pubkey(), expires_at),
+        &[&owner],
+    );
+    let liveness_key: LivenessKey = env.account(&pda::liveness_key(&owner.pubkey(), &key.pubkey()));
+    assert_eq!(liveness_key.owner, owner.pubkey());
+    assert_eq!(liveness_key.key, key.pubkey());
+    assert_eq!(liveness_key.expires_at, expires_at);
+
+    env.warp(30 * DAY);
+    env.execute(
+        instructions::delegated_heartbeat(&owner.pubkey(), &key.pubkey()),
+        &[&key],
+    );
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.last_activity, env.now());
+
+    env.warp(30 * DAY);
+    env.assert_error(
+        instructions::delegated_heartbeat(&owner.pubkey(), &key.pubkey()),
+        &[&key],
+        ErrorCode::LivenessKeyExpired,
+    );
+
+    // Re-registering extends the key
+    env.execute(
+        instructions::register_liveness_key(&owner.pubkey(), &key.pubkey(), 0),
+        &[&owner],
+    );
+    env.warp(365 * DAY);
+    env.execute(
+        instructions::delegated_heartbeat(&owner.pubkey(), &key.pubkey()),
+        &[&key],
+    );
+}
+
+#[test]
+fn register_liveness_key_validates_the_key() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let key = Keypair::new().pubkey();
+
+    env.assert_error(
+        instructions::register_liveness_key(&owner.pubkey(), &key, env.now()),
+        &[&owner],
+        ErrorCode::InvalidExpiry,
+    );
+    env.assert_error(
+        instructions::register_liveness_key(&owner.pubkey(), &owner.pubkey(), 0),
+        &[&owner],
+        ErrorCode::Unauthorized,
+    );
+}
+
+#[test]
+fn revoked_liveness_keys_cannot_heartbeat() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let key = env.funded();
+    let liveness_key = pda::liveness_key(&owner.pubkey(), &key.pubkey());
+
+    env.execute(
+        instructions::register_liveness_key(&owner.pubkey(), &key.pubkey(), 0),
+        &[&owner],
+    );
+    env.execute(
+        instructions::revoke_liveness_key(&owner.pubkey(), &key.pubkey()),
+        &[&owner],
+    );
+    assert!(!env.exists(&liveness_key));
+
+    env.assert_error(
+        instructions::delegated_heartbeat(&owner.pubkey(), &key.pubkey()),
+        &[&key],
+        AnchorError::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn claim_settings_are_bounded() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let profile = pda::user_profile(&owner.pubkey());
+
+    env.execute(
+        instructions::set_claim_notice_period(&owner.pubkey(), 30 * DAY),
+        &[&owner],
+    );
+    assert_eq!(
+        env.account::<UserProfile>(&profile).claim_notice_period_seconds,
+        30 * DAY
+    );
+    for notice_period in [DAY - 1, 30 * DAY + 1] {
+        env.assert_error(
+            instructions::set_claim_notice_period(&owner.pubkey(), notice_period),
+            &[&owner],
+            ErrorCode::InvalidNoticePeriod,
+        );
+    }
+
+    env.execute(instructions::set_crank_bounty(&owner.pubkey(), 100), &[&owner]);
+    assert_eq!(env.account::<UserProfile>(&profile).crank_bounty_bps, 100);
+    env.assert_error(
+        instructions::set_crank_bounty(&owner.pubkey(), 101),
+        &[&owner],
+        ErrorCode::InvalidCrankBounty,
+    );
+}
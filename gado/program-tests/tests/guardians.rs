@@ -0,0 +1,162 @@
+//! Guardian quorums: attesting the owner's death and freezing claims
+
+use gado::ErrorCode;
+use gado_client::{instructions, pda, GuardianSet, Pubkey};
+use gado_program_tests::{TestEnv, DAY, SOL};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 365 * DAY;
+
+/// An owner with a 2-of-3 guardian set and a SOL designation for `heir`
+fn estate(env: &mut TestEnv, heir: &Pubkey) -> (Keypair, [Keypair; 3]) {
+    let owner = env.owner();
+    let guardians = [Keypair::new(), Keypair::new(), Keypair::new()];
+    env.execute(
+        instructions::set_guardians(
+            &owner.pubkey(),
+            guardians.iter().map(|guardian| guardian.pubkey()).collect(),
+            2,
+        ),
+        &[&owner],
+    );
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), heir, 0, SOL, PERIOD),
+        &[&owner],
+    );
+    (owner, guardians)
+}
+
+#[test]
+fn set_guardians_validates_the_set() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let keys: Vec<Pubkey> = (0..11).map(|_| Keypair::new().pubkey()).collect();
+    let set =
+        |guardians: &[Pubkey], threshold| instructions::set_guardians(&owner.pubkey(), guardians.to_vec(), threshold);
+
+    env.assert_error(set(&[], 1), &[&owner], ErrorCode::InvalidGuardianSet);
+    env.assert_error(set(&keys, 1), &[&owner], ErrorCode::InvalidGuardianSet);
+    env.assert_error(
+        set(&[keys[0], owner.pubkey()], 1),
+        &[&owner],
+        ErrorCode::InvalidGuardianSet,
+    );
+    env.assert_error(
+        set(&[keys[0], keys[1], keys[0]], 1),
+        &[&owner],
+        ErrorCode::InvalidGuardianSet,
+    );
+    env.assert_error(set(&keys[..2], 0), &[&owner], ErrorCode::InvalidGuardianThreshold);
+    env.assert_error(set(&keys[..2], 3), &[&owner], ErrorCode::InvalidGuardianThreshold);
+
+    env.execute(set(&keys[..10], 10), &[&owner]);
+    env.execute(set(&keys[..3], 2), &[&owner]);
+    let guardian_set: GuardianSet = env.account(&pda::guardian_set(&owner.pubkey()));
+    assert_eq!(guardian_set.guardians, keys[..3]);
+    assert_eq!(guardian_set.threshold, 2);
+    assert!(!guardian_set.frozen);
+}
+
+#[test]
+fn attested_death_skips_the_inactivity_and_notice_periods() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let heir = env.funded();
+    let (owner, guardians) = estate(&mut env, &heir.pubkey());
+    let [first, second, _] = &guardians;
+    let finalize = instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    env.warp(1);
+
+    let attest = |signers: &[&Keypair]| {
+        let keys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        instructions::attest_deceased(&owner.pubkey(), &keys)
+    };
+    env.assert_error(attest(&[first]), &[&payer, first], ErrorCode::GuardianQuorumNotMet);
+    // Signatures from keys outside the set do not count
+    let outsider = Keypair::new();
+    env.assert_error(
+        attest(&[first, &outsider]),
+        &[&payer, first, &outsider],
+        ErrorCode::GuardianQuorumNotMet,
+    );
+    env.assert_error(finalize.clone(), &[&heir], ErrorCode::ClaimNotRequested);
+
+    env.execute(attest(&[first, second]), &[&payer, first, second]);
+    let guardian_set: GuardianSet = env.account(&pda::guardian_set(&owner.pubkey()));
+    assert_eq!(guardian_set.deceased_attested_at, env.now());
+
+    let before = env.lamports(&heir.pubkey());
+    env.execute(finalize, &[&payer, &heir]);
+    assert_eq!(env.lamports(&heir.pubkey()), before + SOL);
+}
+
+#[test]
+fn owner_activity_voids_an_attestation() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let heir = env.funded();
+    let (owner, guardians) = estate(&mut env, &heir.pubkey());
+    let [first, second, _] = &guardians;
+    env.warp(1);
+    env.execute(
+        instructions::attest_deceased(&owner.pubkey(), &[first.pubkey(), second.pubkey()]),
+        &[&payer, first, second],
+    );
+
+    env.execute(instructions::heartbeat(&owner.pubkey()), &[&owner]);
+    env.assert_error(
+        instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+        ErrorCode::OwnerStillActive,
+    );
+    env.assert_error(
+        instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+        ErrorCode::ClaimNotRequested,
+    );
+}
+
+#[test]
+fn frozen_claims_wait_for_the_guardians() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let heir = env.funded();
+    let (owner, guardians) = estate(&mut env, &heir.pubkey());
+    let [first, second, third] = &guardians;
+    let request = instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    let finalize = instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+
+    env.assert_error(
+        instructions::freeze_claims(&owner.pubkey(), &[first.pubkey()]),
+        &[&payer, first],
+        ErrorCode::GuardianQuorumNotMet,
+    );
+    env.execute(
+        instructions::freeze_claims(&owner.pubkey(), &[first.pubkey(), second.pubkey()]),
+        &[&payer, first, second],
+    );
+    assert!(env.account::<GuardianSet>(&pda::guardian_set(&owner.pubkey())).frozen);
+
+    env.warp(PERIOD + 1);
+    env.assert_error(request.clone(), &[&heir], ErrorCode::ClaimsFrozen);
+
+    env.assert_error(
+        instructions::unfreeze_claims(&owner.pubkey(), &[third.pubkey()]),
+        &[&payer, third],
+        ErrorCode::GuardianQuorumNotMet,
+    );
+    env.execute(
+        instructions::unfreeze_claims(&owner.pubkey(), &[second.pubkey(), third.pubkey()]),
+        &[&payer, second, third],
+    );
+    env.execute(request, &[&heir]);
+
+    // A freeze also holds up a claim that is already requested
+    env.execute(
+        instructions::freeze_claims(&owner.pubkey(), &[first.pubkey(), third.pubkey()]),
+        &[&payer, first, third],
+    );
+    env.warp(7 * DAY);
+    env.assert_error(finalize, &[&heir], ErrorCode::ClaimsFrozen);
+}
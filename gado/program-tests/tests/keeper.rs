@@ -0,0 +1,66 @@
+//! The keeper cranking designations on the program itself, with the clock
+//! warped across the inactivity and notice periods
+
+use std::time::Duration;
+
+use gado_client::{instructions, pda, SolHeir};
+use gado_keeper::{Backoff, Keeper, Step, Summary};
+use gado_program_tests::{KeeperCluster, TestEnv, DAY, SOL};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+
+#[test]
+fn keeper_requests_then_finalizes_a_sol_claim() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let cranker = env.funded();
+    let heir = Keypair::new().pubkey();
+    env.execute(instructions::set_crank_bounty(&owner.pubkey(), 100), &[&owner]);
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), &heir, 0, 10 * SOL, PERIOD),
+        &[&owner],
+    );
+    let sol_heir = pda::sol_heir(&owner.pubkey(), &heir, 0);
+    let cranker_key = cranker.pubkey();
+    let backoff = Backoff {
+        initial: Duration::ZERO,
+        max: Duration::ZERO,
+        attempts: 1,
+    };
+    let keeper = Keeper::new(KeeperCluster::new(env, &[sol_heir]), cranker).with_backoff(backoff);
+    let env = || keeper.cluster().env();
+
+    assert_eq!(keeper.run_once().unwrap(), Summary::default());
+
+    env().warp(PERIOD + 1);
+    let actions = keeper.scan().unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].step, Step::RequestClaim);
+    assert_eq!(keeper.run_once().unwrap().submitted, 1);
+    assert!(env().account::<SolHeir>(&sol_heir).claim_requested_at > 0);
+
+    // Nothing is due while the owner can still veto the request
+    env().warp(NOTICE - 1);
+    assert_eq!(keeper.run_once().unwrap(), Summary::default());
+
+    env().warp(1);
+    let before = env().lamports(&cranker_key);
+    let summary = keeper.run_once().unwrap();
+    assert_eq!(
+        summary,
+        Summary {
+            due: 1,
+            submitted: 1,
+            failed: 0
+        }
+    );
+    assert_eq!(env().lamports(&heir), 10 * SOL - SOL / 10);
+    // The cranker also paid the transaction fee out of the bounty
+    assert!(env().lamports(&cranker_key) > before);
+    assert!(!env().exists(&sol_heir));
+
+    assert_eq!(keeper.run_once().unwrap(), Summary::default());
+}
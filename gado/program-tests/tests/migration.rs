@@ -0,0 +1,252 @@
+//! Growing accounts created by earlier program versions to the current layouts
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use anchor_lang::AccountSerialize;
+use gado::{DesignationKind, ErrorCode, HeirAccountMigrated};
+use gado_client::{instructions, pda, Pubkey, SolHeir, TokenCustody, TokenHeir, UserProfile};
+use gado_program_tests::{event, TestEnv, DAY, SOL};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+/// Fields appended since the first release: `tranche` and `claim_requested_at`
+const HEIR_FIELDS_ADDED: usize = 2 + 8;
+/// `last_activity`, `claim_notice_period_seconds` and `crank_bounty_bps`
+const PROFILE_FIELDS_ADDED: usize = 8 + 8 + 2;
+
+/// Store `account` in the layout it had before the trailing `added` bytes of
+/// fields existed
+fn store_legacy<T: AccountSerialize>(env: &mut TestEnv, address: Pubkey, account: &T, added: usize) {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data.truncate(data.len() - added);
+    env.put(address, gado::ID, data);
+}
+
+fn bump(seeds: &[&[u8]]) -> u8 {
+    Pubkey::find_program_address(seeds, &gado::ID).1
+}
+
+/// A pre-tranche SOL designation escrowing 1 SOL on the account itself, last
+/// touched `idle` seconds ago
+fn legacy_sol_heir(env: &mut TestEnv, owner: &Pubkey, heir: &Pubkey, idle: i64) -> Pubkey {
+    let sol_heir = SolHeir {
+        owner: *owner,
+        heir: *heir,
+        amount: SOL,
+        inactivity_period_seconds: PERIOD,
+        last_activity: env.now() - idle,
+        is_claimed: false,
+        bump: bump(&[b"sol_heir", owner.as_ref(), heir.as_ref()]),
+        tranche: 0,
+        claim_requested_at: 0,
+    };
+    let address = pda::sol_heir(owner, heir, 0);
+    store_legacy(env, address, &sol_heir, HEIR_FIELDS_ADDED);
+    env.svm.airdrop(&address, SOL).unwrap();
+    address
+}
+
+/// A profile from before the estate-wide heartbeat
+fn legacy_profile(env: &mut TestEnv, owner: &Pubkey, total_inheritances: u32) -> Pubkey {
+    let profile = UserProfile {
+        owner: *owner,
+        total_inheritances,
+        bump: bump(&[b"user_profile", owner.as_ref()]),
+        last_activity: 0,
+        claim_notice_period_seconds: 0,
+        crank_bounty_bps: 0,
+    };
+    let address = pda::user_profile(owner);
+    store_legacy(env, address, &profile, PROFILE_FIELDS_ADDED);
+    address
+}
+
+#[test]
+fn migrate_heir_account_grows_legacy_designations() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = Keypair::new().pubkey();
+    let heir = Keypair::new().pubkey();
+    let sol_heir = legacy_sol_heir(&mut env, &owner, &heir, DAY);
+    assert_eq!(env.data(&sol_heir).len(), SolHeir::SPACE - HEIR_FIELDS_ADDED);
+
+    let mint = Keypair::new().pubkey();
+    let token_heir = pda::token_heir(&owner, &heir, &mint, 0);
+    let legacy = TokenHeir {
+        owner,
+        heir,
+        token_mint: mint,
+        amount: 500,
+        inactivity_period_seconds: PERIOD,
+        last_activity: env.now(),
+        is_claimed: false,
+        bump: bump(&[b"token_heir", owner.as_ref(), heir.as_ref(), mint.as_ref()]),
+        tranche: 0,
+        claim_requested_at: 0,
+        custody: TokenCustody::Escrow,
+        share_bps: 0,
+    };
+    // Token designations also predate custody modes and shares
+    store_legacy(&mut env, token_heir, &legacy, HEIR_FIELDS_ADDED + 1 + 2);
+
+    let [sol_event, token_event] = [sol_heir, token_heir].map(|address| {
+        let meta = env.execute(instructions::migrate_heir_account(&address, &payer.pubkey()), &[&payer]);
+        event::<HeirAccountMigrated>(&meta)
+    });
+    assert!(sol_event.kind == DesignationKind::Sol);
+    assert_eq!(
+        (sol_event.designation, sol_event.owner, sol_event.heir),
+        (sol_heir, owner, heir)
+    );
+    assert_eq!((sol_event.amount, sol_event.escrow_moved), (SOL, SOL));
+    assert!(token_event.kind == DesignationKind::Token);
+    assert_eq!((token_event.mint, token_event.amount), (Some(mint), 500));
+    assert_eq!(token_event.escrow_moved, 0);
+
+    assert_eq!(env.data(&sol_heir).len(), SolHeir::SPACE);
+    let migrated: SolHeir = env.account(&sol_heir);
+    assert_eq!((migrated.owner, migrated.heir, migrated.amount), (owner, heir, SOL));
+    assert_eq!((migrated.tranche, migrated.claim_requested_at), (0, 0));
+    // The legacy escrow moved into the vault on top of its rent reserve
+    assert_eq!(
+        env.lamports(&sol_heir),
+        env.svm.minimum_balance_for_rent_exemption(SolHeir::SPACE)
+    );
+    assert_eq!(
+        env.lamports(&pda::sol_vault(&sol_heir)),
+        SOL + env.svm.minimum_balance_for_rent_exemption(0)
+    );
+    assert_eq!(env.data(&token_heir).len(), TokenHeir::SPACE);
+    let migrated: TokenHeir = env.account(&token_heir);
+    assert_eq!((migrated.token_mint, migrated.amount), (mint, 500));
+    assert!(migrated.custody == TokenCustody::Escrow);
+    assert_eq!(
+        env.lamports(&token_heir),
+        env.svm.minimum_balance_for_rent_exemption(TokenHeir::SPACE)
+    );
+
+    // Migrating again is a no-op
+    env.execute(
+        instructions::migrate_heir_account(&sol_heir, &payer.pubkey()),
+        &[&payer],
+    );
+    assert_eq!(env.data(&sol_heir).len(), SolHeir::SPACE);
+}
+
+#[test]
+fn migrated_legacy_escrow_is_paid_to_the_heir() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    let sol_heir = legacy_sol_heir(&mut env, &owner.pubkey(), &heir.pubkey(), 0);
+    env.execute(
+        instructions::migrate_heir_account(&sol_heir, &payer.pubkey()),
+        &[&payer],
+    );
+
+    env.warp(PERIOD + 1);
+    env.execute(
+        instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+    );
+    env.warp(7 * DAY);
+    let before = env.lamports(&heir.pubkey());
+    env.execute(
+        instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&payer, &heir],
+    );
+
+    assert_eq!(env.lamports(&heir.pubkey()), before + SOL);
+    assert!(!env.exists(&sol_heir));
+    assert!(!env.exists(&pda::sol_vault(&sol_heir)));
+}
+
+#[test]
+fn migrate_heir_account_rejects_other_accounts() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+
+    env.assert_error(
+        instructions::migrate_heir_account(&pda::user_profile(&owner.pubkey()), &payer.pubkey()),
+        &[&payer],
+        ErrorCode::InvalidHeirAccount,
+    );
+    env.assert_error(
+        instructions::migrate_heir_account(&payer.pubkey(), &payer.pubkey()),
+        &[&payer],
+        AnchorError::ConstraintOwner,
+    );
+}
+
+#[test]
+fn migrate_user_profile_seeds_the_heartbeat_from_the_designations() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.funded();
+    let profile = legacy_profile(&mut env, &owner.pubkey(), 2);
+    let stale = legacy_sol_heir(&mut env, &owner.pubkey(), &Keypair::new().pubkey(), PERIOD);
+    let heir = env.funded();
+    let recent = legacy_sol_heir(&mut env, &owner.pubkey(), &heir.pubkey(), DAY);
+    let recent_activity = env.now() - DAY;
+
+    // Designations must be migrated before they can vouch for the owner
+    let migrate = instructions::migrate_user_profile(&owner.pubkey(), &payer.pubkey(), &[stale, recent]);
+    env.assert_error(migrate.clone(), &[&payer], AnchorError::AccountDidNotDeserialize);
+    for address in [stale, recent] {
+        env.execute(instructions::migrate_heir_account(&address, &payer.pubkey()), &[&payer]);
+    }
+    env.execute(migrate, &[&payer]);
+
+    assert_eq!(env.data(&profile).len(), UserProfile::SPACE);
+    let migrated: UserProfile = env.account(&profile);
+    assert_eq!(migrated.total_inheritances, 2);
+    assert_eq!(migrated.last_activity, recent_activity);
+    assert_eq!(migrated.crank_bounty_bps, 0);
+
+    // The seeded heartbeat governs claims from here on
+    let request = instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    env.warp(PERIOD - DAY);
+    env.assert_error(request.clone(), &[&heir], ErrorCode::OwnerStillActive);
+    env.warp(1);
+    env.execute(request, &[&heir]);
+    env.execute(instructions::heartbeat(&owner.pubkey()), &[&owner]);
+    assert_eq!(env.account::<UserProfile>(&profile).last_activity, env.now());
+
+    // Migrating again never moves the heartbeat backwards
+    env.execute(
+        instructions::migrate_user_profile(&owner.pubkey(), &payer.pubkey(), &[recent]),
+        &[&payer],
+    );
+    assert_eq!(env.account::<UserProfile>(&profile).last_activity, env.now());
+}
+
+#[test]
+fn migrate_user_profile_only_accepts_the_owners_designations() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = Keypair::new().pubkey();
+    let profile = legacy_profile(&mut env, &owner, 1);
+    let someone_else = Keypair::new().pubkey();
+    let theirs = legacy_sol_heir(&mut env, &someone_else, &Keypair::new().pubkey(), 0);
+    env.execute(instructions::migrate_heir_account(&theirs, &payer.pubkey()), &[&payer]);
+
+    env.assert_error(
+        instructions::migrate_user_profile(&owner, &payer.pubkey(), &[theirs]),
+        &[&payer],
+        ErrorCode::Unauthorized,
+    );
+    env.assert_error(
+        instructions::migrate_user_profile(&owner, &payer.pubkey(), &[profile]),
+        &[&payer],
+        ErrorCode::InvalidHeirAccount,
+    );
+    // Accounts of other programs
+    env.assert_error(
+        instructions::migrate_user_profile(&owner, &payer.pubkey(), &[payer.pubkey()]),
+        &[&payer],
+        ErrorCode::Unauthorized,
+    );
+}
@@ -0,0 +1,362 @@
+//! SOL designations: escrow in the vault, amount changes, claims across the
+//! inactivity and notice periods, cancellation and legacy claimed accounts
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use anchor_lang::system_program;
+use gado::{ClaimedDesignationClosed, DesignationKind, ErrorCode, MAX_SOL_AMOUNT};
+use gado_client::{instructions, pda, Pubkey, SolHeir, UserProfile};
+use gado_program_tests::{event, swap_account, TestEnv, DAY, SOL};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+
+/// Escrow `amount` for `heir`, paid for by `payer` so the owner's balance only
+/// moves by what the program transfers
+fn add(env: &mut TestEnv, payer: &Keypair, owner: &Keypair, heir: &Pubkey, amount: u64) -> Pubkey {
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), heir, 0, amount, PERIOD),
+        &[payer, owner],
+    );
+    pda::sol_heir(&owner.pubkey(), heir, 0)
+}
+
+/// A designation claimed before claims closed their accounts
+fn legacy_claimed(env: &mut TestEnv, owner: &Pubkey, heir: &Pubkey) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"sol_heir", owner.as_ref(), heir.as_ref()], &gado::ID);
+    let sol_heir = SolHeir {
+        owner: *owner,
+        heir: *heir,
+        amount: SOL,
+        inactivity_period_seconds: PERIOD,
+        last_activity: env.now() - PERIOD,
+        is_claimed: true,
+        bump,
+        tranche: 0,
+        claim_requested_at: 0,
+    };
+    env.store(address, &sol_heir);
+    env.put(pda::sol_vault(&address), system_program::ID, Vec::new());
+    address
+}
+
+#[test]
+fn add_sol_heir_escrows_the_bequest_in_the_vault() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let before = env.lamports(&owner.pubkey());
+
+    let sol_heir = add(&mut env, &payer, &owner, &heir, 2 * SOL);
+
+    let designation: SolHeir = env.account(&sol_heir);
+    assert_eq!(designation.owner, owner.pubkey());
+    assert_eq!(designation.heir, heir);
+    assert_eq!(designation.amount, 2 * SOL);
+    assert_eq!(designation.inactivity_period_seconds, PERIOD);
+    assert_eq!(designation.last_activity, env.now());
+    assert_eq!(designation.claim_requested_at, 0);
+    assert!(!designation.is_claimed);
+
+    let reserve = env.svm.minimum_balance_for_rent_exemption(0);
+    assert_eq!(env.lamports(&pda::sol_vault(&sol_heir)), 2 * SOL + reserve);
+    assert_eq!(
+        env.lamports(&owner.pubkey()),
+        before - 2 * SOL - reserve - env.lamports(&sol_heir)
+    );
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 1);
+}
+
+#[test]
+fn add_sol_heir_validates_its_arguments() {
+    let mut env = TestEnv::new();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let add_heir = |heir: &Pubkey, amount, period| instructions::add_sol_heir(&owner.pubkey(), heir, 0, amount, period);
+
+    env.assert_error(add_heir(&heir, 0, PERIOD), &[&owner], ErrorCode::InvalidAmount);
+    env.assert_error(
+        add_heir(&heir, MAX_SOL_AMOUNT + 1, PERIOD),
+        &[&owner],
+        ErrorCode::AmountTooLarge,
+    );
+    env.assert_error(add_heir(&heir, SOL, 0), &[&owner], ErrorCode::InvalidInactivityPeriod);
+    env.assert_error(
+        add_heir(&heir, SOL, DAY - 1),
+        &[&owner],
+        ErrorCode::InactivityPeriodTooShort,
+    );
+    env.assert_error(
+        add_heir(&heir, SOL, 365 * DAY + 1),
+        &[&owner],
+        ErrorCode::InactivityPeriodTooLong,
+    );
+    env.assert_error(
+        add_heir(&owner.pubkey(), SOL, PERIOD),
+        &[&owner],
+        ErrorCode::SelfInheritanceNotAllowed,
+    );
+    let reserve = env.svm.minimum_balance_for_rent_exemption(0);
+    env.assert_error(
+        add_heir(&heir, reserve - 1, PERIOD),
+        &[&owner],
+        ErrorCode::BequestBelowRentExemption,
+    );
+
+    env.execute(add_heir(&heir, SOL, DAY), &[&owner]);
+    env.execute(
+        instructions::add_sol_heir(&owner.pubkey(), &heir, 1, SOL, 365 * DAY),
+        &[&owner],
+    );
+    assert!(env.exists(&pda::sol_heir(&owner.pubkey(), &heir, 1)));
+}
+
+#[test]
+fn claim_opens_after_the_inactivity_period_and_pays_after_the_notice() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    let before = env.lamports(&owner.pubkey());
+    let sol_heir = add(&mut env, &payer, &owner, &heir.pubkey(), 5 * SOL);
+    let request = instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    let finalize = instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+
+    env.warp(PERIOD);
+    env.assert_error(request.clone(), &[&heir], ErrorCode::OwnerStillActive);
+    env.warp(1);
+    env.execute(request.clone(), &[&heir]);
+    assert_eq!(env.account::<SolHeir>(&sol_heir).claim_requested_at, env.now());
+    env.assert_error(request, &[&heir], ErrorCode::ClaimAlreadyRequested);
+
+    env.assert_error(finalize.clone(), &[&payer, &heir], ErrorCode::NoticePeriodActive);
+    env.warp(NOTICE - 1);
+    env.assert_error(finalize.clone(), &[&payer, &heir], ErrorCode::NoticePeriodActive);
+    env.warp(1);
+    let heir_before = env.lamports(&heir.pubkey());
+    env.execute(finalize, &[&payer, &heir]);
+
+    assert_eq!(env.lamports(&heir.pubkey()), heir_before + 5 * SOL);
+    assert!(!env.exists(&sol_heir));
+    assert!(!env.exists(&pda::sol_vault(&sol_heir)));
+    // The vault reserve and the rent come back to the owner
+    assert_eq!(env.lamports(&owner.pubkey()), before - 5 * SOL);
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
+
+#[test]
+fn finalize_sol_claim_requires_a_request() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    add(&mut env, &payer, &owner, &heir.pubkey(), SOL);
+
+    env.warp(PERIOD + NOTICE + 1);
+    env.assert_error(
+        instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+        ErrorCode::ClaimNotRequested,
+    );
+}
+
+#[test]
+fn owner_activity_vetoes_a_pending_claim() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    add(&mut env, &payer, &owner, &heir.pubkey(), SOL);
+    let request = instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+    let finalize = instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0);
+
+    env.warp(PERIOD + 1);
+    env.execute(request.clone(), &[&heir]);
+    env.warp(DAY);
+    env.execute(instructions::heartbeat(&owner.pubkey()), &[&owner]);
+    env.warp(NOTICE);
+    env.assert_error(finalize.clone(), &[&heir], ErrorCode::ClaimVetoed);
+
+    // The veto restarts the inactivity period; after it a new request replaces the old one
+    env.assert_error(request.clone(), &[&heir], ErrorCode::OwnerStillActive);
+    env.warp(PERIOD - NOTICE + 1);
+    env.execute(request, &[&heir]);
+    env.warp(NOTICE);
+    env.execute(finalize, &[&heir]);
+}
+
+#[test]
+fn update_sol_activity_withdraws_a_pending_request() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    let sol_heir = add(&mut env, &payer, &owner, &heir.pubkey(), SOL);
+
+    env.warp(PERIOD + 1);
+    env.execute(
+        instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+    );
+    env.execute(
+        instructions::update_sol_activity(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&owner],
+    );
+
+    let designation: SolHeir = env.account(&sol_heir);
+    assert_eq!(designation.last_activity, env.now());
+    assert_eq!(designation.claim_requested_at, 0);
+    env.warp(NOTICE);
+    env.assert_error(
+        instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+        ErrorCode::ClaimNotRequested,
+    );
+}
+
+#[test]
+fn only_the_heir_can_claim() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let stranger = env.funded();
+    let sol_heir = add(&mut env, &payer, &owner, &heir, SOL);
+    env.warp(PERIOD + 1);
+
+    let request = swap_account(
+        instructions::request_sol_claim(&owner.pubkey(), &stranger.pubkey(), 0),
+        &pda::sol_heir(&owner.pubkey(), &stranger.pubkey(), 0),
+        &sol_heir,
+    );
+    env.assert_error(request, &[&stranger], AnchorError::ConstraintSeeds);
+}
+
+#[test]
+fn amount_changes_move_lamports_through_the_vault() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    let sol_heir = add(&mut env, &payer, &owner, &heir.pubkey(), 2 * SOL);
+    let vault = pda::sol_vault(&sol_heir);
+    let vault_before = env.lamports(&vault);
+    let increase = |amount| instructions::increase_sol_amount(&owner.pubkey(), &heir.pubkey(), 0, amount);
+    let decrease = |amount| instructions::decrease_sol_amount(&owner.pubkey(), &heir.pubkey(), 0, amount);
+
+    env.warp(PERIOD + 1);
+    env.execute(
+        instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+    );
+    env.execute(increase(SOL), &[&owner]);
+    let designation: SolHeir = env.account(&sol_heir);
+    assert_eq!(designation.amount, 3 * SOL);
+    assert_eq!(designation.claim_requested_at, 0);
+    assert_eq!(env.lamports(&vault), vault_before + SOL);
+
+    env.execute(decrease(2 * SOL), &[&owner]);
+    assert_eq!(env.account::<SolHeir>(&sol_heir).amount, SOL);
+    assert_eq!(env.lamports(&vault), vault_before - SOL);
+
+    env.assert_error(increase(0), &[&owner], ErrorCode::InvalidAmount);
+    env.assert_error(decrease(0), &[&owner], ErrorCode::InvalidAmount);
+    env.assert_error(increase(MAX_SOL_AMOUNT), &[&owner], ErrorCode::AmountTooLarge);
+    // Withdrawing everything is a cancellation
+    env.assert_error(decrease(SOL), &[&owner], ErrorCode::InsufficientEscrowBalance);
+    let reserve = env.svm.minimum_balance_for_rent_exemption(0);
+    env.assert_error(
+        decrease(SOL - reserve + 1),
+        &[&owner],
+        ErrorCode::BequestBelowRentExemption,
+    );
+}
+
+#[test]
+fn cancel_sol_heir_refunds_the_escrow_and_rent() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    let before = env.lamports(&owner.pubkey());
+    let sol_heir = add(&mut env, &payer, &owner, &heir, 3 * SOL);
+
+    env.execute(
+        instructions::cancel_sol_heir(&owner.pubkey(), &heir, 0),
+        &[&payer, &owner],
+    );
+
+    assert!(!env.exists(&sol_heir));
+    assert!(!env.exists(&pda::sol_vault(&sol_heir)));
+    assert_eq!(env.lamports(&owner.pubkey()), before);
+    let profile: UserProfile = env.account(&pda::user_profile(&owner.pubkey()));
+    assert_eq!(profile.total_inheritances, 0);
+}
+
+#[test]
+fn legacy_claimed_designations_can_only_be_closed() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = env.funded();
+    let sol_heir = legacy_claimed(&mut env, &owner.pubkey(), &heir.pubkey());
+    let rent = env.lamports(&sol_heir) + env.lamports(&pda::sol_vault(&sol_heir));
+
+    env.assert_error(
+        instructions::request_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+        ErrorCode::AlreadyClaimed,
+    );
+    env.assert_error(
+        instructions::finalize_sol_claim(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&heir],
+        ErrorCode::AlreadyClaimed,
+    );
+    env.assert_error(
+        instructions::increase_sol_amount(&owner.pubkey(), &heir.pubkey(), 0, SOL),
+        &[&owner],
+        ErrorCode::AlreadyClaimed,
+    );
+    env.assert_error(
+        instructions::cancel_sol_heir(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&owner],
+        ErrorCode::AlreadyClaimed,
+    );
+
+    // Anyone can close it; the owner gets the rent
+    let before = env.lamports(&owner.pubkey());
+    let meta = env.execute(
+        instructions::close_claimed_sol_heir(&owner.pubkey(), &heir.pubkey(), 0),
+        &[&payer],
+    );
+    assert!(!env.exists(&sol_heir));
+    assert_eq!(env.lamports(&owner.pubkey()), before + rent);
+
+    let closed: ClaimedDesignationClosed = event(&meta);
+    assert_eq!(closed.designation, sol_heir);
+    assert!(closed.kind == DesignationKind::Sol);
+    assert_eq!(
+        (closed.owner, closed.heir, closed.mint),
+        (owner.pubkey(), heir.pubkey(), None)
+    );
+    assert_eq!(closed.timestamp, env.now());
+}
+
+#[test]
+fn close_claimed_sol_heir_rejects_live_designations() {
+    let mut env = TestEnv::new();
+    let payer = env.funded();
+    let owner = env.owner();
+    let heir = Keypair::new().pubkey();
+    add(&mut env, &payer, &owner, &heir, SOL);
+
+    env.assert_error(
+        instructions::close_claimed_sol_heir(&owner.pubkey(), &heir, 0),
+        &[&payer],
+        ErrorCode::NotClaimed,
+    );
+}
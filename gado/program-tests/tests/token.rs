@@ -0,0 +1,587 @@
+//! Token designations in each custody mode: escrowed, delegated from the
+//! owner's account, and shares of the owner's balance
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use anchor_spl::token_interface::spl_token_2022::instruction as token_instruction;
+use anchor_spl::token_interface::TokenAccount;
+use gado::{ClaimedDesignationClosed, DesignationKind, ErrorCode, MAX_TOKEN_AMOUNT};
+use gado_client::{
+    instructions, pda, Instruction, MintRiskPolicy, Pubkey, TokenCustody, TokenEstate, TokenHeir, UserProfile,
+};
+use gado_program_tests::{event, swap_account, TestEnv, DAY, TOKEN, TOKEN_2022};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+
+struct Estate {
+    owner: Keypair,
+    mint: Pubkey,
+    token_program: Pubkey,
+    /// The owner's associated token account, holding 1000 tokens
+    owner_account: Pubkey,
+}
+
+impl Estate {
+    fn new(env: &mut TestEnv, token_program: Pubkey, freezable: bool) -> Self {
+        let owner = env.owner();
+        let mint = env.mint(&token_program, 6, freezable);
+        let owner_account = env.mint_to(&mint, &token_program, &owner.pubkey(), 1_000);
+        Self {
+            owner,
+            mint,
+            token_program,
+            owner_account,
+        }
+    }
+
+    fn add_token_heir(&self, heir: &Pubkey, tranche: u16, amount: u64, policy: MintRiskPolicy) -> Instruction {
+        instructions::add_token_heir(
+            &self.owner.pubkey(),
+            heir,
+            &self.mint,
+            &self.token_program,
+            tranche,
+            amount,
+            PERIOD,
+            policy,
+        )
+    }
+
+    fn add_delegated_token_heir(&self, heir: &Pubkey, tranche: u16, amount: u64) -> Instruction {
+        instructions::add_delegated_token_heir(
+            &self.owner.pubkey(),
+            heir,
+            &self.mint,
+            &self.token_program,
+            tranche,
+            amount,
+            PERIOD,
+            MintRiskPolicy::Reject,
+        )
+    }
+
+    fn add_token_share_heir(&self, heir: &Pubkey, tranche: u16, share_bps: u16) -> Instruction {
+        instructions::add_token_share_heir(
+            &self.owner.pubkey(),
+            heir,
+            &self.mint,
+            &self.token_program,
+            tranche,
+            share_bps,
+            PERIOD,
+            MintRiskPolicy::Reject,
+        )
+    }
+
+    fn request(&self, env: &mut TestEnv, heir: &Keypair) {
+        env.execute(
+            instructions::request_token_claim(&self.owner.pubkey(), &heir.pubkey(), &self.mint, 0),
+            &[heir],
+        );
+    }
+
+    fn heir_account(&self, heir: &Pubkey) -> Pubkey {
+        pda::associated_token_account(heir, &self.mint, &self.token_program)
+    }
+
+    fn delegate(&self, env: &TestEnv) -> Option<Pubkey> {
+        env.account::<TokenAccount>(&self.owner_account).delegate.into()
+    }
+}
+
+#[test]
+fn add_token_heir_escrows_the_tokens() {
+    for token_program in [TOKEN, TOKEN_2022] {
+        let mut env = TestEnv::new();
+        let estate = Estate::new(&mut env, token_program, false);
+        let heir = Keypair::new().pubkey();
+
+        env.execute(
+            estate.add_token_heir(&heir, 0, 400, MintRiskPolicy::Reject),
+            &[&estate.owner],
+        );
+
+        let token_heir = pda::token_heir(&estate.owner.pubkey(), &heir, &estate.mint, 0);
+        let designation: TokenHeir = env.account(&token_heir);
+        assert_eq!(designation.token_mint, estate.mint);
+        assert_eq!(designation.amount, 400);
+        assert!(designation.custody == TokenCustody::Escrow);
+        let escrow = pda::associated_token_account(&token_heir, &estate.mint, &token_program);
+        assert_eq!(env.token_balance(&escrow), 400);
+        assert_eq!(env.token_balance(&estate.owner_account), 600);
+    }
+}
+
+#[test]
+fn add_token_heir_validates_its_arguments() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let owner = estate.owner.pubkey();
+    let heir = Keypair::new().pubkey();
+    let add = |amount, period| {
+        instructions::add_token_heir(
+            &owner,
+            &heir,
+            &estate.mint,
+            &TOKEN,
+            0,
+            amount,
+            period,
+            MintRiskPolicy::Reject,
+        )
+    };
+
+    env.assert_error(add(0, PERIOD), &[&estate.owner], ErrorCode::InvalidAmount);
+    env.assert_error(
+        add(MAX_TOKEN_AMOUNT + 1, PERIOD),
+        &[&estate.owner],
+        ErrorCode::AmountTooLarge,
+    );
+    env.assert_error(add(400, 0), &[&estate.owner], ErrorCode::InvalidInactivityPeriod);
+    env.assert_error(add(400, DAY - 1), &[&estate.owner], ErrorCode::InactivityPeriodTooShort);
+    env.assert_error(
+        add(400, 365 * DAY + 1),
+        &[&estate.owner],
+        ErrorCode::InactivityPeriodTooLong,
+    );
+    env.assert_error(
+        estate.add_token_heir(&owner, 0, 400, MintRiskPolicy::Reject),
+        &[&estate.owner],
+        ErrorCode::SelfInheritanceNotAllowed,
+    );
+
+    // The source must be the owner's account of the designated mint
+    let stranger_account = env.mint_to(&estate.mint, &TOKEN, &heir, 1_000);
+    env.assert_error(
+        swap_account(add(400, PERIOD), &estate.owner_account, &stranger_account),
+        &[&estate.owner],
+        ErrorCode::Unauthorized,
+    );
+    let other_mint = env.mint(&TOKEN, 6, false);
+    let other_account = env.mint_to(&other_mint, &TOKEN, &owner, 1_000);
+    env.assert_error(
+        swap_account(add(400, PERIOD), &estate.owner_account, &other_account),
+        &[&estate.owner],
+        ErrorCode::InvalidMint,
+    );
+}
+
+#[test]
+fn mint_risk_policy_decides_on_issuer_powers() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, true);
+    let heir = Keypair::new().pubkey();
+
+    env.assert_error(
+        estate.add_token_heir(&heir, 0, 100, MintRiskPolicy::Reject),
+        &[&estate.owner],
+        ErrorCode::MintHasFreezeAuthority,
+    );
+    env.execute(
+        estate.add_token_heir(&heir, 0, 100, MintRiskPolicy::Warn),
+        &[&estate.owner],
+    );
+    env.execute(
+        estate.add_token_heir(&heir, 1, 100, MintRiskPolicy::Allow),
+        &[&estate.owner],
+    );
+
+    let owner = env.owner();
+    let mint = env.mint_with_permanent_delegate(&Keypair::new().pubkey());
+    env.mint_to(&mint, &TOKEN_2022, &owner.pubkey(), 1_000);
+    let add = |policy| instructions::add_token_heir(&owner.pubkey(), &heir, &mint, &TOKEN_2022, 0, 100, PERIOD, policy);
+    env.assert_error(
+        add(MintRiskPolicy::Reject),
+        &[&owner],
+        ErrorCode::MintHasPermanentDelegate,
+    );
+    env.execute(add(MintRiskPolicy::Allow), &[&owner]);
+}
+
+#[test]
+fn escrowed_claim_pays_the_heir_after_the_notice() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_token_heir(&heir.pubkey(), 0, 400, MintRiskPolicy::Reject),
+        &[&estate.owner],
+    );
+    let token_heir = pda::token_heir(&owner, &heir.pubkey(), &estate.mint, 0);
+    let escrow = pda::associated_token_account(&token_heir, &estate.mint, &TOKEN);
+    let finalize = instructions::finalize_token_claim(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0);
+
+    env.warp(PERIOD);
+    env.assert_error(
+        instructions::request_token_claim(&owner, &heir.pubkey(), &estate.mint, 0),
+        &[&heir],
+        ErrorCode::OwnerStillActive,
+    );
+    env.warp(1);
+    estate.request(&mut env, &heir);
+    env.warp(NOTICE - 1);
+    env.assert_error(finalize.clone(), &[&heir], ErrorCode::NoticePeriodActive);
+    env.warp(1);
+    env.execute(finalize, &[&heir]);
+
+    assert_eq!(env.token_balance(&estate.heir_account(&heir.pubkey())), 400);
+    assert!(!env.exists(&token_heir));
+    assert!(!env.exists(&escrow));
+    let profile: UserProfile = env.account(&pda::user_profile(&owner));
+    assert_eq!(profile.total_inheritances, 0);
+}
+
+#[test]
+fn frozen_escrows_cannot_be_claimed_or_cancelled() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, true);
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_token_heir(&heir.pubkey(), 0, 400, MintRiskPolicy::Allow),
+        &[&estate.owner],
+    );
+    let token_heir = pda::token_heir(&owner, &heir.pubkey(), &estate.mint, 0);
+    env.freeze(
+        &pda::associated_token_account(&token_heir, &estate.mint, &TOKEN),
+        &estate.mint,
+        &TOKEN,
+    );
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &heir);
+    env.warp(NOTICE);
+    env.assert_error(
+        instructions::finalize_token_claim(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+        &[&heir],
+        ErrorCode::EscrowFrozen,
+    );
+    env.assert_error(
+        instructions::cancel_token_heir(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+        &[&estate.owner],
+        ErrorCode::EscrowFrozen,
+    );
+}
+
+#[test]
+fn escrow_amount_changes_and_activity() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_token_heir(&heir.pubkey(), 0, 400, MintRiskPolicy::Reject),
+        &[&estate.owner],
+    );
+    let token_heir = pda::token_heir(&owner, &heir.pubkey(), &estate.mint, 0);
+    let escrow = pda::associated_token_account(&token_heir, &estate.mint, &TOKEN);
+    let increase =
+        |amount| instructions::increase_token_amount(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0, amount);
+    let decrease =
+        |amount| instructions::decrease_token_amount(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0, amount);
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &heir);
+    env.execute(increase(100), &[&estate.owner]);
+    let designation: TokenHeir = env.account(&token_heir);
+    assert_eq!(designation.amount, 500);
+    assert_eq!(designation.claim_requested_at, 0);
+    assert_eq!(env.token_balance(&escrow), 500);
+
+    env.execute(decrease(200), &[&estate.owner]);
+    assert_eq!(env.account::<TokenHeir>(&token_heir).amount, 300);
+    assert_eq!(env.token_balance(&estate.owner_account), 700);
+
+    env.assert_error(increase(0), &[&estate.owner], ErrorCode::InvalidAmount);
+    env.assert_error(increase(MAX_TOKEN_AMOUNT), &[&estate.owner], ErrorCode::AmountTooLarge);
+    env.assert_error(decrease(300), &[&estate.owner], ErrorCode::InsufficientEscrowBalance);
+    let stranger_account = env.mint_to(&estate.mint, &TOKEN, &heir.pubkey(), 100);
+    env.assert_error(
+        swap_account(increase(100), &estate.owner_account, &stranger_account),
+        &[&estate.owner],
+        ErrorCode::Unauthorized,
+    );
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &heir);
+    env.execute(
+        instructions::update_token_activity(&owner, &heir.pubkey(), &estate.mint, 0),
+        &[&estate.owner],
+    );
+    let designation: TokenHeir = env.account(&token_heir);
+    assert_eq!(designation.last_activity, env.now());
+    assert_eq!(designation.claim_requested_at, 0);
+}
+
+#[test]
+fn cancel_token_heir_returns_the_escrow() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN_2022, false);
+    let heir = Keypair::new().pubkey();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_token_heir(&heir, 0, 400, MintRiskPolicy::Reject),
+        &[&estate.owner],
+    );
+    let token_heir = pda::token_heir(&owner, &heir, &estate.mint, 0);
+
+    env.execute(
+        instructions::cancel_token_heir(&owner, &heir, &estate.mint, &TOKEN_2022, 0),
+        &[&estate.owner],
+    );
+
+    assert_eq!(env.token_balance(&estate.owner_account), 1_000);
+    assert!(!env.exists(&token_heir));
+    assert!(!env.exists(&pda::associated_token_account(&token_heir, &estate.mint, &TOKEN_2022)));
+}
+
+#[test]
+fn legacy_claimed_token_designations_close_once_empty() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let payer = env.funded();
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+
+    let legacy = |env: &mut TestEnv, tranche: u16, left_in_escrow: u64| {
+        let (address, bump) = Pubkey::find_program_address(
+            &[
+                b"token_heir",
+                owner.as_ref(),
+                heir.pubkey().as_ref(),
+                estate.mint.as_ref(),
+                &gado::tranche_seed(tranche),
+            ],
+            &gado::ID,
+        );
+        let token_heir = TokenHeir {
+            owner,
+            heir: heir.pubkey(),
+            token_mint: estate.mint,
+            amount: 400,
+            inactivity_period_seconds: PERIOD,
+            last_activity: env.now() - PERIOD,
+            is_claimed: true,
+            bump,
+            tranche,
+            claim_requested_at: 0,
+            custody: TokenCustody::Escrow,
+            share_bps: 0,
+        };
+        env.store(address, &token_heir);
+        env.mint_to(&estate.mint, &TOKEN, &address, left_in_escrow)
+    };
+    legacy(&mut env, 1, 5);
+    let escrow = legacy(&mut env, 2, 0);
+
+    env.assert_error(
+        instructions::request_token_claim(&owner, &heir.pubkey(), &estate.mint, 2),
+        &[&heir],
+        ErrorCode::AlreadyClaimed,
+    );
+    env.assert_error(
+        instructions::close_claimed_token_heir(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 1),
+        &[&payer],
+        ErrorCode::EscrowNotEmpty,
+    );
+    let meta = env.execute(
+        instructions::close_claimed_token_heir(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 2),
+        &[&payer],
+    );
+    assert!(!env.exists(&pda::token_heir(&owner, &heir.pubkey(), &estate.mint, 2)));
+    assert!(!env.exists(&escrow));
+    let closed: ClaimedDesignationClosed = event(&meta);
+    assert_eq!(
+        closed.designation,
+        pda::token_heir(&owner, &heir.pubkey(), &estate.mint, 2)
+    );
+    assert!(closed.kind == DesignationKind::Token);
+    assert_eq!((closed.mint, closed.amount), (Some(estate.mint), 400));
+
+    env.execute(
+        estate.add_token_heir(&heir.pubkey(), 0, 400, MintRiskPolicy::Reject),
+        &[&estate.owner],
+    );
+    env.assert_error(
+        instructions::close_claimed_token_heir(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+        &[&payer],
+        ErrorCode::NotClaimed,
+    );
+}
+
+#[test]
+fn delegated_claim_pulls_from_the_owner_account() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_delegated_token_heir(&heir.pubkey(), 0, 300),
+        &[&estate.owner],
+    );
+    let token_heir = pda::token_heir(&owner, &heir.pubkey(), &estate.mint, 0);
+    assert!(env.account::<TokenHeir>(&token_heir).custody == TokenCustody::Delegate);
+    assert_eq!(estate.delegate(&env), Some(token_heir));
+    // The owner keeps the tokens until the claim
+    assert_eq!(env.token_balance(&estate.owner_account), 1_000);
+
+    // A token account has a single delegate
+    let other_heir = Keypair::new().pubkey();
+    env.assert_error(
+        estate.add_delegated_token_heir(&other_heir, 0, 100),
+        &[&estate.owner],
+        ErrorCode::TokenAccountAlreadyDelegated,
+    );
+    env.assert_error(
+        estate.add_token_share_heir(&other_heir, 0, 1_000),
+        &[&estate.owner],
+        ErrorCode::TokenAccountAlreadyDelegated,
+    );
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &heir);
+    env.warp(NOTICE);
+    env.execute(
+        instructions::finalize_delegated_token_claim(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+        &[&heir],
+    );
+    assert_eq!(env.token_balance(&estate.heir_account(&heir.pubkey())), 300);
+    assert_eq!(env.token_balance(&estate.owner_account), 700);
+    assert!(!env.exists(&token_heir));
+}
+
+#[test]
+fn revoking_the_allowance_defeats_a_delegated_claim() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_delegated_token_heir(&heir.pubkey(), 0, 300),
+        &[&estate.owner],
+    );
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &heir);
+    let revoke = token_instruction::revoke(&TOKEN, &estate.owner_account, &owner, &[]).unwrap();
+    env.send(&[revoke], &[&estate.owner]).unwrap();
+    env.warp(NOTICE);
+    env.assert_error(
+        instructions::finalize_delegated_token_claim(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+        &[&heir],
+        ErrorCode::DelegateAllowanceRevoked,
+    );
+}
+
+#[test]
+fn finalizing_with_the_wrong_custody_fails() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let heir = env.funded();
+    let owner = estate.owner.pubkey();
+    env.execute(
+        estate.add_token_heir(&heir.pubkey(), 0, 400, MintRiskPolicy::Reject),
+        &[&estate.owner],
+    );
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &heir);
+    env.warp(NOTICE);
+    env.assert_error(
+        instructions::finalize_delegated_token_claim(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+        &[&heir],
+        ErrorCode::WrongTokenCustody,
+    );
+}
+
+#[test]
+fn cancel_delegated_token_heir_revokes_the_allowance() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let heir = Keypair::new().pubkey();
+    let owner = estate.owner.pubkey();
+    env.execute(estate.add_delegated_token_heir(&heir, 0, 300), &[&estate.owner]);
+
+    env.execute(
+        instructions::cancel_delegated_token_heir(&owner, &heir, &estate.mint, &TOKEN, 0),
+        &[&estate.owner],
+    );
+    assert_eq!(estate.delegate(&env), None);
+    assert!(!env.exists(&pda::token_heir(&owner, &heir, &estate.mint, 0)));
+}
+
+#[test]
+fn shares_are_paid_from_one_snapshot_of_the_balance() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let first = env.funded();
+    let second = env.funded();
+    let owner = estate.owner.pubkey();
+    let estate_address = pda::token_estate(&owner, &estate.mint);
+
+    env.execute(estate.add_token_share_heir(&first.pubkey(), 0, 5_000), &[&estate.owner]);
+    env.execute(
+        estate.add_token_share_heir(&second.pubkey(), 0, 2_500),
+        &[&estate.owner],
+    );
+    assert_eq!(env.account::<TokenEstate>(&estate_address).total_share_bps, 7_500);
+    assert_eq!(estate.delegate(&env), Some(estate_address));
+
+    let third = Keypair::new().pubkey();
+    for share_bps in [0, 10_001, 2_501] {
+        env.assert_error(
+            estate.add_token_share_heir(&third, 0, share_bps),
+            &[&estate.owner],
+            ErrorCode::InvalidShare,
+        );
+    }
+
+    env.warp(PERIOD + 1);
+    estate.request(&mut env, &first);
+    estate.request(&mut env, &second);
+    env.warp(NOTICE);
+    for heir in [&first, &second] {
+        env.execute(
+            instructions::finalize_token_share_claim(&owner, &heir.pubkey(), &estate.mint, &TOKEN, 0),
+            &[heir],
+        );
+    }
+
+    // The second share is of the balance before the first was paid
+    assert_eq!(env.token_balance(&estate.heir_account(&first.pubkey())), 500);
+    assert_eq!(env.token_balance(&estate.heir_account(&second.pubkey())), 250);
+    assert_eq!(env.token_balance(&estate.owner_account), 250);
+    let token_estate: TokenEstate = env.account(&estate_address);
+    assert_eq!(token_estate.total_share_bps, 0);
+    assert_eq!(token_estate.snapshot_balance, 1_000);
+}
+
+#[test]
+fn cancelling_the_last_share_revokes_the_estate_allowance() {
+    let mut env = TestEnv::new();
+    let estate = Estate::new(&mut env, TOKEN, false);
+    let first = Keypair::new().pubkey();
+    let second = Keypair::new().pubkey();
+    let owner = estate.owner.pubkey();
+    env.execute(estate.add_token_share_heir(&first, 0, 5_000), &[&estate.owner]);
+    env.execute(estate.add_token_share_heir(&second, 0, 5_000), &[&estate.owner]);
+    // The estate's allowance blocks delegated designations
+    env.assert_error(
+        estate.add_delegated_token_heir(&first, 1, 100),
+        &[&estate.owner],
+        ErrorCode::TokenAccountAlreadyDelegated,
+    );
+
+    let cancel = |heir| instructions::cancel_token_share_heir(&owner, heir, &estate.mint, &TOKEN, 0);
+    env.execute(cancel(&first), &[&estate.owner]);
+    assert_eq!(estate.delegate(&env), Some(pda::token_estate(&owner, &estate.mint)));
+    env.execute(cancel(&second), &[&estate.owner]);
+    assert_eq!(estate.delegate(&env), None);
+
+    let profile: UserProfile = env.account(&pda::user_profile(&owner));
+    assert_eq!(profile.total_inheritances, 0);
+    env.assert_error(cancel(&first), &[&estate.owner], AnchorError::AccountNotInitialized);
+}
@@ -0,0 +1,100 @@
+//! Token-2022 transfer-fee mints: every escrow holds withheld fees from its
+//! deposit, which are harvested into the mint before the escrow closes
+
+use gado_client::{instructions, pda, MintRiskPolicy, Pubkey, TokenHeir};
+use gado_program_tests::{TestEnv, DAY, TOKEN_2022};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const PERIOD: i64 = 30 * DAY;
+const NOTICE: i64 = 7 * DAY;
+/// 1% of each transfer, capped at 5 base units
+const FEE_BPS: u16 = 100;
+const MAX_FEE: u64 = 5;
+
+/// An owner who escrowed 1000 tokens of a transfer-fee mint for `heir`,
+/// 995 of which arrived
+fn escrow(env: &mut TestEnv, heir: &Pubkey) -> (Keypair, Pubkey, Pubkey) {
+    let owner = env.owner();
+    let mint = env.mint_with_transfer_fee(FEE_BPS, MAX_FEE);
+    env.mint_to(&mint, &TOKEN_2022, &owner.pubkey(), 1_000);
+    env.execute(
+        instructions::add_token_heir(
+            &owner.pubkey(),
+            heir,
+            &mint,
+            &TOKEN_2022,
+            0,
+            1_000,
+            PERIOD,
+            MintRiskPolicy::Reject,
+        ),
+        &[&owner],
+    );
+    let token_heir = pda::token_heir(&owner.pubkey(), heir, &mint, 0);
+    assert_eq!(env.account::<TokenHeir>(&token_heir).amount, 1_000 - MAX_FEE);
+    (owner, mint, token_heir)
+}
+
+#[test]
+fn claiming_harvests_the_withheld_fees() {
+    let mut env = TestEnv::new();
+    let heir = env.funded();
+    let (owner, mint, token_heir) = escrow(&mut env, &heir.pubkey());
+
+    env.warp(PERIOD + 1);
+    env.execute(
+        instructions::request_token_claim(&owner.pubkey(), &heir.pubkey(), &mint, 0),
+        &[&heir],
+    );
+    env.warp(NOTICE);
+    env.execute(
+        instructions::finalize_token_claim(&owner.pubkey(), &heir.pubkey(), &mint, &TOKEN_2022, 0),
+        &[&heir],
+    );
+
+    let heir_account = pda::associated_token_account(&heir.pubkey(), &mint, &TOKEN_2022);
+    assert_eq!(env.token_balance(&heir_account), 1_000 - 2 * MAX_FEE);
+    assert!(!env.exists(&pda::associated_token_account(&token_heir, &mint, &TOKEN_2022)));
+    assert!(!env.exists(&token_heir));
+}
+
+#[test]
+fn cancelling_harvests_the_withheld_fees() {
+    let mut env = TestEnv::new();
+    let heir = Keypair::new().pubkey();
+    let (owner, mint, token_heir) = escrow(&mut env, &heir);
+
+    env.execute(
+        instructions::cancel_token_heir(&owner.pubkey(), &heir, &mint, &TOKEN_2022, 0),
+        &[&owner],
+    );
+
+    let owner_account = pda::associated_token_account(&owner.pubkey(), &mint, &TOKEN_2022);
+    assert_eq!(env.token_balance(&owner_account), 1_000 - 2 * MAX_FEE);
+    assert!(!env.exists(&pda::associated_token_account(&token_heir, &mint, &TOKEN_2022)));
+    assert!(!env.exists(&token_heir));
+}
+
+#[test]
+fn cranking_harvests_the_withheld_fees() {
+    let mut env = TestEnv::new();
+    let cranker = env.funded();
+    let heir = Keypair::new().pubkey();
+    let (owner, mint, token_heir) = escrow(&mut env, &heir);
+    env.execute(instructions::set_crank_bounty(&owner.pubkey(), 100), &[&owner]);
+    let crank = instructions::crank_token_claim(&owner.pubkey(), &heir, &mint, &TOKEN_2022, 0, &cranker.pubkey());
+
+    env.warp(PERIOD + 1);
+    env.execute(crank.clone(), &[&cranker]);
+    env.warp(NOTICE);
+    env.execute(crank, &[&cranker]);
+
+    // A 9-token bounty out of 995; each payout pays its own fee
+    let heir_account = pda::associated_token_account(&heir, &mint, &TOKEN_2022);
+    let cranker_account = pda::associated_token_account(&cranker.pubkey(), &mint, &TOKEN_2022);
+    assert_eq!(env.token_balance(&heir_account), 986 - MAX_FEE);
+    assert_eq!(env.token_balance(&cranker_account), 9 - 1);
+    assert!(!env.exists(&pda::associated_token_account(&token_heir, &mint, &TOKEN_2022)));
+    assert!(!env.exists(&token_heir));
+}
@@ -1,11 +1,62 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TokenAccount, Token, Transfer, Mint};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, TokenAccount, TokenInterface, TransferChecked, ApproveChecked, Revoke, Mint, CloseAccount};
+use anchor_spl::token_interface::spl_token_2022::extension::{
+    permanent_delegate::PermanentDelegate,
+    transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount},
+    transfer_hook::TransferHook,
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::stake::{self, Stake};
+use solana_stake_interface::state::{StakeAuthorize, StakeStateV2};
+use solana_loader_v3_interface::{get_program_data_address, instruction::set_upgrade_authority};
 use anchor_lang::solana_program::clock::Clock;
-use anchor_lang::solana_program::system_instruction;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::system_program;
 
 declare_id!("EciS2vNDTe5S6WnNWEBmdBmKjQL5bsXyfauYmxPFKQGu");
 
+/// Maximum lamports a single SOL designation may hold (1M SOL)
+pub const MAX_SOL_AMOUNT: u64 = 1_000_000 * 1_000_000_000;
+/// Maximum base units a single token designation may hold (prevents overflow)
+pub const MAX_TOKEN_AMOUNT: u64 = u64::MAX / 2;
+
+/// Minimum inactivity period (1 day)
+#[cfg(not(feature = "short-claim-delay"))]
+pub const MIN_INACTIVITY_PERIOD_SECONDS: i64 = 86_400;
+/// Minimum inactivity period, shortened so local tests can exercise real claims
+#[cfg(feature = "short-claim-delay")]
+pub const MIN_INACTIVITY_PERIOD_SECONDS: i64 = 1;
+
+/// Minimum notice between a claim request and its finalization (1 day)
+#[cfg(not(feature = "short-claim-delay"))]
+pub const MIN_CLAIM_NOTICE_PERIOD_SECONDS: i64 = 86_400;
+/// Minimum claim notice period, shortened so local tests can finalize claims
+#[cfg(feature = "short-claim-delay")]
+pub const MIN_CLAIM_NOTICE_PERIOD_SECONDS: i64 = 1;
+/// Claim notice period for new profiles (7 days)
+#[cfg(not(feature = "short-claim-delay"))]
+pub const DEFAULT_CLAIM_NOTICE_PERIOD_SECONDS: i64 = 7 * 86_400;
+/// Claim notice period for new profiles under `short-claim-delay`
+#[cfg(feature = "short-claim-delay")]
+pub const DEFAULT_CLAIM_NOTICE_PERIOD_SECONDS: i64 = MIN_CLAIM_NOTICE_PERIOD_SECONDS;
+/// Maximum claim notice period an owner may configure (30 days)
+pub const MAX_CLAIM_NOTICE_PERIOD_SECONDS: i64 = 30 * 86_400;
+
+/// Maximum number of guardians in an owner's guardian set
+pub const MAX_GUARDIANS: usize = 10;
+
+/// Maximum number of NFTs in a single NFT bundle designation
+pub const MAX_NFTS_PER_BUNDLE: usize = 10;
+/// Maximum number of mints in a single token basket designation
+pub const MAX_BASKET_ENTRIES: usize = 8;
+
+/// Basis points making up a whole balance
+pub const BPS_DENOMINATOR: u16 = 10_000;
+/// Largest crank bounty an owner may offer (1% of each bequest)
+pub const MAX_CRANK_BOUNTY_BPS: u16 = 100;
+
 #[program]
 pub mod gado {
     use super::*;
@@ -17,7 +68,11 @@ pub mod gado {
         user_profile.owner = ctx.accounts.owner.key();
         user_profile.total_inheritances = 0;
         user_profile.bump = ctx.bumps.user_profile;
+        user_profile.last_activity = Clock::get()?.unix_timestamp;
+        user_profile.claim_notice_period_seconds = DEFAULT_CLAIM_NOTICE_PERIOD_SECONDS;
+        user_profile.crank_bounty_bps = 0;
         
+        emit_estate_activity(&ctx.accounts.user_profile);
         msg!("User profile initialized for: {}", ctx.accounts.owner.key());
         Ok(())
     }
@@ -25,18 +80,22 @@ pub mod gado {
     /// Add a SOL inheritance heir
     pub fn add_sol_heir(
         ctx: Context<AddSolHeir>, 
+        tranche: u16,
         amount: u64, 
         inactivity_period_seconds: i64
     ) -> Result<()> {
         // Enhanced input validation
         require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(amount <= 1_000_000 * 1_000_000_000, ErrorCode::AmountTooLarge); // Max 1M SOL
+        require!(amount <= MAX_SOL_AMOUNT, ErrorCode::AmountTooLarge); // Max 1M SOL
         require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
-        require!(inactivity_period_seconds >= 86400, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
         require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
         
         // Ensure owner is not heir (prevent self-inheritance)
         require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
+        // The payout alone must be able to open the heir's account
+        let reserve = Rent::get()?.minimum_balance(0);
+        require!(amount >= reserve, ErrorCode::BequestBelowRentExemption);
         
         let sol_heir = &mut ctx.accounts.sol_heir;
         
@@ -48,41 +107,53 @@ pub mod gado {
         sol_heir.last_activity = Clock::get()?.unix_timestamp;
         sol_heir.is_claimed = false;
         sol_heir.bump = ctx.bumps.sol_heir;
+        sol_heir.tranche = tranche;
+        sol_heir.claim_requested_at = 0;
         
-        // Transfer SOL from owner to the heir account (escrow)
-        let transfer_ix = system_instruction::transfer(
-            &ctx.accounts.owner.key(),
-            &sol_heir.key(),
-            amount,
-        );
-        
-        anchor_lang::solana_program::program::invoke(
-            &transfer_ix,
-            &[
-                ctx.accounts.owner.to_account_info(),
-                sol_heir.to_account_info(),
-            ],
-        )?;
+        // Transfer SOL from owner to the vault (escrow). The vault also keeps a
+        // rent-exempt reserve so small bequests never leave it below the minimum.
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.sol_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount + reserve)?;
         
         // Update user profile
         let user_profile = &mut ctx.accounts.user_profile;
         user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
         
-        msg!("SOL heir added: {} lamports for heir: {}", amount, ctx.accounts.heir.key());
+        emit!(HeirAdded {
+            designation: ctx.accounts.sol_heir.key(),
+            kind: DesignationKind::Sol,
+            owner: ctx.accounts.owner.key(),
+            heir: ctx.accounts.heir.key(),
+            mint: None,
+            amount,
+            inactivity_period_seconds,
+            timestamp: ctx.accounts.sol_heir.last_activity,
+        });
+        msg!("SOL heir added: {} lamports for heir: {} (tranche {})", amount, ctx.accounts.heir.key(), tranche);
         Ok(())
     }
 
-    /// Add a SPL token inheritance heir
-    pub fn add_token_heir(
-        ctx: Context<AddTokenHeir>, 
+    /// Add a SPL Token or Token-2022 inheritance heir. For mints with a transfer
+    /// hook, pass the hook's extra accounts as remaining accounts (likewise for
+    /// every other instruction that moves escrowed tokens). `mint_risk_policy`
+    /// decides what happens if the issuer could freeze or drain the escrow.
+    pub fn add_token_heir<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddTokenHeir<'info>>, 
+        tranche: u16,
         amount: u64, 
-        inactivity_period_seconds: i64
+        inactivity_period_seconds: i64,
+        mint_risk_policy: MintRiskPolicy
     ) -> Result<()> {
         // Enhanced input validation
         require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(amount <= u64::MAX / 2, ErrorCode::AmountTooLarge); // Prevent overflow
+        require!(amount <= MAX_TOKEN_AMOUNT, ErrorCode::AmountTooLarge); // Prevent overflow
         require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
-        require!(inactivity_period_seconds >= 86400, ErrorCode::InactivityPeriodTooShort); // Min 1 day  
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
         require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
         
         // Ensure owner is not heir (prevent self-inheritance)
@@ -100,6 +171,9 @@ pub mod gado {
             ErrorCode::InvalidMint
         );
         
+        // Check whether the issuer keeps control over the escrowed tokens
+        apply_mint_risk_policy(&ctx.accounts.token_mint, mint_risk_policy, ctx.accounts.token_heir.key())?;
+        
         let token_heir = &mut ctx.accounts.token_heir;
         
         // Initialize the heir account
@@ -111,198 +185,4059 @@ pub mod gado {
         token_heir.last_activity = Clock::get()?.unix_timestamp;
         token_heir.is_claimed = false;
         token_heir.bump = ctx.bumps.token_heir;
+        token_heir.tranche = tranche;
+        token_heir.claim_requested_at = 0;
+        token_heir.custody = TokenCustody::Escrow;
+        token_heir.share_bps = 0;
         
         // Transfer tokens from owner to escrow account
-        let cpi_accounts = Transfer {
+        let escrow_before = ctx.accounts.escrow_token_account.amount;
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
             to: ctx.accounts.escrow_token_account.to_account_info(),
             authority: ctx.accounts.owner.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        
+        // Record what actually arrived, net of any Token-2022 transfer fee
+        ctx.accounts.escrow_token_account.reload()?;
+        token_heir.amount = ctx.accounts.escrow_token_account.amount.saturating_sub(escrow_before);
+        require!(token_heir.amount > 0, ErrorCode::InvalidAmount);
         
         // Update user profile
         let user_profile = &mut ctx.accounts.user_profile;
         user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
         
-        msg!("Token heir added: {} tokens for heir: {}", amount, ctx.accounts.heir.key());
+        emit!(HeirAdded {
+            designation: ctx.accounts.token_heir.key(),
+            kind: DesignationKind::Token,
+            owner: ctx.accounts.owner.key(),
+            heir: ctx.accounts.heir.key(),
+            mint: Some(ctx.accounts.token_mint.key()),
+            amount: ctx.accounts.token_heir.amount,
+            inactivity_period_seconds,
+            timestamp: ctx.accounts.token_heir.last_activity,
+        });
+        msg!("Token heir added: {} tokens for heir: {} (tranche {})", ctx.accounts.token_heir.amount, ctx.accounts.heir.key(), tranche);
         Ok(())
     }
 
-    /// Update activity for SOL heir (resets inactivity timer)
-    pub fn update_sol_activity(ctx: Context<UpdateSolActivity>) -> Result<()> {
-        let sol_heir = &mut ctx.accounts.sol_heir;
-        require_keys_eq!(sol_heir.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+    /// Add a token heir without escrow: the designation is approved as delegate
+    /// for up to `amount` on the owner's token account, which the owner keeps
+    /// using. The claim pulls whatever balance and allowance remain. A token
+    /// account has a single delegate, so it backs one delegated designation.
+    pub fn add_delegated_token_heir(
+        ctx: Context<AddDelegatedTokenHeir>,
+        tranche: u16,
+        amount: u64,
+        inactivity_period_seconds: i64,
+        mint_risk_policy: MintRiskPolicy
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount <= MAX_TOKEN_AMOUNT, ErrorCode::AmountTooLarge); // Prevent overflow
+        require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
         
-        sol_heir.last_activity = Clock::get()?.unix_timestamp;
-        msg!("SOL activity updated for owner: {}", ctx.accounts.owner.key());
+        // Ensure owner is not heir (prevent self-inheritance)
+        require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
+        
+        // Approving would silently replace another designation's allowance
+        require!(ctx.accounts.owner_token_account.delegate.is_none(), ErrorCode::TokenAccountAlreadyDelegated);
+        
+        apply_mint_risk_policy(&ctx.accounts.token_mint, mint_risk_policy, ctx.accounts.token_heir.key())?;
+        
+        let token_heir = &mut ctx.accounts.token_heir;
+        token_heir.owner = ctx.accounts.owner.key();
+        token_heir.heir = ctx.accounts.heir.key();
+        token_heir.token_mint = ctx.accounts.token_mint.key();
+        token_heir.amount = amount;
+        token_heir.inactivity_period_seconds = inactivity_period_seconds;
+        token_heir.last_activity = Clock::get()?.unix_timestamp;
+        token_heir.is_claimed = false;
+        token_heir.bump = ctx.bumps.token_heir;
+        token_heir.tranche = tranche;
+        token_heir.claim_requested_at = 0;
+        token_heir.custody = TokenCustody::Delegate;
+        token_heir.share_bps = 0;
+        
+        let cpi_accounts = ApproveChecked {
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            delegate: token_heir.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::approve_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        
+        // Update user profile
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
+        
+        emit!(HeirAdded {
+            designation: ctx.accounts.token_heir.key(),
+            kind: DesignationKind::DelegatedToken,
+            owner: ctx.accounts.owner.key(),
+            heir: ctx.accounts.heir.key(),
+            mint: Some(ctx.accounts.token_mint.key()),
+            amount,
+            inactivity_period_seconds,
+            timestamp: ctx.accounts.token_heir.last_activity,
+        });
+        msg!("Delegated token heir added: up to {} tokens for heir: {} (tranche {})", amount, ctx.accounts.heir.key(), tranche);
         Ok(())
     }
 
-    /// Update activity for token heir (resets inactivity timer)
-    pub fn update_token_activity(ctx: Context<UpdateTokenActivity>) -> Result<()> {
-        let token_heir = &mut ctx.accounts.token_heir;
-        require_keys_eq!(token_heir.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+    /// Bequeath a share, in basis points, of whatever balance the owner's token
+    /// account holds when the first share of it is claimed. The owner's
+    /// `TokenEstate` for the mint is approved as the account's delegate and
+    /// shared by all of the owner's share designations for that mint.
+    pub fn add_token_share_heir(
+        ctx: Context<AddTokenShareHeir>,
+        tranche: u16,
+        share_bps: u16,
+        inactivity_period_seconds: i64,
+        mint_risk_policy: MintRiskPolicy
+    ) -> Result<()> {
+        require!(share_bps > 0 && share_bps <= BPS_DENOMINATOR, ErrorCode::InvalidShare);
+        require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
+        
+        // Ensure owner is not heir (prevent self-inheritance)
+        require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
         
+        // Only the estate may hold the allowance, or another designation's would be replaced
+        let token_estate = &mut ctx.accounts.token_estate;
+        require!(
+            ctx.accounts.owner_token_account.delegate.is_none()
+                || ctx.accounts.owner_token_account.delegate == Some(token_estate.key()).into(),
+            ErrorCode::TokenAccountAlreadyDelegated
+        );
+        
+        let total_share_bps = token_estate
+            .total_share_bps
+            .checked_add(share_bps)
+            .filter(|total| *total <= BPS_DENOMINATOR)
+            .ok_or(ErrorCode::InvalidShare)?;
+        
+        apply_mint_risk_policy(&ctx.accounts.token_mint, mint_risk_policy, ctx.accounts.token_heir.key())?;
+        
+        token_estate.owner = ctx.accounts.owner.key();
+        token_estate.token_mint = ctx.accounts.token_mint.key();
+        token_estate.total_share_bps = total_share_bps;
+        token_estate.bump = ctx.bumps.token_estate;
+        
+        let token_heir = &mut ctx.accounts.token_heir;
+        token_heir.owner = ctx.accounts.owner.key();
+        token_heir.heir = ctx.accounts.heir.key();
+        token_heir.token_mint = ctx.accounts.token_mint.key();
+        token_heir.amount = 0;
+        token_heir.inactivity_period_seconds = inactivity_period_seconds;
         token_heir.last_activity = Clock::get()?.unix_timestamp;
-        msg!("Token activity updated for owner: {}", ctx.accounts.owner.key());
+        token_heir.is_claimed = false;
+        token_heir.bump = ctx.bumps.token_heir;
+        token_heir.tranche = tranche;
+        token_heir.claim_requested_at = 0;
+        token_heir.custody = TokenCustody::Share;
+        token_heir.share_bps = share_bps;
+        
+        // The shares apply to a live balance, so the allowance is unbounded
+        let cpi_accounts = ApproveChecked {
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            delegate: token_estate.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::approve_checked(cpi_ctx, u64::MAX, ctx.accounts.token_mint.decimals)?;
+        
+        // Update user profile
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
+        
+        emit!(HeirAdded {
+            designation: ctx.accounts.token_heir.key(),
+            kind: DesignationKind::TokenShare,
+            owner: ctx.accounts.owner.key(),
+            heir: ctx.accounts.heir.key(),
+            mint: Some(ctx.accounts.token_mint.key()),
+            amount: share_bps as u64,
+            inactivity_period_seconds,
+            timestamp: ctx.accounts.token_heir.last_activity,
+        });
+        msg!("Token share heir added: {} bps for heir: {} (tranche {})", share_bps, ctx.accounts.heir.key(), tranche);
         Ok(())
     }
 
-    /// Claim SOL inheritance
-    pub fn claim_sol_inheritance(ctx: Context<ClaimSolInheritance>) -> Result<()> {
-        let sol_heir = &mut ctx.accounts.sol_heir;
-        let current_time = Clock::get()?.unix_timestamp;
+    /// Bequeath a bundle of NFTs (0-decimal, supply-1 mints of `token_program`)
+    /// to one heir. For every NFT pass `[mint, owner_token_account, escrow_token_account]`
+    /// as remaining accounts; each escrow is the designation's ATA for the mint
+    /// and is created if needed. Mints with a transfer hook are rejected.
+    pub fn add_nft_heir<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddNftHeir<'info>>,
+        tranche: u16,
+        inactivity_period_seconds: i64
+    ) -> Result<()> {
+        require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
         
-        // Validate claim conditions
-        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
-        require_keys_eq!(sol_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        // Ensure owner is not heir (prevent self-inheritance)
+        require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
+        
+        let nft_accounts = account_triples(ctx.remaining_accounts).ok_or(ErrorCode::InvalidNftBundle)?;
         require!(
-            current_time - sol_heir.last_activity > sol_heir.inactivity_period_seconds,
-            ErrorCode::OwnerStillActive
+            !nft_accounts.is_empty() && nft_accounts.len() <= MAX_NFTS_PER_BUNDLE,
+            ErrorCode::InvalidNftBundle
         );
+        let mut mints = Vec::with_capacity(nft_accounts.len());
+        for [mint_info, owner_nft_info, escrow_info] in nft_accounts {
+            let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+            require_nft_mint(&mint)?;
+            require_no_transfer_hook(&mint)?;
+            require!(!mints.contains(&mint.key()), ErrorCode::InvalidNftBundle);
+            
+            let owner_nft_account = InterfaceAccount::<TokenAccount>::try_from(owner_nft_info)?;
+            require_keys_eq!(owner_nft_account.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+            require_keys_eq!(owner_nft_account.mint, mint.key(), ErrorCode::InvalidMint);
+            
+            create_ata_if_needed(
+                &ctx.accounts.owner,
+                escrow_info,
+                &ctx.accounts.nft_heir,
+                mint_info,
+                &ctx.accounts.system_program,
+                &ctx.accounts.token_program,
+                &ctx.accounts.associated_token_program,
+            )?;
+            
+            let cpi_accounts = TransferChecked {
+                from: owner_nft_info.clone(),
+                mint: mint_info.clone(),
+                to: escrow_info.clone(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+            
+            mints.push(mint.key());
+        }
+        
+        let nft_heir = &mut ctx.accounts.nft_heir;
+        nft_heir.owner = ctx.accounts.owner.key();
+        nft_heir.heir = ctx.accounts.heir.key();
+        nft_heir.mints = mints;
+        nft_heir.inactivity_period_seconds = inactivity_period_seconds;
+        nft_heir.last_activity = Clock::get()?.unix_timestamp;
+        nft_heir.bump = ctx.bumps.nft_heir;
+        nft_heir.tranche = tranche;
+        nft_heir.claim_requested_at = 0;
+        
+        // Update user profile
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
         
-        // Transfer SOL from heir account to heir's wallet
-        let transfer_ix = system_instruction::transfer(
-            &sol_heir.key(),
-            &ctx.accounts.heir.key(),
-            sol_heir.amount,
+        let nft_heir = &ctx.accounts.nft_heir;
+        for mint in nft_heir.mints.iter() {
+            emit!(HeirAdded {
+                designation: nft_heir.key(),
+                kind: DesignationKind::NftBundle,
+                owner: ctx.accounts.owner.key(),
+                heir: ctx.accounts.heir.key(),
+                mint: Some(*mint),
+                amount: 1,
+                inactivity_period_seconds,
+                timestamp: nft_heir.last_activity,
+            });
+        }
+        msg!("NFT heir added: {} NFTs for heir: {} (tranche {})", nft_heir.mints.len(), ctx.accounts.heir.key(), tranche);
+        Ok(())
+    }
+
+    /// Bequeath several mints to one heir in a single designation. Pass
+    /// `[mint, owner_token_account, escrow_token_account]` per entry as remaining
+    /// accounts, in the order of `amounts`; each escrow is the basket's ATA for
+    /// the mint and is created if needed. Every mint must belong to `token_program`
+    /// and have no transfer hook.
+    pub fn add_token_basket_heir<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddTokenBasketHeir<'info>>,
+        tranche: u16,
+        amounts: Vec<u64>,
+        inactivity_period_seconds: i64,
+        mint_risk_policy: MintRiskPolicy
+    ) -> Result<()> {
+        require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
+        
+        // Ensure owner is not heir (prevent self-inheritance)
+        require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
+        
+        let entry_accounts = account_triples(ctx.remaining_accounts).ok_or(ErrorCode::InvalidTokenBasket)?;
+        require!(
+            !entry_accounts.is_empty()
+                && entry_accounts.len() <= MAX_BASKET_ENTRIES
+                && entry_accounts.len() == amounts.len(),
+            ErrorCode::InvalidTokenBasket
         );
         
-        let seeds = &[
-            b"sol_heir",
-            sol_heir.owner.as_ref(),
-            sol_heir.heir.as_ref(),
-            &[sol_heir.bump],
-        ];
-        
-        anchor_lang::solana_program::program::invoke_signed(
-            &transfer_ix,
-            &[
-                sol_heir.to_account_info(),
-                ctx.accounts.heir.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            &[seeds],
+        let basket_key = ctx.accounts.token_basket_heir.key();
+        let mut entries: Vec<BasketEntry> = Vec::with_capacity(amounts.len());
+        for ([mint_info, owner_token_info, escrow_info], amount) in entry_accounts.into_iter().zip(amounts) {
+            require!(amount > 0, ErrorCode::InvalidAmount);
+            require!(amount <= MAX_TOKEN_AMOUNT, ErrorCode::AmountTooLarge); // Prevent overflow
+            
+            let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+            require!(!entries.iter().any(|entry| entry.mint == mint.key()), ErrorCode::InvalidTokenBasket);
+            require_no_transfer_hook(&mint)?;
+            apply_mint_risk_policy(&mint, mint_risk_policy, basket_key)?;
+            
+            let owner_token_account = InterfaceAccount::<TokenAccount>::try_from(owner_token_info)?;
+            require_keys_eq!(owner_token_account.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+            require_keys_eq!(owner_token_account.mint, mint.key(), ErrorCode::InvalidMint);
+            
+            create_ata_if_needed(
+                &ctx.accounts.owner,
+                escrow_info,
+                &ctx.accounts.token_basket_heir,
+                mint_info,
+                &ctx.accounts.system_program,
+                &ctx.accounts.token_program,
+                &ctx.accounts.associated_token_program,
+            )?;
+            
+            let escrow_before = InterfaceAccount::<TokenAccount>::try_from(escrow_info)?.amount;
+            let cpi_accounts = TransferChecked {
+                from: owner_token_info.clone(),
+                mint: mint_info.clone(),
+                to: escrow_info.clone(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+            
+            // Record what actually arrived, net of any Token-2022 transfer fee
+            let received = InterfaceAccount::<TokenAccount>::try_from(escrow_info)?.amount.saturating_sub(escrow_before);
+            require!(received > 0, ErrorCode::InvalidAmount);
+            entries.push(BasketEntry { mint: mint.key(), amount: received });
+        }
+        
+        let token_basket_heir = &mut ctx.accounts.token_basket_heir;
+        token_basket_heir.owner = ctx.accounts.owner.key();
+        token_basket_heir.heir = ctx.accounts.heir.key();
+        token_basket_heir.entries = entries;
+        token_basket_heir.inactivity_period_seconds = inactivity_period_seconds;
+        token_basket_heir.last_activity = Clock::get()?.unix_timestamp;
+        token_basket_heir.bump = ctx.bumps.token_basket_heir;
+        token_basket_heir.tranche = tranche;
+        token_basket_heir.claim_requested_at = 0;
+        
+        // Update user profile
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
+        
+        let token_basket_heir = &ctx.accounts.token_basket_heir;
+        for entry in token_basket_heir.entries.iter() {
+            emit!(HeirAdded {
+                designation: token_basket_heir.key(),
+                kind: DesignationKind::TokenBasket,
+                owner: ctx.accounts.owner.key(),
+                heir: ctx.accounts.heir.key(),
+                mint: Some(entry.mint),
+                amount: entry.amount,
+                inactivity_period_seconds,
+                timestamp: token_basket_heir.last_activity,
+            });
+        }
+        msg!("Token basket heir added: {} mints for heir: {} (tranche {})", token_basket_heir.entries.len(), ctx.accounts.heir.key(), tranche);
+        Ok(())
+    }
+
+    /// Bequeath a native stake account. Its staker and withdrawer authorities
+    /// move from the owner to the designation, so the stake stays delegated
+    /// but cannot be managed until the designation is claimed or cancelled.
+    pub fn add_stake_heir(ctx: Context<AddStakeHeir>, inactivity_period_seconds: i64) -> Result<()> {
+        require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
+        
+        // Ensure owner is not heir (prevent self-inheritance)
+        require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
+        
+        // The owner must hold both authorities, and a lockup would need its
+        // custodian to sign the withdrawer change
+        let meta = {
+            let data = ctx.accounts.stake_account.try_borrow_data()?;
+            StakeStateV2::deserialize(&mut &data[..])?
+                .meta()
+                .ok_or(ErrorCode::InvalidStakeAccount)?
+        };
+        require_keys_eq!(meta.authorized.staker, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        require_keys_eq!(meta.authorized.withdrawer, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        require!(!meta.lockup.is_in_force(&ctx.accounts.clock, None), ErrorCode::StakeLockupInForce);
+        
+        transfer_stake_authorities(
+            &ctx.accounts.stake_account,
+            &ctx.accounts.owner,
+            &ctx.accounts.stake_heir,
+            &ctx.accounts.clock,
+            &ctx.accounts.stake_program,
+            &[],
         )?;
         
-        sol_heir.is_claimed = true;
-        msg!("SOL inheritance claimed: {} lamports", sol_heir.amount);
+        let stake_heir = &mut ctx.accounts.stake_heir;
+        stake_heir.owner = ctx.accounts.owner.key();
+        stake_heir.heir = ctx.accounts.heir.key();
+        stake_heir.stake_account = ctx.accounts.stake_account.key();
+        stake_heir.inactivity_period_seconds = inactivity_period_seconds;
+        stake_heir.last_activity = Clock::get()?.unix_timestamp;
+        stake_heir.bump = ctx.bumps.stake_heir;
+        stake_heir.claim_requested_at = 0;
+        
+        // Update user profile
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
+        
+        emit!(HeirAdded {
+            designation: ctx.accounts.stake_heir.key(),
+            kind: DesignationKind::Stake,
+            owner: ctx.accounts.owner.key(),
+            heir: ctx.accounts.heir.key(),
+            mint: None,
+            amount: ctx.accounts.stake_account.lamports(),
+            inactivity_period_seconds,
+            timestamp: ctx.accounts.stake_heir.last_activity,
+        });
+        msg!("Stake heir added: stake account {} for heir: {}", ctx.accounts.stake_account.key(), ctx.accounts.heir.key());
         Ok(())
     }
 
-    /// Claim token inheritance
-    pub fn claim_token_inheritance(ctx: Context<ClaimTokenInheritance>) -> Result<()> {
-        let token_heir = &mut ctx.accounts.token_heir;
+    /// Bequeath the upgrade authority of a BPF-upgradeable program. The
+    /// authority moves from the owner to the designation, so the program can
+    /// only be upgraded again once the designation is claimed or cancelled.
+    pub fn add_program_heir(ctx: Context<AddProgramHeir>, inactivity_period_seconds: i64) -> Result<()> {
+        require!(inactivity_period_seconds > 0, ErrorCode::InvalidInactivityPeriod);
+        require!(inactivity_period_seconds >= MIN_INACTIVITY_PERIOD_SECONDS, ErrorCode::InactivityPeriodTooShort); // Min 1 day
+        require!(inactivity_period_seconds <= 31_536_000, ErrorCode::InactivityPeriodTooLong); // Max 1 year
+        
+        // Ensure owner is not heir (prevent self-inheritance)
+        require_keys_neq!(ctx.accounts.owner.key(), ctx.accounts.heir.key(), ErrorCode::SelfInheritanceNotAllowed);
+        
+        // An immutable program has no authority left to bequeath
+        let upgrade_authority = ctx.accounts.program_data.upgrade_authority_address
+            .ok_or(ErrorCode::InvalidUpgradeableProgram)?;
+        require_keys_eq!(upgrade_authority, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        
+        transfer_upgrade_authority(
+            &ctx.accounts.upgradeable_program,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner,
+            &ctx.accounts.program_heir,
+            &ctx.accounts.upgradeable_loader,
+            &[],
+        )?;
+        
+        let program_heir = &mut ctx.accounts.program_heir;
+        program_heir.owner = ctx.accounts.owner.key();
+        program_heir.heir = ctx.accounts.heir.key();
+        program_heir.upgradeable_program = ctx.accounts.upgradeable_program.key();
+        program_heir.inactivity_period_seconds = inactivity_period_seconds;
+        program_heir.last_activity = Clock::get()?.unix_timestamp;
+        program_heir.bump = ctx.bumps.program_heir;
+        program_heir.claim_requested_at = 0;
+        
+        // Update user profile
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances += 1;
+        user_profile.record_activity()?;
+        
+        emit!(HeirAdded {
+            designation: ctx.accounts.program_heir.key(),
+            kind: DesignationKind::Program,
+            owner: ctx.accounts.owner.key(),
+            heir: ctx.accounts.heir.key(),
+            mint: None,
+            amount: 0,
+            inactivity_period_seconds,
+            timestamp: ctx.accounts.program_heir.last_activity,
+        });
+        msg!("Program heir added: program {} for heir: {}", ctx.accounts.upgradeable_program.key(), ctx.accounts.heir.key());
+        Ok(())
+    }
+
+    /// Record proof of life for every designation of the owner at once.
+    /// Also vetoes any claim request that is still in its notice period.
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        ctx.accounts.user_profile.record_activity()?;
+        
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Heartbeat recorded for owner: {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Register (or re-register with a new expiry) a liveness key that may record
+    /// heartbeats on the owner's behalf. `expires_at` of 0 means it never expires.
+    pub fn register_liveness_key(ctx: Context<RegisterLivenessKey>, expires_at: i64) -> Result<()> {
         let current_time = Clock::get()?.unix_timestamp;
+        require!(expires_at == 0 || expires_at > current_time, ErrorCode::InvalidExpiry);
+        require_keys_neq!(ctx.accounts.key.key(), ctx.accounts.owner.key(), ErrorCode::Unauthorized);
         
-        // Validate claim conditions
-        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
-        require_keys_eq!(token_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        let liveness_key = &mut ctx.accounts.liveness_key;
+        liveness_key.owner = ctx.accounts.owner.key();
+        liveness_key.key = ctx.accounts.key.key();
+        liveness_key.expires_at = expires_at;
+        liveness_key.bump = ctx.bumps.liveness_key;
+        ctx.accounts.user_profile.record_activity()?;
+        
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Liveness key registered: {} (expires at {})", liveness_key.key, expires_at);
+        Ok(())
+    }
+
+    /// Revoke a liveness key and return its rent to the owner
+    pub fn revoke_liveness_key(ctx: Context<RevokeLivenessKey>) -> Result<()> {
+        ctx.accounts.user_profile.record_activity()?;
+        
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Liveness key revoked: {}", ctx.accounts.liveness_key.key);
+        Ok(())
+    }
+
+    /// Record a heartbeat signed by a registered liveness key instead of the
+    /// owner. Liveness keys can only prove activity; they cannot move funds.
+    pub fn delegated_heartbeat(ctx: Context<DelegatedHeartbeat>) -> Result<()> {
+        let liveness_key = &ctx.accounts.liveness_key;
+        let current_time = Clock::get()?.unix_timestamp;
         require!(
-            current_time - token_heir.last_activity > token_heir.inactivity_period_seconds,
-            ErrorCode::OwnerStillActive
+            liveness_key.expires_at == 0 || current_time < liveness_key.expires_at,
+            ErrorCode::LivenessKeyExpired
         );
         
-        // Validate token accounts
-        require_keys_eq!(
-            ctx.accounts.heir_token_account.owner, 
-            ctx.accounts.heir.key(), 
-            ErrorCode::Unauthorized
+        ctx.accounts.user_profile.record_activity()?;
+        
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Heartbeat recorded by liveness key {} for owner: {}", liveness_key.key, liveness_key.owner);
+        Ok(())
+    }
+
+    /// Configure how long a claim request must wait before it can be finalized
+    pub fn set_claim_notice_period(ctx: Context<Heartbeat>, notice_period_seconds: i64) -> Result<()> {
+        require!(
+            (MIN_CLAIM_NOTICE_PERIOD_SECONDS..=MAX_CLAIM_NOTICE_PERIOD_SECONDS).contains(&notice_period_seconds),
+            ErrorCode::InvalidNoticePeriod
         );
-        require_keys_eq!(
-            ctx.accounts.heir_token_account.mint, 
-            token_heir.token_mint, 
-            ErrorCode::InvalidMint
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.claim_notice_period_seconds = notice_period_seconds;
+        user_profile.record_activity()?;
+        
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Claim notice period set to {} seconds for owner: {}", notice_period_seconds, ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Configure the share of each bequest, in basis points, paid to whoever
+    /// cranks a claim through to the heir
+    pub fn set_crank_bounty(ctx: Context<Heartbeat>, bounty_bps: u16) -> Result<()> {
+        require!(bounty_bps <= MAX_CRANK_BOUNTY_BPS, ErrorCode::InvalidCrankBounty);
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.crank_bounty_bps = bounty_bps;
+        user_profile.record_activity()?;
+        
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Crank bounty set to {} bps for owner: {}", bounty_bps, ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Create or replace the owner's guardian set: trusted keys that, with an
+    /// M-of-N quorum, can attest the owner's death or freeze claims.
+    pub fn set_guardians(ctx: Context<SetGuardians>, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        require!(
+            !guardians.is_empty() && guardians.len() <= MAX_GUARDIANS,
+            ErrorCode::InvalidGuardianSet
         );
-        require_keys_eq!(
-            ctx.accounts.escrow_token_account.mint, 
-            token_heir.token_mint, 
-            ErrorCode::InvalidMint
+        for (i, guardian) in guardians.iter().enumerate() {
+            require_keys_neq!(*guardian, owner, ErrorCode::InvalidGuardianSet);
+            require!(!guardians[..i].contains(guardian), ErrorCode::InvalidGuardianSet);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= guardians.len(),
+            ErrorCode::InvalidGuardianThreshold
         );
         
-        // Transfer tokens from escrow to heir
-        let seeds: &[&[&[u8]]] = &[&[
-            b"token_heir",
-            token_heir.owner.as_ref(),
-            token_heir.heir.as_ref(),
-            token_heir.token_mint.as_ref(),
-            &[token_heir.bump],
-        ]];
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        guardian_set.owner = owner;
+        guardian_set.guardians = guardians;
+        guardian_set.threshold = threshold;
+        guardian_set.bump = ctx.bumps.guardian_set;
+        ctx.accounts.user_profile.record_activity()?;
         
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.heir_token_account.to_account_info(),
-            authority: token_heir.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds);
-        token::transfer(cpi_ctx, token_heir.amount)?;
+        emit_estate_activity(&ctx.accounts.user_profile);
+        msg!("Guardian set updated: {}-of-{} for owner: {}", threshold, guardian_set.guardians.len(), owner);
+        Ok(())
+    }
+
+    /// Attest the owner's death with a guardian quorum (guardians sign as
+    /// remaining accounts). Claims skip the inactivity and notice periods until
+    /// the owner shows activity again.
+    pub fn attest_deceased(ctx: Context<GuardianAction>) -> Result<()> {
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        require!(guardian_set.has_quorum(ctx.remaining_accounts), ErrorCode::GuardianQuorumNotMet);
         
-        token_heir.is_claimed = true;
-        msg!("Token inheritance claimed: {} tokens", token_heir.amount);
+        guardian_set.deceased_attested_at = Clock::get()?.unix_timestamp;
+        emit_guardian_action(guardian_set, ctx.remaining_accounts, GuardianActionKind::AttestDeceased)?;
+        msg!("Guardians attested death of owner: {}", guardian_set.owner);
         Ok(())
     }
-}
 
-// ===============================================
-// ACCOUNT STRUCTURES
-// ===============================================
+    /// Block all claims on the owner's estate with a guardian quorum
+    pub fn freeze_claims(ctx: Context<GuardianAction>) -> Result<()> {
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        require!(guardian_set.has_quorum(ctx.remaining_accounts), ErrorCode::GuardianQuorumNotMet);
+        
+        guardian_set.frozen = true;
+        emit_guardian_action(guardian_set, ctx.remaining_accounts, GuardianActionKind::FreezeClaims)?;
+        msg!("Claims frozen for owner: {}", guardian_set.owner);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct InitializeUser<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = UserProfile::SPACE,
-        seeds = [b"user_profile", owner.key().as_ref()],
-        bump
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-    
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    /// Lift a guardian freeze with a guardian quorum
+    pub fn unfreeze_claims(ctx: Context<GuardianAction>) -> Result<()> {
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        require!(guardian_set.has_quorum(ctx.remaining_accounts), ErrorCode::GuardianQuorumNotMet);
+        
+        guardian_set.frozen = false;
+        emit_guardian_action(guardian_set, ctx.remaining_accounts, GuardianActionKind::UnfreezeClaims)?;
+        msg!("Claims unfrozen for owner: {}", guardian_set.owner);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct AddSolHeir<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = SolHeir::SPACE,
-        seeds = [b"sol_heir", owner.key().as_ref(), heir.key().as_ref()],
-        bump
-    )]
-    pub sol_heir: Account<'info, SolHeir>,
-    
-    #[account(
-        mut,
-        seeds = [b"user_profile", owner.key().as_ref()],
-        bump = user_profile.bump
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-    
-    #[account(mut)]
+    /// Grow a user profile from an older layout to the current one (permissionless).
+    /// The estate-wide `last_activity` is seeded from the newest per-heir timestamp
+    /// among the `SolHeir`/`TokenHeir` accounts passed as remaining accounts, which
+    /// must already be on the current layout (see `migrate_heir_account`).
+    pub fn migrate_user_profile(ctx: Context<MigrateUserProfile>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        
+        let mut last_activity = 0;
+        for info in ctx.remaining_accounts.iter() {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+            let data = info.try_borrow_data()?;
+            let (heir_owner, heir_last_activity) = if data.starts_with(SolHeir::DISCRIMINATOR) {
+                let sol_heir = SolHeir::try_deserialize(&mut &data[..])?;
+                (sol_heir.owner, sol_heir.last_activity)
+            } else if data.starts_with(TokenHeir::DISCRIMINATOR) {
+                let token_heir = TokenHeir::try_deserialize(&mut &data[..])?;
+                (token_heir.owner, token_heir.last_activity)
+            } else {
+                return err!(ErrorCode::InvalidHeirAccount);
+            };
+            require_keys_eq!(heir_owner, owner, ErrorCode::Unauthorized);
+            last_activity = last_activity.max(heir_last_activity);
+        }
+        
+        // Grow the account to the current layout; new trailing fields start zeroed
+        let info = ctx.accounts.user_profile.to_account_info();
+        if info.data_len() < UserProfile::SPACE {
+            let rent_due = Rent::get()?
+                .minimum_balance(UserProfile::SPACE)
+                .saturating_sub(info.lamports());
+            if rent_due > 0 {
+                let cpi_accounts = system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+                system_program::transfer(cpi_ctx, rent_due)?;
+            }
+            info.realloc(UserProfile::SPACE, true)?;
+        }
+        
+        let mut data = info.try_borrow_mut_data()?;
+        let mut user_profile = UserProfile::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(user_profile.owner, owner, ErrorCode::Unauthorized);
+        
+        // Never move the heartbeat backwards if the profile was already migrated
+        user_profile.last_activity = user_profile.last_activity.max(last_activity);
+        user_profile.try_serialize(&mut &mut data[..])?;
+        
+        emit_estate_activity(&user_profile);
+        msg!("User profile migrated for: {}, last activity: {}", owner, user_profile.last_activity);
+        Ok(())
+    }
+
+    /// Grow a pre-tranche `SolHeir`/`TokenHeir` account to the current layout
+    /// (permissionless). The new `tranche` field reads as 0, which matches the
+    /// address the account was created at. SOL designations created before
+    /// vaults existed hold the bequest on the heir account itself; it moves to
+    /// the vault so the claim paths can pay it out.
+    pub fn migrate_heir_account(ctx: Context<MigrateHeirAccount>) -> Result<()> {
+        let info = ctx.accounts.heir_account.to_account_info();
+        let (space, is_sol_heir) = {
+            let data = info.try_borrow_data()?;
+            if data.starts_with(SolHeir::DISCRIMINATOR) {
+                (SolHeir::SPACE, true)
+            } else if data.starts_with(TokenHeir::DISCRIMINATOR) {
+                (TokenHeir::SPACE, false)
+            } else {
+                return err!(ErrorCode::InvalidHeirAccount);
+            }
+        };
+        
+        let rent = Rent::get()?;
+        if info.data_len() < space {
+            let rent_due = rent
+                .minimum_balance(space)
+                .saturating_sub(info.lamports());
+            if rent_due > 0 {
+                let cpi_accounts = system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+                system_program::transfer(cpi_ctx, rent_due)?;
+            }
+            info.realloc(space, true)?;
+        }
+        
+        // Everything above the rent minimum is a legacy escrow. The vault gets the
+        // same rent-exempt reserve `add_sol_heir` funds, paid by the caller.
+        let escrow = info.lamports().saturating_sub(rent.minimum_balance(space));
+        if is_sol_heir && escrow > 0 {
+            let vault = ctx.accounts.sol_vault.to_account_info();
+            let reserve_due = rent.minimum_balance(0).saturating_sub(vault.lamports());
+            if reserve_due > 0 {
+                let cpi_accounts = system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: vault.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+                system_program::transfer(cpi_ctx, reserve_due)?;
+            }
+            // The heir account is owned by this program, so it can be debited directly
+            info.sub_lamports(escrow)?;
+            vault.add_lamports(escrow)?;
+            msg!("Legacy escrow of {} lamports moved to vault: {}", escrow, vault.key());
+        }
+        
+        let data = info.try_borrow_data()?;
+        let (kind, owner, heir, mint, amount) = if is_sol_heir {
+            let sol_heir = SolHeir::try_deserialize(&mut &data[..])?;
+            (DesignationKind::Sol, sol_heir.owner, sol_heir.heir, None, sol_heir.amount)
+        } else {
+            let token_heir = TokenHeir::try_deserialize(&mut &data[..])?;
+            let kind = match token_heir.custody {
+                TokenCustody::Escrow => DesignationKind::Token,
+                TokenCustody::Delegate => DesignationKind::DelegatedToken,
+                TokenCustody::Share => DesignationKind::TokenShare,
+            };
+            (kind, token_heir.owner, token_heir.heir, Some(token_heir.token_mint), token_heir.amount)
+        };
+        emit!(HeirAccountMigrated {
+            designation: info.key(),
+            kind,
+            owner,
+            heir,
+            mint,
+            amount,
+            escrow_moved: if is_sol_heir { escrow } else { 0 },
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Heir account migrated: {}", info.key());
+        Ok(())
+    }
+
+    /// Update activity for SOL heir (resets inactivity timer).
+    /// Superseded by `heartbeat`, which covers all designations at once.
+    pub fn update_sol_activity(ctx: Context<UpdateSolActivity>) -> Result<()> {
+        let sol_heir = &mut ctx.accounts.sol_heir;
+        require_keys_eq!(sol_heir.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        
+        sol_heir.last_activity = Clock::get()?.unix_timestamp;
+        sol_heir.claim_requested_at = 0;
+        ctx.accounts.user_profile.record_activity()?;
+        emit!(ActivityUpdated {
+            owner: sol_heir.owner,
+            designation: Some(sol_heir.key()),
+            heir: Some(sol_heir.heir),
+            mint: None,
+            amount: Some(sol_heir.amount),
+            last_activity: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("SOL activity updated for owner: {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Update activity for token heir (resets inactivity timer).
+    /// Superseded by `heartbeat`, which covers all designations at once.
+    pub fn update_token_activity(ctx: Context<UpdateTokenActivity>) -> Result<()> {
+        let token_heir = &mut ctx.accounts.token_heir;
+        require_keys_eq!(token_heir.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        
+        token_heir.last_activity = Clock::get()?.unix_timestamp;
+        token_heir.claim_requested_at = 0;
+        ctx.accounts.user_profile.record_activity()?;
+        emit!(ActivityUpdated {
+            owner: token_heir.owner,
+            designation: Some(token_heir.key()),
+            heir: Some(token_heir.heir),
+            mint: Some(token_heir.token_mint),
+            amount: Some(token_heir.bequest()),
+            last_activity: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Token activity updated for owner: {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Add SOL to an existing SOL heir escrow
+    pub fn increase_sol_amount(ctx: Context<UpdateSolAmount>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let sol_heir = &mut ctx.accounts.sol_heir;
+        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        
+        let new_amount = sol_heir.amount.checked_add(amount).ok_or(ErrorCode::AmountTooLarge)?;
+        require!(new_amount <= MAX_SOL_AMOUNT, ErrorCode::AmountTooLarge); // Max 1M SOL
+        
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.sol_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        
+        sol_heir.amount = new_amount;
+        sol_heir.claim_requested_at = 0;
+        ctx.accounts.user_profile.record_activity()?;
+        emit!(ActivityUpdated {
+            owner: sol_heir.owner,
+            designation: Some(sol_heir.key()),
+            heir: Some(sol_heir.heir),
+            mint: None,
+            amount: Some(sol_heir.amount),
+            last_activity: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("SOL heir topped up: {} lamports, new total: {}", amount, new_amount);
+        Ok(())
+    }
+
+    /// Withdraw part of an existing SOL heir escrow back to the owner
+    pub fn decrease_sol_amount(ctx: Context<UpdateSolAmount>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let sol_heir = &mut ctx.accounts.sol_heir;
+        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        // Withdrawing everything is a cancellation, which also reclaims rent
+        require!(amount < sol_heir.amount, ErrorCode::InsufficientEscrowBalance);
+        require!(
+            sol_heir.amount - amount >= Rent::get()?.minimum_balance(0),
+            ErrorCode::BequestBelowRentExemption
+        );
+        
+        let sol_heir_key = sol_heir.key();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"sol_vault",
+            sol_heir_key.as_ref(),
+            &[ctx.bumps.sol_vault],
+        ]];
+        
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.sol_vault.to_account_info(),
+            to: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, seeds);
+        system_program::transfer(cpi_ctx, amount)?;
+        
+        sol_heir.amount -= amount;
+        sol_heir.claim_requested_at = 0;
+        ctx.accounts.user_profile.record_activity()?;
+        emit!(ActivityUpdated {
+            owner: sol_heir.owner,
+            designation: Some(sol_heir.key()),
+            heir: Some(sol_heir.heir),
+            mint: None,
+            amount: Some(sol_heir.amount),
+            last_activity: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("SOL heir reduced: {} lamports, new total: {}", amount, sol_heir.amount);
+        Ok(())
+    }
+
+    /// Add tokens to an existing token heir escrow
+    pub fn increase_token_amount<'info>(ctx: Context<'_, '_, '_, 'info, UpdateTokenAmount<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let token_heir = &mut ctx.accounts.token_heir;
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        
+        let new_amount = token_heir.amount.checked_add(amount).ok_or(ErrorCode::AmountTooLarge)?;
+        require!(new_amount <= MAX_TOKEN_AMOUNT, ErrorCode::AmountTooLarge); // Prevent overflow
+        
+        // Validate token accounts
+        require_keys_eq!(
+            ctx.accounts.owner_token_account.owner, 
+            ctx.accounts.owner.key(), 
+            ErrorCode::Unauthorized
+        );
+        require_keys_eq!(
+            ctx.accounts.owner_token_account.mint, 
+            token_heir.token_mint, 
+            ErrorCode::InvalidMint
+        );
+        
+        let escrow_before = ctx.accounts.escrow_token_account.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        
+        // Record what actually arrived, net of any Token-2022 transfer fee
+        ctx.accounts.escrow_token_account.reload()?;
+        let received = ctx.accounts.escrow_token_account.amount.saturating_sub(escrow_before);
+        token_heir.amount += received;
+        token_heir.claim_requested_at = 0;
+        ctx.accounts.user_profile.record_activity()?;
+        emit!(ActivityUpdated {
+            owner: token_heir.owner,
+            designation: Some(token_heir.key()),
+            heir: Some(token_heir.heir),
+            mint: Some(token_heir.token_mint),
+            amount: Some(token_heir.amount),
+            last_activity: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Token heir topped up: {} tokens, new total: {}", received, token_heir.amount);
+        Ok(())
+    }
+
+    /// Withdraw part of an existing token heir escrow back to the owner
+    pub fn decrease_token_amount<'info>(ctx: Context<'_, '_, '_, 'info, UpdateTokenAmount<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let token_heir = &mut ctx.accounts.token_heir;
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        // Withdrawing everything is a cancellation, which also reclaims rent
+        require!(amount < token_heir.amount, ErrorCode::InsufficientEscrowBalance);
+        
+        // Validate token accounts
+        require_keys_eq!(
+            ctx.accounts.owner_token_account.owner, 
+            ctx.accounts.owner.key(), 
+            ErrorCode::Unauthorized
+        );
+        require_keys_eq!(
+            ctx.accounts.owner_token_account.mint, 
+            token_heir.token_mint, 
+            ErrorCode::InvalidMint
+        );
+        
+        let tranche = tranche_seed(token_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_heir",
+            token_heir.owner.as_ref(),
+            token_heir.heir.as_ref(),
+            token_heir.token_mint.as_ref(),
+            &tranche,
+            &[token_heir.bump],
+        ]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: token_heir.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        
+        token_heir.amount -= amount;
+        token_heir.claim_requested_at = 0;
+        ctx.accounts.user_profile.record_activity()?;
+        emit!(ActivityUpdated {
+            owner: token_heir.owner,
+            designation: Some(token_heir.key()),
+            heir: Some(token_heir.heir),
+            mint: Some(token_heir.token_mint),
+            amount: Some(token_heir.amount),
+            last_activity: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Token heir reduced: {} tokens, new total: {}", amount, token_heir.amount);
+        Ok(())
+    }
+
+    /// Request a SOL inheritance claim once the owner has been inactive for the
+    /// designation's period. Starts the owner's claim notice period.
+    pub fn request_sol_claim(ctx: Context<RequestSolClaim>) -> Result<()> {
+        let sol_heir = &mut ctx.accounts.sol_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Validate claim conditions
+        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require_keys_eq!(sol_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_requestable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            sol_heir.last_activity,
+            sol_heir.inactivity_period_seconds,
+            sol_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        sol_heir.claim_requested_at = current_time;
+        emit!(ClaimRequested {
+            designation: sol_heir.key(),
+            kind: DesignationKind::Sol,
+            owner: sol_heir.owner,
+            heir: sol_heir.heir,
+            mint: None,
+            amount: sol_heir.amount,
+            requested_at: current_time,
+            finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+        });
+        msg!("SOL claim requested, finalizable after {} seconds", ctx.accounts.user_profile.claim_notice_period());
+        Ok(())
+    }
+
+    /// Finalize a SOL inheritance claim after the notice period. Pays the bequest
+    /// to the heir, then closes the designation and returns the vault reserve and
+    /// all rent to the owner.
+    pub fn finalize_sol_claim(ctx: Context<FinalizeSolClaim>) -> Result<()> {
+        let sol_heir = &ctx.accounts.sol_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Validate claim conditions
+        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require_keys_eq!(sol_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            sol_heir.last_activity,
+            sol_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        // Transfer SOL from the vault to heir's wallet
+        let sol_heir_key = sol_heir.key();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"sol_vault",
+            sol_heir_key.as_ref(),
+            &[ctx.bumps.sol_vault],
+        ]];
+        
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.sol_vault.to_account_info(),
+            to: ctx.accounts.heir.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, seeds);
+        system_program::transfer(cpi_ctx, sol_heir.amount)?;
+        
+        // Sweep the rent reserve left in the vault back to the owner
+        sweep_sol_vault(
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(Claimed {
+            designation: sol_heir.key(),
+            kind: DesignationKind::Sol,
+            owner: sol_heir.owner,
+            heir: sol_heir.heir,
+            mint: None,
+            amount: sol_heir.amount,
+            timestamp: current_time,
+        });
+        msg!("SOL inheritance claimed: {} lamports", sol_heir.amount);
+        Ok(())
+    }
+
+    /// Request a token inheritance claim once the owner has been inactive for
+    /// the designation's period. Starts the owner's claim notice period.
+    pub fn request_token_claim(ctx: Context<RequestTokenClaim>) -> Result<()> {
+        let token_heir = &mut ctx.accounts.token_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Validate claim conditions
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require_keys_eq!(token_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_requestable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_heir.last_activity,
+            token_heir.inactivity_period_seconds,
+            token_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        token_heir.claim_requested_at = current_time;
+        emit!(ClaimRequested {
+            designation: token_heir.key(),
+            kind: token_heir.kind(),
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: token_heir.bequest(),
+            requested_at: current_time,
+            finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+        });
+        msg!("Token claim requested, finalizable after {} seconds", ctx.accounts.user_profile.claim_notice_period());
+        Ok(())
+    }
+
+    /// Finalize a token inheritance claim after the notice period. Pays the
+    /// escrow to the heir, then closes the escrow ATA and the designation and
+    /// returns all rent to the owner.
+    pub fn finalize_token_claim<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeTokenClaim<'info>>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Validate claim conditions
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require_keys_eq!(token_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_heir.last_activity,
+            token_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        // Validate token accounts
+        require_keys_eq!(
+            ctx.accounts.heir_token_account.owner, 
+            ctx.accounts.heir.key(), 
+            ErrorCode::Unauthorized
+        );
+        require_keys_eq!(
+            ctx.accounts.heir_token_account.mint, 
+            token_heir.token_mint, 
+            ErrorCode::InvalidMint
+        );
+        require_keys_eq!(
+            ctx.accounts.escrow_token_account.mint, 
+            token_heir.token_mint, 
+            ErrorCode::InvalidMint
+        );
+        require!(!ctx.accounts.escrow_token_account.is_frozen(), ErrorCode::EscrowFrozen);
+        
+        // Transfer tokens from escrow to heir
+        let tranche = tranche_seed(token_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_heir",
+            token_heir.owner.as_ref(),
+            token_heir.heir.as_ref(),
+            token_heir.token_mint.as_ref(),
+            &tranche,
+            &[token_heir.bump],
+        ]];
+        
+        // Anything sent to the escrow directly goes to the heir as well, so
+        // the escrow is empty and can be closed
+        let escrowed = ctx.accounts.escrow_token_account.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.heir_token_account.to_account_info(),
+            authority: token_heir.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, escrowed, ctx.accounts.token_mint.decimals)?;
+        
+        harvest_withheld_fees(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: token_heir.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds);
+        token_interface::close_account(cpi_ctx)?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(Claimed {
+            designation: token_heir.key(),
+            kind: DesignationKind::Token,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: escrowed,
+            timestamp: current_time,
+        });
+        msg!("Token inheritance claimed: {} tokens", escrowed);
+        Ok(())
+    }
+
+    /// Finalize a delegated token claim after the notice period. Pulls the
+    /// smallest of the bequeathed amount, the remaining allowance and the
+    /// owner's balance straight from the owner's token account, then closes
+    /// the designation. Fails if the owner revoked or reassigned the allowance.
+    pub fn finalize_delegated_token_claim<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeDelegatedTokenClaim<'info>>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Validate claim conditions
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require_keys_eq!(token_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_heir.last_activity,
+            token_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let owner_token_account = &ctx.accounts.owner_token_account;
+        require!(
+            owner_token_account.delegate == Some(token_heir.key()).into() && owner_token_account.delegated_amount > 0,
+            ErrorCode::DelegateAllowanceRevoked
+        );
+        require!(!owner_token_account.is_frozen(), ErrorCode::EscrowFrozen);
+        
+        let claimable = token_heir
+            .amount
+            .min(owner_token_account.delegated_amount)
+            .min(owner_token_account.amount);
+        if claimable > 0 {
+            let tranche = tranche_seed(token_heir.tranche);
+            let seeds: &[&[&[u8]]] = &[&[
+                b"token_heir",
+                token_heir.owner.as_ref(),
+                token_heir.heir.as_ref(),
+                token_heir.token_mint.as_ref(),
+                &tranche,
+                &[token_heir.bump],
+            ]];
+            
+            let cpi_accounts = TransferChecked {
+                from: owner_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.heir_token_account.to_account_info(),
+                authority: token_heir.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx, claimable, ctx.accounts.token_mint.decimals)?;
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(Claimed {
+            designation: token_heir.key(),
+            kind: DesignationKind::DelegatedToken,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: claimable,
+            timestamp: current_time,
+        });
+        msg!("Delegated token inheritance claimed: {} of {} tokens", claimable, token_heir.amount);
+        Ok(())
+    }
+
+    /// Finalize a share claim after the notice period. The first share claimed
+    /// since the owner's last activity snapshots the owner's balance; every
+    /// share is paid from that snapshot, capped by what is left and allowed.
+    pub fn finalize_token_share_claim<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeTokenShareClaim<'info>>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Validate claim conditions
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require_keys_eq!(token_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_heir.last_activity,
+            token_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let token_estate = &mut ctx.accounts.token_estate;
+        let owner_token_account = &ctx.accounts.owner_token_account;
+        require!(
+            owner_token_account.delegate == Some(token_estate.key()).into() && owner_token_account.delegated_amount > 0,
+            ErrorCode::DelegateAllowanceRevoked
+        );
+        require!(!owner_token_account.is_frozen(), ErrorCode::EscrowFrozen);
+        
+        // A snapshot from before the owner's last sign of life is stale
+        if token_estate.snapshot_taken_at <= ctx.accounts.user_profile.last_activity {
+            token_estate.snapshot_balance = owner_token_account.amount;
+            token_estate.snapshot_taken_at = current_time;
+        }
+        let share = (token_estate.snapshot_balance as u128 * token_heir.share_bps as u128
+            / BPS_DENOMINATOR as u128) as u64;
+        let claimable = share
+            .min(owner_token_account.delegated_amount)
+            .min(owner_token_account.amount);
+        token_estate.total_share_bps = token_estate.total_share_bps.saturating_sub(token_heir.share_bps);
+        
+        if claimable > 0 {
+            let seeds: &[&[&[u8]]] = &[&[
+                b"token_estate",
+                token_estate.owner.as_ref(),
+                token_estate.token_mint.as_ref(),
+                &[token_estate.bump],
+            ]];
+            let cpi_accounts = TransferChecked {
+                from: owner_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.heir_token_account.to_account_info(),
+                authority: token_estate.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx, claimable, ctx.accounts.token_mint.decimals)?;
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(Claimed {
+            designation: token_heir.key(),
+            kind: DesignationKind::TokenShare,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: claimable,
+            timestamp: current_time,
+        });
+        msg!("Token share claimed: {} tokens ({} bps of {})", claimable, token_heir.share_bps, token_estate.snapshot_balance);
+        Ok(())
+    }
+
+    /// Request an NFT bundle claim once the owner has been inactive for the
+    /// designation's period. Starts the owner's claim notice period.
+    pub fn request_nft_claim(ctx: Context<RequestNftClaim>) -> Result<()> {
+        let nft_heir = &mut ctx.accounts.nft_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(nft_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_requestable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            nft_heir.last_activity,
+            nft_heir.inactivity_period_seconds,
+            nft_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        nft_heir.claim_requested_at = current_time;
+        for mint in nft_heir.mints.iter() {
+            emit!(ClaimRequested {
+                designation: nft_heir.key(),
+                kind: DesignationKind::NftBundle,
+                owner: nft_heir.owner,
+                heir: nft_heir.heir,
+                mint: Some(*mint),
+                amount: 1,
+                requested_at: current_time,
+                finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+            });
+        }
+        msg!("NFT claim requested, finalizable after {} seconds", ctx.accounts.user_profile.claim_notice_period());
+        Ok(())
+    }
+
+    /// Finalize an NFT bundle claim after the notice period, delivering every
+    /// NFT at once. Pass `[mint, escrow_token_account, heir_token_account]` per
+    /// NFT, in the order the bundle lists its mints; the heir's ATAs are created
+    /// if needed. Escrows and the designation are closed with rent to the owner.
+    pub fn finalize_nft_claim<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeNftClaim<'info>>) -> Result<()> {
+        let nft_heir = &ctx.accounts.nft_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(nft_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            nft_heir.last_activity,
+            nft_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let nft_accounts = account_triples(ctx.remaining_accounts).ok_or(ErrorCode::InvalidNftBundle)?;
+        require!(nft_accounts.len() == nft_heir.mints.len(), ErrorCode::InvalidNftBundle);
+        
+        let tranche = tranche_seed(nft_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"nft_heir",
+            nft_heir.owner.as_ref(),
+            nft_heir.heir.as_ref(),
+            &tranche,
+            &[nft_heir.bump],
+        ]];
+        
+        for ([mint_info, escrow_info, heir_nft_info], expected_mint) in nft_accounts.into_iter().zip(&nft_heir.mints) {
+            require_keys_eq!(mint_info.key(), *expected_mint, ErrorCode::InvalidMint);
+            
+            create_ata_if_needed(
+                &ctx.accounts.heir,
+                heir_nft_info,
+                &ctx.accounts.heir,
+                mint_info,
+                &ctx.accounts.system_program,
+                &ctx.accounts.token_program,
+                &ctx.accounts.associated_token_program,
+            )?;
+            
+            let released = release_escrow(
+                &InterfaceAccount::<Mint>::try_from(mint_info)?,
+                &InterfaceAccount::<TokenAccount>::try_from(escrow_info)?,
+                heir_nft_info,
+                nft_heir,
+                &ctx.accounts.owner,
+                &ctx.accounts.token_program,
+                seeds,
+            )?;
+            emit!(Claimed {
+                designation: nft_heir.key(),
+                kind: DesignationKind::NftBundle,
+                owner: nft_heir.owner,
+                heir: nft_heir.heir,
+                mint: Some(*expected_mint),
+                amount: released,
+                timestamp: current_time,
+            });
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        msg!("NFT inheritance claimed: {} NFTs", nft_heir.mints.len());
+        Ok(())
+    }
+
+    /// Request a token basket claim once the owner has been inactive for the
+    /// designation's period. Starts the owner's claim notice period.
+    pub fn request_token_basket_claim(ctx: Context<RequestTokenBasketClaim>) -> Result<()> {
+        let token_basket_heir = &mut ctx.accounts.token_basket_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(token_basket_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_requestable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_basket_heir.last_activity,
+            token_basket_heir.inactivity_period_seconds,
+            token_basket_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        token_basket_heir.claim_requested_at = current_time;
+        for entry in token_basket_heir.entries.iter() {
+            emit!(ClaimRequested {
+                designation: token_basket_heir.key(),
+                kind: DesignationKind::TokenBasket,
+                owner: token_basket_heir.owner,
+                heir: token_basket_heir.heir,
+                mint: Some(entry.mint),
+                amount: entry.amount,
+                requested_at: current_time,
+                finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+            });
+        }
+        msg!("Token basket claim requested, finalizable after {} seconds", ctx.accounts.user_profile.claim_notice_period());
+        Ok(())
+    }
+
+    /// Finalize a token basket claim after the notice period, paying out every
+    /// entry at once. Pass `[mint, escrow_token_account, heir_token_account]` per
+    /// entry, in the basket's order; the heir's ATAs are created if needed.
+    /// Escrows and the designation are closed with rent to the owner.
+    pub fn finalize_token_basket_claim<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeTokenBasketClaim<'info>>) -> Result<()> {
+        let token_basket_heir = &ctx.accounts.token_basket_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(token_basket_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_basket_heir.last_activity,
+            token_basket_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let entry_accounts = account_triples(ctx.remaining_accounts).ok_or(ErrorCode::InvalidTokenBasket)?;
+        require!(entry_accounts.len() == token_basket_heir.entries.len(), ErrorCode::InvalidTokenBasket);
+        
+        let tranche = tranche_seed(token_basket_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_basket_heir",
+            token_basket_heir.owner.as_ref(),
+            token_basket_heir.heir.as_ref(),
+            &tranche,
+            &[token_basket_heir.bump],
+        ]];
+        
+        for ([mint_info, escrow_info, heir_token_info], entry) in entry_accounts.into_iter().zip(&token_basket_heir.entries) {
+            require_keys_eq!(mint_info.key(), entry.mint, ErrorCode::InvalidMint);
+            
+            create_ata_if_needed(
+                &ctx.accounts.heir,
+                heir_token_info,
+                &ctx.accounts.heir,
+                mint_info,
+                &ctx.accounts.system_program,
+                &ctx.accounts.token_program,
+                &ctx.accounts.associated_token_program,
+            )?;
+            
+            // Anything sent to an escrow directly goes to the heir as well
+            let released = release_escrow(
+                &InterfaceAccount::<Mint>::try_from(mint_info)?,
+                &InterfaceAccount::<TokenAccount>::try_from(escrow_info)?,
+                heir_token_info,
+                token_basket_heir,
+                &ctx.accounts.owner,
+                &ctx.accounts.token_program,
+                seeds,
+            )?;
+            emit!(Claimed {
+                designation: token_basket_heir.key(),
+                kind: DesignationKind::TokenBasket,
+                owner: token_basket_heir.owner,
+                heir: token_basket_heir.heir,
+                mint: Some(entry.mint),
+                amount: released,
+                timestamp: current_time,
+            });
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        msg!("Token basket inheritance claimed: {} mints", token_basket_heir.entries.len());
+        Ok(())
+    }
+
+    /// Request a stake account claim once the owner has been inactive for the
+    /// designation's period. Starts the owner's claim notice period.
+    pub fn request_stake_claim(ctx: Context<RequestStakeClaim>) -> Result<()> {
+        let stake_heir = &mut ctx.accounts.stake_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(stake_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_requestable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            stake_heir.last_activity,
+            stake_heir.inactivity_period_seconds,
+            stake_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        stake_heir.claim_requested_at = current_time;
+        emit!(ClaimRequested {
+            designation: stake_heir.key(),
+            kind: DesignationKind::Stake,
+            owner: stake_heir.owner,
+            heir: stake_heir.heir,
+            mint: None,
+            amount: ctx.accounts.stake_account.lamports(),
+            requested_at: current_time,
+            finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+        });
+        msg!("Stake claim requested, finalizable after {} seconds", ctx.accounts.user_profile.claim_notice_period());
+        Ok(())
+    }
+
+    /// Finalize a stake account claim after the notice period. Both stake
+    /// authorities pass to the heir without deactivating the stake; the
+    /// designation is closed with rent to the owner.
+    pub fn finalize_stake_claim(ctx: Context<FinalizeStakeClaim>) -> Result<()> {
+        let stake_heir = &ctx.accounts.stake_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(stake_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            stake_heir.last_activity,
+            stake_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let seeds: &[&[&[u8]]] = &[&[
+            b"stake_heir",
+            stake_heir.owner.as_ref(),
+            stake_heir.heir.as_ref(),
+            stake_heir.stake_account.as_ref(),
+            &[stake_heir.bump],
+        ]];
+        transfer_stake_authorities(
+            &ctx.accounts.stake_account,
+            stake_heir,
+            &ctx.accounts.heir,
+            &ctx.accounts.clock,
+            &ctx.accounts.stake_program,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(Claimed {
+            designation: stake_heir.key(),
+            kind: DesignationKind::Stake,
+            owner: stake_heir.owner,
+            heir: stake_heir.heir,
+            mint: None,
+            amount: ctx.accounts.stake_account.lamports(),
+            timestamp: current_time,
+        });
+        msg!("Stake inheritance claimed: stake account {} now controlled by {}", stake_heir.stake_account, ctx.accounts.heir.key());
+        Ok(())
+    }
+
+    /// Request a program upgrade authority claim once the owner has been
+    /// inactive for the designation's period. Starts the owner's claim notice period.
+    pub fn request_program_claim(ctx: Context<RequestProgramClaim>) -> Result<()> {
+        let program_heir = &mut ctx.accounts.program_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(program_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_requestable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            program_heir.last_activity,
+            program_heir.inactivity_period_seconds,
+            program_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        program_heir.claim_requested_at = current_time;
+        emit!(ClaimRequested {
+            designation: program_heir.key(),
+            kind: DesignationKind::Program,
+            owner: program_heir.owner,
+            heir: program_heir.heir,
+            mint: None,
+            amount: 0,
+            requested_at: current_time,
+            finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+        });
+        msg!("Program claim requested, finalizable after {} seconds", ctx.accounts.user_profile.claim_notice_period());
+        Ok(())
+    }
+
+    /// Finalize a program upgrade authority claim after the notice period. The
+    /// upgrade authority passes to the heir; the designation is closed with
+    /// rent to the owner.
+    pub fn finalize_program_claim(ctx: Context<FinalizeProgramClaim>) -> Result<()> {
+        let program_heir = &ctx.accounts.program_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require_keys_eq!(program_heir.heir, ctx.accounts.heir.key(), ErrorCode::Unauthorized);
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            program_heir.last_activity,
+            program_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let seeds: &[&[&[u8]]] = &[&[
+            b"program_heir",
+            program_heir.owner.as_ref(),
+            program_heir.heir.as_ref(),
+            program_heir.upgradeable_program.as_ref(),
+            &[program_heir.bump],
+        ]];
+        transfer_upgrade_authority(
+            &ctx.accounts.upgradeable_program,
+            &ctx.accounts.program_data,
+            program_heir,
+            &ctx.accounts.heir,
+            &ctx.accounts.upgradeable_loader,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(Claimed {
+            designation: program_heir.key(),
+            kind: DesignationKind::Program,
+            owner: program_heir.owner,
+            heir: program_heir.heir,
+            mint: None,
+            amount: 0,
+            timestamp: current_time,
+        });
+        msg!("Program inheritance claimed: program {} now upgradeable by {}", program_heir.upgradeable_program, ctx.accounts.heir.key());
+        Ok(())
+    }
+
+    /// Advance a SOL claim on the heir's behalf (permissionless). Without a live
+    /// request the crank requests the claim; after the notice period it pays
+    /// the bequest to the recorded heir, minus the owner's crank bounty, which
+    /// goes to the caller. Rent and the vault reserve return to the owner.
+    pub fn crank_sol_claim(ctx: Context<CrankSolClaim>) -> Result<()> {
+        let sol_heir = &mut ctx.accounts.sol_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        if crank_starts_claim(&ctx.accounts.user_profile, &ctx.accounts.guardian_set, sol_heir.last_activity, sol_heir.claim_requested_at)? {
+            require_claim_requestable(
+                &ctx.accounts.user_profile,
+                &ctx.accounts.guardian_set,
+                sol_heir.last_activity,
+                sol_heir.inactivity_period_seconds,
+                sol_heir.claim_requested_at,
+                current_time,
+            )?;
+            sol_heir.claim_requested_at = current_time;
+            emit!(ClaimRequested {
+                designation: sol_heir.key(),
+                kind: DesignationKind::Sol,
+                owner: sol_heir.owner,
+                heir: sol_heir.heir,
+                mint: None,
+                amount: sol_heir.amount,
+                requested_at: current_time,
+                finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+            });
+            msg!("SOL claim requested by crank {}, finalizable after {} seconds", ctx.accounts.cranker.key(), ctx.accounts.user_profile.claim_notice_period());
+            return Ok(());
+        }
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            sol_heir.last_activity,
+            sol_heir.claim_requested_at,
+            current_time,
+        )?;
+        
+        let sol_heir_key = sol_heir.key();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"sol_vault",
+            sol_heir_key.as_ref(),
+            &[ctx.bumps.sol_vault],
+        ]];
+        
+        // The bounty gives way where it would leave a new heir account short of
+        // rent exemption, which would otherwise fail every crank
+        let heir_shortfall = Rent::get()?.minimum_balance(0).saturating_sub(ctx.accounts.heir.lamports());
+        let bounty = ctx
+            .accounts
+            .user_profile
+            .crank_bounty(sol_heir.amount)
+            .min(sol_heir.amount.saturating_sub(heir_shortfall));
+        for (destination, lamports) in [
+            (ctx.accounts.heir.to_account_info(), sol_heir.amount - bounty),
+            (ctx.accounts.cranker.to_account_info(), bounty),
+        ] {
+            if lamports == 0 {
+                continue;
+            }
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.sol_vault.to_account_info(),
+                to: destination,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, seeds);
+            system_program::transfer(cpi_ctx, lamports)?;
+        }
+        
+        // Sweep the rent reserve left in the vault back to the owner
+        sweep_sol_vault(
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            seeds,
+        )?;
+        emit!(Claimed {
+            designation: sol_heir.key(),
+            kind: DesignationKind::Sol,
+            owner: sol_heir.owner,
+            heir: sol_heir.heir,
+            mint: None,
+            amount: sol_heir.amount - bounty,
+            timestamp: current_time,
+        });
+        let amount = sol_heir.amount;
+        ctx.accounts.sol_heir.close(ctx.accounts.owner.to_account_info())?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        msg!("SOL inheritance cranked: {} lamports to heir, {} lamports bounty", amount - bounty, bounty);
+        Ok(())
+    }
+
+    /// Advance an escrowed token claim on the heir's behalf (permissionless),
+    /// like `crank_sol_claim`. The heir's ATA is created if needed at the
+    /// caller's expense, and the bounty is paid in tokens from the escrow.
+    pub fn crank_token_claim<'info>(ctx: Context<'_, '_, '_, 'info, CrankTokenClaim<'info>>) -> Result<()> {
+        let token_heir = &mut ctx.accounts.token_heir;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        if crank_starts_claim(&ctx.accounts.user_profile, &ctx.accounts.guardian_set, token_heir.last_activity, token_heir.claim_requested_at)? {
+            require_claim_requestable(
+                &ctx.accounts.user_profile,
+                &ctx.accounts.guardian_set,
+                token_heir.last_activity,
+                token_heir.inactivity_period_seconds,
+                token_heir.claim_requested_at,
+                current_time,
+            )?;
+            token_heir.claim_requested_at = current_time;
+            emit!(ClaimRequested {
+                designation: token_heir.key(),
+                kind: DesignationKind::Token,
+                owner: token_heir.owner,
+                heir: token_heir.heir,
+                mint: Some(token_heir.token_mint),
+                amount: token_heir.amount,
+                requested_at: current_time,
+                finalizable_at: current_time + ctx.accounts.user_profile.claim_notice_period(),
+            });
+            msg!("Token claim requested by crank {}, finalizable after {} seconds", ctx.accounts.cranker.key(), ctx.accounts.user_profile.claim_notice_period());
+            return Ok(());
+        }
+        require_claim_finalizable(
+            &ctx.accounts.user_profile,
+            &ctx.accounts.guardian_set,
+            token_heir.last_activity,
+            token_heir.claim_requested_at,
+            current_time,
+        )?;
+        require!(!ctx.accounts.escrow_token_account.is_frozen(), ErrorCode::EscrowFrozen);
+        
+        let tranche = tranche_seed(token_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_heir",
+            token_heir.owner.as_ref(),
+            token_heir.heir.as_ref(),
+            token_heir.token_mint.as_ref(),
+            &tranche,
+            &[token_heir.bump],
+        ]];
+        
+        // Everything in the escrow is paid out, so it can be closed
+        let escrowed = ctx.accounts.escrow_token_account.amount;
+        let bounty = ctx.accounts.user_profile.crank_bounty(escrowed);
+        for (destination, amount) in [
+            (ctx.accounts.heir_token_account.to_account_info(), escrowed - bounty),
+            (ctx.accounts.cranker_token_account.to_account_info(), bounty),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: destination,
+                authority: token_heir.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        }
+        
+        harvest_withheld_fees(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: token_heir.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds);
+        token_interface::close_account(cpi_ctx)?;
+        emit!(Claimed {
+            designation: token_heir.key(),
+            kind: DesignationKind::Token,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: escrowed - bounty,
+            timestamp: current_time,
+        });
+        ctx.accounts.token_heir.close(ctx.accounts.owner.to_account_info())?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        msg!("Token inheritance cranked: {} tokens to heir, {} tokens bounty", escrowed - bounty, bounty);
+        Ok(())
+    }
+
+    /// Close a SOL designation claimed before claims closed their accounts
+    /// (permissionless; rent and the vault reserve go to the owner)
+    pub fn close_claimed_sol_heir(ctx: Context<CloseClaimedSolHeir>) -> Result<()> {
+        require!(ctx.accounts.sol_heir.is_claimed, ErrorCode::NotClaimed);
+        
+        let sol_heir_key = ctx.accounts.sol_heir.key();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"sol_vault",
+            sol_heir_key.as_ref(),
+            &[ctx.bumps.sol_vault],
+        ]];
+        sweep_sol_vault(
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        let sol_heir = &ctx.accounts.sol_heir;
+        emit!(ClaimedDesignationClosed {
+            designation: sol_heir_key,
+            kind: DesignationKind::Sol,
+            owner: sol_heir.owner,
+            heir: sol_heir.heir,
+            mint: None,
+            amount: sol_heir.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Claimed SOL heir closed, rent returned to owner: {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Close a token designation and its empty escrow claimed before claims
+    /// closed their accounts (permissionless; rent goes to the owner)
+    pub fn close_claimed_token_heir(ctx: Context<CloseClaimedTokenHeir>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        require!(token_heir.is_claimed, ErrorCode::NotClaimed);
+        require!(ctx.accounts.escrow_token_account.amount == 0, ErrorCode::EscrowNotEmpty);
+        
+        let tranche = tranche_seed(token_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_heir",
+            token_heir.owner.as_ref(),
+            token_heir.heir.as_ref(),
+            token_heir.token_mint.as_ref(),
+            &tranche,
+            &[token_heir.bump],
+        ]];
+        
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: token_heir.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds);
+        token_interface::close_account(cpi_ctx)?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        
+        emit!(ClaimedDesignationClosed {
+            designation: token_heir.key(),
+            kind: DesignationKind::Token,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: token_heir.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Claimed token heir closed, rent returned to owner: {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel a SOL heir designation (closes the account and refunds escrow plus rent to owner)
+    pub fn cancel_sol_heir(ctx: Context<CancelSolHeir>) -> Result<()> {
+        let sol_heir = &ctx.accounts.sol_heir;
+        require!(!sol_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        
+        // Drain the vault (bequest plus rent reserve) back to the owner. Designations
+        // created before vaults existed hold the escrow on the heir account itself,
+        // which closing it below refunds as well.
+        let sol_heir_key = sol_heir.key();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"sol_vault",
+            sol_heir_key.as_ref(),
+            &[ctx.bumps.sol_vault],
+        ]];
+        sweep_sol_vault(
+            &ctx.accounts.sol_vault,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        emit!(Cancelled {
+            designation: sol_heir.key(),
+            kind: DesignationKind::Sol,
+            owner: sol_heir.owner,
+            heir: sol_heir.heir,
+            mint: None,
+            amount: sol_heir.amount,
+            timestamp: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("SOL heir cancelled: {} lamports returned to owner: {}", sol_heir.amount, ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel a token heir designation (returns escrowed tokens, closes escrow and heir accounts)
+    pub fn cancel_token_heir<'info>(ctx: Context<'_, '_, '_, 'info, CancelTokenHeir<'info>>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        require!(!ctx.accounts.escrow_token_account.is_frozen(), ErrorCode::EscrowFrozen);
+        
+        // Validate token accounts
+        require_keys_eq!(
+            ctx.accounts.owner_token_account.owner, 
+            ctx.accounts.owner.key(), 
+            ErrorCode::Unauthorized
+        );
+        require_keys_eq!(
+            ctx.accounts.owner_token_account.mint, 
+            token_heir.token_mint, 
+            ErrorCode::InvalidMint
+        );
+        
+        let tranche = tranche_seed(token_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_heir",
+            token_heir.owner.as_ref(),
+            token_heir.heir.as_ref(),
+            token_heir.token_mint.as_ref(),
+            &tranche,
+            &[token_heir.bump],
+        ]];
+        
+        // Return the full escrow balance, including anything sent to the ATA directly
+        let escrowed = ctx.accounts.escrow_token_account.amount;
+        if escrowed > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.owner_token_account.to_account_info(),
+                authority: token_heir.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(cpi_ctx, escrowed, ctx.accounts.token_mint.decimals)?;
+        }
+        
+        // Close the now empty escrow ATA, sending its rent to the owner
+        harvest_withheld_fees(
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: token_heir.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, seeds);
+        token_interface::close_account(cpi_ctx)?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        emit!(Cancelled {
+            designation: token_heir.key(),
+            kind: DesignationKind::Token,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: escrowed,
+            timestamp: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Token heir cancelled: {} tokens returned to owner: {}", escrowed, ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel a delegated token heir designation, revoking its allowance on
+    /// the owner's token account if it still holds it
+    pub fn cancel_delegated_token_heir(ctx: Context<CancelDelegatedTokenHeir>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        
+        if ctx.accounts.owner_token_account.delegate == Some(token_heir.key()).into() {
+            let cpi_accounts = Revoke {
+                source: ctx.accounts.owner_token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::revoke(cpi_ctx)?;
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        emit!(Cancelled {
+            designation: token_heir.key(),
+            kind: DesignationKind::DelegatedToken,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: token_heir.amount,
+            timestamp: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Delegated token heir cancelled for owner: {}", ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel a share designation. Revokes the estate's allowance on the
+    /// owner's token account once no shares of it remain.
+    pub fn cancel_token_share_heir(ctx: Context<CancelTokenShareHeir>) -> Result<()> {
+        let token_heir = &ctx.accounts.token_heir;
+        require!(!token_heir.is_claimed, ErrorCode::AlreadyClaimed);
+        
+        let token_estate = &mut ctx.accounts.token_estate;
+        token_estate.total_share_bps = token_estate.total_share_bps.saturating_sub(token_heir.share_bps);
+        
+        if token_estate.total_share_bps == 0
+            && ctx.accounts.owner_token_account.delegate == Some(token_estate.key()).into()
+        {
+            let cpi_accounts = Revoke {
+                source: ctx.accounts.owner_token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::revoke(cpi_ctx)?;
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        emit!(Cancelled {
+            designation: token_heir.key(),
+            kind: DesignationKind::TokenShare,
+            owner: token_heir.owner,
+            heir: token_heir.heir,
+            mint: Some(token_heir.token_mint),
+            amount: token_heir.share_bps as u64,
+            timestamp: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Token share heir cancelled: {} bps returned to owner: {}", token_heir.share_bps, ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel an NFT bundle designation, returning every NFT to the owner. Pass
+    /// `[mint, escrow_token_account, owner_token_account]` per NFT, in the order
+    /// the bundle lists its mints.
+    pub fn cancel_nft_heir<'info>(ctx: Context<'_, '_, 'info, 'info, CancelNftHeir<'info>>) -> Result<()> {
+        let nft_heir = &ctx.accounts.nft_heir;
+        
+        let nft_accounts = account_triples(ctx.remaining_accounts).ok_or(ErrorCode::InvalidNftBundle)?;
+        require!(nft_accounts.len() == nft_heir.mints.len(), ErrorCode::InvalidNftBundle);
+        
+        let tranche = tranche_seed(nft_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"nft_heir",
+            nft_heir.owner.as_ref(),
+            nft_heir.heir.as_ref(),
+            &tranche,
+            &[nft_heir.bump],
+        ]];
+        
+        for ([mint_info, escrow_info, owner_nft_info], expected_mint) in nft_accounts.into_iter().zip(&nft_heir.mints) {
+            require_keys_eq!(mint_info.key(), *expected_mint, ErrorCode::InvalidMint);
+            
+            let owner_nft_account = InterfaceAccount::<TokenAccount>::try_from(owner_nft_info)?;
+            require_keys_eq!(owner_nft_account.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+            
+            release_escrow(
+                &InterfaceAccount::<Mint>::try_from(mint_info)?,
+                &InterfaceAccount::<TokenAccount>::try_from(escrow_info)?,
+                owner_nft_info,
+                nft_heir,
+                &ctx.accounts.owner,
+                &ctx.accounts.token_program,
+                seeds,
+            )?;
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        for mint in nft_heir.mints.iter() {
+            emit!(Cancelled {
+                designation: nft_heir.key(),
+                kind: DesignationKind::NftBundle,
+                owner: nft_heir.owner,
+                heir: nft_heir.heir,
+                mint: Some(*mint),
+                amount: 1,
+                timestamp: ctx.accounts.user_profile.last_activity,
+            });
+        }
+        msg!("NFT heir cancelled: {} NFTs returned to owner: {}", nft_heir.mints.len(), ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel a token basket designation, returning every escrow to the owner.
+    /// Pass `[mint, escrow_token_account, owner_token_account]` per entry, in
+    /// the basket's order.
+    pub fn cancel_token_basket_heir<'info>(ctx: Context<'_, '_, 'info, 'info, CancelTokenBasketHeir<'info>>) -> Result<()> {
+        let token_basket_heir = &ctx.accounts.token_basket_heir;
+        
+        let entry_accounts = account_triples(ctx.remaining_accounts).ok_or(ErrorCode::InvalidTokenBasket)?;
+        require!(entry_accounts.len() == token_basket_heir.entries.len(), ErrorCode::InvalidTokenBasket);
+        
+        let tranche = tranche_seed(token_basket_heir.tranche);
+        let seeds: &[&[&[u8]]] = &[&[
+            b"token_basket_heir",
+            token_basket_heir.owner.as_ref(),
+            token_basket_heir.heir.as_ref(),
+            &tranche,
+            &[token_basket_heir.bump],
+        ]];
+        
+        for ([mint_info, escrow_info, owner_token_info], entry) in entry_accounts.into_iter().zip(&token_basket_heir.entries) {
+            require_keys_eq!(mint_info.key(), entry.mint, ErrorCode::InvalidMint);
+            
+            let owner_token_account = InterfaceAccount::<TokenAccount>::try_from(owner_token_info)?;
+            require_keys_eq!(owner_token_account.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+            
+            release_escrow(
+                &InterfaceAccount::<Mint>::try_from(mint_info)?,
+                &InterfaceAccount::<TokenAccount>::try_from(escrow_info)?,
+                owner_token_info,
+                token_basket_heir,
+                &ctx.accounts.owner,
+                &ctx.accounts.token_program,
+                seeds,
+            )?;
+        }
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        for entry in token_basket_heir.entries.iter() {
+            emit!(Cancelled {
+                designation: token_basket_heir.key(),
+                kind: DesignationKind::TokenBasket,
+                owner: token_basket_heir.owner,
+                heir: token_basket_heir.heir,
+                mint: Some(entry.mint),
+                amount: entry.amount,
+                timestamp: ctx.accounts.user_profile.last_activity,
+            });
+        }
+        msg!("Token basket heir cancelled: {} mints returned to owner: {}", token_basket_heir.entries.len(), ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Cancel a stake account designation, handing both stake authorities
+    /// back to the owner
+    pub fn cancel_stake_heir(ctx: Context<CancelStakeHeir>) -> Result<()> {
+        let stake_heir = &ctx.accounts.stake_heir;
+        
+        let seeds: &[&[&[u8]]] = &[&[
+            b"stake_heir",
+            stake_heir.owner.as_ref(),
+            stake_heir.heir.as_ref(),
+            stake_heir.stake_account.as_ref(),
+            &[stake_heir.bump],
+        ]];
+        transfer_stake_authorities(
+            &ctx.accounts.stake_account,
+            stake_heir,
+            &ctx.accounts.owner,
+            &ctx.accounts.clock,
+            &ctx.accounts.stake_program,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        emit!(Cancelled {
+            designation: stake_heir.key(),
+            kind: DesignationKind::Stake,
+            owner: stake_heir.owner,
+            heir: stake_heir.heir,
+            mint: None,
+            amount: ctx.accounts.stake_account.lamports(),
+            timestamp: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Stake heir cancelled: stake account {} returned to owner: {}", stake_heir.stake_account, ctx.accounts.owner.key());
+        Ok(())
+    }
+
+    /// Reclaim a program's upgrade authority, cancelling its designation
+    pub fn cancel_program_heir(ctx: Context<CancelProgramHeir>) -> Result<()> {
+        let program_heir = &ctx.accounts.program_heir;
+        
+        let seeds: &[&[&[u8]]] = &[&[
+            b"program_heir",
+            program_heir.owner.as_ref(),
+            program_heir.heir.as_ref(),
+            program_heir.upgradeable_program.as_ref(),
+            &[program_heir.bump],
+        ]];
+        transfer_upgrade_authority(
+            &ctx.accounts.upgradeable_program,
+            &ctx.accounts.program_data,
+            program_heir,
+            &ctx.accounts.owner,
+            &ctx.accounts.upgradeable_loader,
+            seeds,
+        )?;
+        
+        let user_profile = &mut ctx.accounts.user_profile;
+        user_profile.total_inheritances = user_profile.total_inheritances.saturating_sub(1);
+        user_profile.record_activity()?;
+        
+        emit!(Cancelled {
+            designation: program_heir.key(),
+            kind: DesignationKind::Program,
+            owner: program_heir.owner,
+            heir: program_heir.heir,
+            mint: None,
+            amount: 0,
+            timestamp: ctx.accounts.user_profile.last_activity,
+        });
+        msg!("Program heir cancelled: program {} returned to owner: {}", program_heir.upgradeable_program, ctx.accounts.owner.key());
+        Ok(())
+    }
+}
+
+// ===============================================
+// HELPERS
+// ===============================================
+
+/// PDA seed for a designation's tranche index. Tranche 0 contributes an empty
+/// seed, so designations created before tranches existed keep their address.
+pub fn tranche_seed(tranche: u16) -> Vec<u8> {
+    if tranche == 0 {
+        Vec::new()
+    } else {
+        tranche.to_le_bytes().to_vec()
+    }
+}
+
+/// Apply the owner's guardian set, if one exists, to a claim. Fails while the
+/// guardians have frozen claims; otherwise returns whether a guardian quorum
+/// attested the owner's death after their last activity.
+fn guardian_attestation(guardian_set: &AccountInfo, last_activity: i64) -> Result<bool> {
+    if guardian_set.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*guardian_set.owner, crate::ID, ErrorCode::Unauthorized);
+    let data = guardian_set.try_borrow_data()?;
+    let guardian_set = GuardianSet::try_deserialize(&mut &data[..])?;
+    
+    require!(!guardian_set.frozen, ErrorCode::ClaimsFrozen);
+    Ok(guardian_set.deceased_attested_at > last_activity)
+}
+
+/// Check that the owner has been inactive long enough (or attested deceased)
+/// for a claim to be requested and that no unvetoed request is already pending
+fn require_claim_requestable(
+    user_profile: &UserProfile,
+    guardian_set: &AccountInfo,
+    designation_last_activity: i64,
+    inactivity_period_seconds: i64,
+    claim_requested_at: i64,
+    current_time: i64,
+) -> Result<()> {
+    let last_activity = user_profile.last_activity_for(designation_last_activity);
+    if !guardian_attestation(guardian_set, last_activity)? {
+        require!(
+            current_time - last_activity > inactivity_period_seconds,
+            ErrorCode::OwnerStillActive
+        );
+    }
+    // A request the owner has since vetoed may be replaced
+    require!(claim_requested_at <= last_activity, ErrorCode::ClaimAlreadyRequested);
+    Ok(())
+}
+
+/// Emit `ActivityUpdated` for estate-wide owner activity (heartbeats and settings)
+fn emit_estate_activity(user_profile: &UserProfile) {
+    emit!(ActivityUpdated {
+        owner: user_profile.owner,
+        designation: None,
+        heir: None,
+        mint: None,
+        amount: None,
+        last_activity: user_profile.last_activity,
+    });
+}
+
+fn emit_guardian_action(guardian_set: &GuardianSet, signers: &[AccountInfo], action: GuardianActionKind) -> Result<()> {
+    emit!(GuardianActionTaken {
+        owner: guardian_set.owner,
+        action,
+        guardians: guardian_set.signed_by(signers),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Whether a crank should request the claim rather than finalize it: no live
+/// request (none made, or vetoed since) and no guardian attestation standing in
+/// for one
+fn crank_starts_claim(
+    user_profile: &UserProfile,
+    guardian_set: &AccountInfo,
+    designation_last_activity: i64,
+    claim_requested_at: i64,
+) -> Result<bool> {
+    let last_activity = user_profile.last_activity_for(designation_last_activity);
+    Ok(claim_requested_at <= last_activity && !guardian_attestation(guardian_set, last_activity)?)
+}
+
+/// Check that a claim was requested, that the owner has not shown any activity
+/// since (which vetoes it), and that the notice period has elapsed. A guardian
+/// attestation of death makes the claim final without a request.
+fn require_claim_finalizable(
+    user_profile: &UserProfile,
+    guardian_set: &AccountInfo,
+    designation_last_activity: i64,
+    claim_requested_at: i64,
+    current_time: i64,
+) -> Result<()> {
+    let last_activity = user_profile.last_activity_for(designation_last_activity);
+    if guardian_attestation(guardian_set, last_activity)? {
+        return Ok(());
+    }
+    
+    require!(claim_requested_at > 0, ErrorCode::ClaimNotRequested);
+    require!(last_activity < claim_requested_at, ErrorCode::ClaimVetoed);
+    require!(
+        current_time - claim_requested_at >= user_profile.claim_notice_period(),
+        ErrorCode::NoticePeriodActive
+    );
+    Ok(())
+}
+
+/// `token_interface::transfer_checked`, but forwarding the context's remaining
+/// accounts. Token-2022 mints with a transfer hook need the hook program, its
+/// extra-account-metas account and every account that list names.
+fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let mut ix = token_interface::spl_token_2022::instruction::transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from.key,
+        ctx.accounts.mint.key,
+        ctx.accounts.to.key,
+        ctx.accounts.authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    let mut account_infos = vec![
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+    ];
+    for info in ctx.remaining_accounts {
+        ix.accounts.push(AccountMeta {
+            pubkey: info.key(),
+            is_signer: info.is_signer,
+            is_writable: info.is_writable,
+        });
+        account_infos.push(info);
+    }
+    invoke_signed(&ix, &account_infos, ctx.signer_seeds).map_err(Into::into)
+}
+
+/// Move Token-2022 transfer fees withheld on an escrow to the mint, since an
+/// account holding withheld fees cannot be closed. Harvesting is permissionless.
+fn harvest_withheld_fees<'info>(
+    escrow_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if token_program.key() != token_interface::spl_token_2022::ID {
+        return Ok(());
+    }
+    
+    let escrow_info = escrow_token_account.to_account_info();
+    let withheld = {
+        let data = escrow_info.try_borrow_data()?;
+        let escrow = StateWithExtensions::<token_interface::spl_token_2022::state::Account>::unpack(&data)?;
+        escrow
+            .get_extension::<TransferFeeAmount>()
+            .map(|fee| u64::from(fee.withheld_amount))
+            .unwrap_or(0)
+    };
+    if withheld == 0 {
+        return Ok(());
+    }
+    
+    let ix = harvest_withheld_tokens_to_mint(&token_program.key(), &token_mint.key(), &[&escrow_info.key()])?;
+    invoke(&ix, &[token_mint.to_account_info(), escrow_info, token_program.to_account_info()])?;
+    Ok(())
+}
+
+/// Apply the owner's `MintRiskPolicy` to a mint about to be escrowed by `designation`
+fn apply_mint_risk_policy(mint: &InterfaceAccount<Mint>, policy: MintRiskPolicy, designation: Pubkey) -> Result<()> {
+    let risks = MintRisks::inspect(mint)?;
+    if !risks.any() {
+        return Ok(());
+    }
+    match policy {
+        MintRiskPolicy::Reject => {
+            require!(!risks.freeze_authority, ErrorCode::MintHasFreezeAuthority);
+            return err!(ErrorCode::MintHasPermanentDelegate);
+        }
+        MintRiskPolicy::Warn => emit!(RiskyMintAccepted {
+            token_heir: designation,
+            token_mint: mint.key(),
+            freeze_authority: risks.freeze_authority,
+            permanent_delegate: risks.permanent_delegate,
+        }),
+        MintRiskPolicy::Allow => {}
+    }
+    Ok(())
+}
+
+/// Split remaining accounts into one triple per designation entry (NFT or
+/// basket mint). Returns `None` if a triple is incomplete.
+fn account_triples<'a, 'info>(remaining_accounts: &'a [AccountInfo<'info>]) -> Option<Vec<[&'a AccountInfo<'info>; 3]>> {
+    let chunks = remaining_accounts.chunks_exact(3);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    Some(chunks.map(|accounts| [&accounts[0], &accounts[1], &accounts[2]]).collect())
+}
+
+/// Check that a mint is an NFT: no decimals and exactly one token in existence
+fn require_nft_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    require!(mint.decimals == 0 && mint.supply == 1, ErrorCode::NotAnNft);
+    Ok(())
+}
+
+/// Reject Token-2022 mints with a transfer hook. Multi-mint escrows move their
+/// tokens without the hook's extra accounts, so such a mint would be stranded
+/// in the escrow at claim time.
+fn require_no_transfer_hook(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != token_interface::spl_token_2022::ID {
+        return Ok(());
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<token_interface::spl_token_2022::state::Mint>::unpack(&data)?;
+    let hooked = state
+        .get_extension::<TransferHook>()
+        .is_ok_and(|ext| Option::<Pubkey>::from(ext.program_id).is_some());
+    require!(!hooked, ErrorCode::MintHasTransferHook);
+    Ok(())
+}
+
+/// Create `authority`'s ATA for `mint` unless it exists. The associated token
+/// program rejects any `associated_token` that is not that ATA.
+fn create_ata_if_needed<'info>(
+    payer: &impl ToAccountInfo<'info>,
+    associated_token: &AccountInfo<'info>,
+    authority: &impl ToAccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+    associated_token_program: &Program<'info, AssociatedToken>,
+) -> Result<()> {
+    let cpi_accounts = associated_token::Create {
+        payer: payer.to_account_info(),
+        associated_token: associated_token.clone(),
+        authority: authority.to_account_info(),
+        mint: mint.clone(),
+        system_program: system_program.to_account_info(),
+        token_program: token_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(associated_token_program.to_account_info(), cpi_accounts);
+    associated_token::create_idempotent(cpi_ctx)
+}
+
+/// Move an escrow's whole balance to `destination`, then close the escrow and
+/// send its rent to `rent_destination`. Signed by the designation owning the escrow.
+fn release_escrow<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    escrow_token_account: &InterfaceAccount<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    authority: &impl ToAccountInfo<'info>,
+    rent_destination: &impl ToAccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    require!(!escrow_token_account.is_frozen(), ErrorCode::EscrowFrozen);
+    
+    let escrowed = escrow_token_account.amount;
+    if escrowed > 0 {
+        let cpi_accounts = TransferChecked {
+            from: escrow_token_account.to_account_info(),
+            mint: mint.to_account_info(),
+            to: destination.clone(),
+            authority: authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token_interface::transfer_checked(cpi_ctx, escrowed, mint.decimals)?;
+    }
+    
+    harvest_withheld_fees(escrow_token_account, mint, token_program)?;
+    let cpi_accounts = CloseAccount {
+        account: escrow_token_account.to_account_info(),
+        destination: rent_destination.to_account_info(),
+        authority: authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+    Ok(escrowed)
+}
+
+/// Move both the staker and withdrawer authority of a stake account from
+/// `authority` to `new_authority`
+fn transfer_stake_authorities<'info>(
+    stake_account: &AccountInfo<'info>,
+    authority: &impl ToAccountInfo<'info>,
+    new_authority: &impl ToAccountInfo<'info>,
+    clock: &Sysvar<'info, Clock>,
+    stake_program: &Program<'info, Stake>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    for stake_authorize in [StakeAuthorize::Staker, StakeAuthorize::Withdrawer] {
+        let cpi_accounts = stake::Authorize {
+            stake: stake_account.clone(),
+            authorized: authority.to_account_info(),
+            new_authorized: new_authority.to_account_info(),
+            clock: clock.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(stake_program.to_account_info(), cpi_accounts, signer_seeds);
+        stake::authorize(cpi_ctx, stake_authorize, None)?;
+    }
+    Ok(())
+}
+
+/// Hand the upgrade authority of `upgradeable_program` from `authority` to
+/// `new_authority` through the upgradeable loader's `SetAuthority`
+fn transfer_upgrade_authority<'info>(
+    upgradeable_program: &AccountInfo<'info>,
+    program_data: &impl ToAccountInfo<'info>,
+    authority: &impl ToAccountInfo<'info>,
+    new_authority: &impl ToAccountInfo<'info>,
+    upgradeable_loader: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let authority = authority.to_account_info();
+    let new_authority = new_authority.to_account_info();
+    let ix = set_upgrade_authority(upgradeable_program.key, authority.key, Some(new_authority.key));
+    invoke_signed(
+        &ix,
+        &[program_data.to_account_info(), authority, new_authority, upgradeable_loader.clone()],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Move every lamport left in a SOL vault to `destination`
+fn sweep_sol_vault<'info>(
+    sol_vault: &SystemAccount<'info>,
+    destination: &impl ToAccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let balance = sol_vault.lamports();
+    if balance == 0 {
+        return Ok(());
+    }
+    
+    let cpi_accounts = system_program::Transfer {
+        from: sol_vault.to_account_info(),
+        to: destination.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(system_program.to_account_info(), cpi_accounts, signer_seeds);
+    system_program::transfer(cpi_ctx, balance)
+}
+
+// ===============================================
+// ACCOUNT STRUCTURES
+// ===============================================
+
+#[derive(Accounts)]
+pub struct InitializeUser<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = UserProfile::SPACE,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tranche: u16)]
+pub struct AddSolHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = SolHeir::SPACE,
+        seeds = [b"sol_heir", owner.key().as_ref(), heir.key().as_ref(), &tranche_seed(tranche)],
+        bump
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    /// Data-less vault holding the escrowed lamports
+    #[account(
+        mut,
+        seeds = [b"sol_vault", sol_heir.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tranche: u16)]
+pub struct AddTokenHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = TokenHeir::SPACE,
+        seeds = [b"token_heir", owner.key().as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(tranche)],
+        bump
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_heir,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tranche: u16)]
+pub struct AddDelegatedTokenHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = TokenHeir::SPACE,
+        seeds = [b"token_heir", owner.key().as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(tranche)],
+        bump
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    /// The owner's token account the designation is approved to draw from
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tranche: u16)]
+pub struct AddTokenShareHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = TokenHeir::SPACE,
+        seeds = [b"token_heir", owner.key().as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(tranche)],
+        bump
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = TokenEstate::SPACE,
+        seeds = [b"token_estate", owner.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub token_estate: Account<'info, TokenEstate>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    /// The owner's token account the shares are drawn from
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tranche: u16)]
+pub struct AddNftHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = NftHeir::SPACE,
+        seeds = [b"nft_heir", owner.key().as_ref(), heir.key().as_ref(), &tranche_seed(tranche)],
+        bump
+    )]
+    pub nft_heir: Account<'info, NftHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tranche: u16)]
+pub struct AddTokenBasketHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = TokenBasketHeir::SPACE,
+        seeds = [b"token_basket_heir", owner.key().as_ref(), heir.key().as_ref(), &tranche_seed(tranche)],
+        bump
+    )]
+    pub token_basket_heir: Account<'info, TokenBasketHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddStakeHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = StakeHeir::SPACE,
+        seeds = [b"stake_heir", owner.key().as_ref(), heir.key().as_ref(), stake_account.key().as_ref()],
+        bump
+    )]
+    pub stake_heir: Account<'info, StakeHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    /// CHECK: Owned by the stake program; its state is checked by the handler
+    #[account(mut, owner = stake_program.key())]
+    pub stake_account: AccountInfo<'info>,
+    
+    pub clock: Sysvar<'info, Clock>,
+    pub stake_program: Program<'info, Stake>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddProgramHeir<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = ProgramHeir::SPACE,
+        seeds = [b"program_heir", owner.key().as_ref(), heir.key().as_ref(), upgradeable_program.key().as_ref()],
+        bump
+    )]
+    pub program_heir: Account<'info, ProgramHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// CHECK: This is the heir's public key
+    pub heir: AccountInfo<'info>,
+    
+    /// CHECK: Owned by the upgradeable loader; its program data is derived from it
+    #[account(owner = upgradeable_loader.key() @ ErrorCode::InvalidUpgradeableProgram)]
+    pub upgradeable_program: AccountInfo<'info>,
+    
+    #[account(mut, address = get_program_data_address(upgradeable_program.key) @ ErrorCode::InvalidUpgradeableProgram)]
+    pub program_data: Account<'info, ProgramData>,
+    
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = ProgramData::owner())]
+    pub upgradeable_loader: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump,
+        has_one = owner
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterLivenessKey<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LivenessKey::SPACE,
+        seeds = [b"liveness_key", owner.key().as_ref(), key.key().as_ref()],
+        bump
+    )]
+    pub liveness_key: Account<'info, LivenessKey>,
+    
+    /// CHECK: Only the public key is recorded; it never needs to sign here
+    pub key: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump,
+        has_one = owner
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeLivenessKey<'info> {
+    #[account(
+        mut,
+        seeds = [b"liveness_key", owner.key().as_ref(), liveness_key.key.as_ref()],
+        bump = liveness_key.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub liveness_key: Account<'info, LivenessKey>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump,
+        has_one = owner
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedHeartbeat<'info> {
+    #[account(
+        seeds = [b"liveness_key", liveness_key.owner.as_ref(), key.key().as_ref()],
+        bump = liveness_key.bump,
+        has_one = key
+    )]
+    pub liveness_key: Account<'info, LivenessKey>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", liveness_key.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    pub key: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardians<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = GuardianSet::SPACE,
+        seeds = [b"guardian_set", owner.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump,
+        has_one = owner
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Guardian signatures are passed as remaining accounts
+#[derive(Accounts)]
+pub struct GuardianAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"guardian_set", guardian_set.owner.as_ref()],
+        bump = guardian_set.bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserProfile<'info> {
+    /// CHECK: Legacy layouts cannot be deserialized as `UserProfile` until resized;
+    /// the handler validates the discriminator and owner after resizing.
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub user_profile: UncheckedAccount<'info>,
+    
+    /// CHECK: Only used to derive the profile address; migration is permissionless
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateHeirAccount<'info> {
+    /// CHECK: Legacy layouts cannot be deserialized until resized; the handler
+    /// checks the discriminator, and only this program's accounts are accepted.
+    #[account(mut, owner = crate::ID)]
+    pub heir_account: UncheckedAccount<'info>,
+    
+    /// Vault receiving a legacy SOL escrow; unused for token designations
+    #[account(
+        mut,
+        seeds = [b"sol_vault", heir_account.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSolActivity<'info> {
+    #[account(
+        mut,
+        seeds = [b"sol_heir", owner.key().as_ref(), sol_heir.heir.as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump,
+        has_one = owner
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenActivity<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_heir.token_mint.as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSolAmount<'info> {
+    #[account(
+        mut,
+        seeds = [b"sol_heir", owner.key().as_ref(), sol_heir.heir.as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump,
+        has_one = owner
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    /// Data-less vault holding the escrowed lamports
+    #[account(
+        mut,
+        seeds = [b"sol_vault", sol_heir.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenAmount<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Escrow @ ErrorCode::WrongTokenCustody
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_heir,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RequestSolClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"sol_heir", sol_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    #[account(
+        seeds = [b"user_profile", sol_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", sol_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    pub heir: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSolClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"sol_heir", sol_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    /// Data-less vault holding the escrowed lamports
+    #[account(
+        mut,
+        seeds = [b"sol_vault", sol_heir.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", sol_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", sol_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent and the vault reserve; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub heir: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankSolClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"sol_heir", sol_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump,
+        has_one = owner
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    /// Data-less vault holding the escrowed lamports
+    #[account(
+        mut,
+        seeds = [b"sol_vault", sol_heir.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", sol_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", sol_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent and the vault reserve; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    /// CHECK: The recorded heir; pinned by the designation's seeds
+    #[account(mut)]
+    pub heir: UncheckedAccount<'info>,
+    
+    /// Anyone; receives the crank bounty
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestTokenClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", token_heir.owner.as_ref(), heir.key().as_ref(), token_heir.token_mint.as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        seeds = [b"user_profile", token_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    pub heir: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTokenClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", token_heir.owner.as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Escrow @ ErrorCode::WrongTokenCustody,
+        close = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", token_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed accounts; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_heir,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = heir,
+        associated_token::mint = token_mint,
+        associated_token::authority = heir,
+        associated_token::token_program = token_program
+    )]
+    pub heir_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Receives the escrow's withheld Token-2022 transfer fees before it closes
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub heir: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankTokenClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", token_heir.owner.as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Escrow @ ErrorCode::WrongTokenCustody
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", token_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed accounts; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_heir,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = heir,
+        associated_token::token_program = token_program
+    )]
+    pub heir_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = cranker,
+        associated_token::token_program = token_program
+    )]
+    pub cranker_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Receives the escrow's withheld Token-2022 transfer fees before it closes
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: The recorded heir; pinned by the designation's seeds
+    pub heir: UncheckedAccount<'info>,
+    
+    /// Anyone; pays for missing token accounts and receives the crank bounty
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDelegatedTokenClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", token_heir.owner.as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Delegate @ ErrorCode::WrongTokenCustody,
+        close = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", token_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed designation; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = heir,
+        associated_token::mint = token_mint,
+        associated_token::authority = heir,
+        associated_token::token_program = token_program
+    )]
+    pub heir_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub heir: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTokenShareClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", token_heir.owner.as_ref(), heir.key().as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Share @ ErrorCode::WrongTokenCustody,
+        close = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_estate", owner.key().as_ref(), token_mint.key().as_ref()],
+        bump = token_estate.bump
+    )]
+    pub token_estate: Account<'info, TokenEstate>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", token_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed designation; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = heir,
+        associated_token::mint = token_mint,
+        associated_token::authority = heir,
+        associated_token::token_program = token_program
+    )]
+    pub heir_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub heir: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestNftClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"nft_heir", nft_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(nft_heir.tranche)],
+        bump = nft_heir.bump
+    )]
+    pub nft_heir: Account<'info, NftHeir>,
+    
+    #[account(
+        seeds = [b"user_profile", nft_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", nft_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    pub heir: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeNftClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"nft_heir", nft_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(nft_heir.tranche)],
+        bump = nft_heir.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub nft_heir: Account<'info, NftHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", nft_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", nft_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed accounts; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub heir: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestTokenBasketClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_basket_heir", token_basket_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(token_basket_heir.tranche)],
+        bump = token_basket_heir.bump
+    )]
+    pub token_basket_heir: Account<'info, TokenBasketHeir>,
+    
+    #[account(
+        seeds = [b"user_profile", token_basket_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_basket_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    pub heir: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTokenBasketClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_basket_heir", token_basket_heir.owner.as_ref(), heir.key().as_ref(), &tranche_seed(token_basket_heir.tranche)],
+        bump = token_basket_heir.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub token_basket_heir: Account<'info, TokenBasketHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", token_basket_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", token_basket_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed accounts; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub heir: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestStakeClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_heir", stake_heir.owner.as_ref(), heir.key().as_ref(), stake_account.key().as_ref()],
+        bump = stake_heir.bump,
+        has_one = stake_account
+    )]
+    pub stake_heir: Account<'info, StakeHeir>,
+    
+    #[account(
+        seeds = [b"user_profile", stake_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", stake_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: The designated stake account, read for its balance; pinned by `has_one = stake_account`
+    pub stake_account: AccountInfo<'info>,
+    
+    pub heir: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeStakeClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_heir", stake_heir.owner.as_ref(), heir.key().as_ref(), stake_account.key().as_ref()],
+        bump = stake_heir.bump,
+        has_one = owner,
+        has_one = stake_account,
+        close = owner
+    )]
+    pub stake_heir: Account<'info, StakeHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", stake_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", stake_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed designation; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    /// CHECK: The designated stake account; pinned by `has_one = stake_account`
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+    
+    pub heir: Signer<'info>,
+    
+    pub clock: Sysvar<'info, Clock>,
+    pub stake_program: Program<'info, Stake>,
+}
+
+#[derive(Accounts)]
+pub struct RequestProgramClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_heir", program_heir.owner.as_ref(), heir.key().as_ref(), program_heir.upgradeable_program.as_ref()],
+        bump = program_heir.bump
+    )]
+    pub program_heir: Account<'info, ProgramHeir>,
+    
+    #[account(
+        seeds = [b"user_profile", program_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", program_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    pub heir: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProgramClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_heir", program_heir.owner.as_ref(), heir.key().as_ref(), upgradeable_program.key().as_ref()],
+        bump = program_heir.bump,
+        has_one = owner,
+        has_one = upgradeable_program,
+        close = owner
+    )]
+    pub program_heir: Account<'info, ProgramHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", program_heir.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: The owner's guardian set; may be uninitialized if the owner has none
+    #[account(seeds = [b"guardian_set", program_heir.owner.as_ref()], bump)]
+    pub guardian_set: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives rent from the closed designation; pinned by `has_one = owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    /// CHECK: The designated program; pinned by `has_one = upgradeable_program`
+    pub upgradeable_program: AccountInfo<'info>,
+    
+    /// CHECK: Derived from the designated program; the loader checks the authority
+    #[account(mut, address = get_program_data_address(upgradeable_program.key))]
+    pub program_data: UncheckedAccount<'info>,
+    
+    pub heir: Signer<'info>,
+    
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = ProgramData::owner())]
+    pub upgradeable_loader: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSolHeir<'info> {
+    #[account(
+        mut,
+        seeds = [b"sol_heir", owner.key().as_ref(), sol_heir.heir.as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub sol_heir: Account<'info, SolHeir>,
+    
+    /// Data-less vault holding the escrowed lamports
+    #[account(
+        mut,
+        seeds = [b"sol_vault", sol_heir.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTokenHeir<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Escrow @ ErrorCode::WrongTokenCustody,
+        close = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// Receives the escrow's withheld Token-2022 transfer fees before it closes
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_heir,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelDelegatedTokenHeir<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Delegate @ ErrorCode::WrongTokenCustody,
+        close = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTokenShareHeir<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_mint.key().as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Share @ ErrorCode::WrongTokenCustody,
+        close = owner
+    )]
+    pub token_heir: Account<'info, TokenHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_estate", owner.key().as_ref(), token_mint.key().as_ref()],
+        bump = token_estate.bump
+    )]
+    pub token_estate: Account<'info, TokenEstate>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
     pub owner: Signer<'info>,
     
-    /// CHECK: This is the heir's public key
-    pub heir: AccountInfo<'info>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct AddTokenHeir<'info> {
+pub struct CancelNftHeir<'info> {
     #[account(
-        init,
-        payer = owner,
-        space = TokenHeir::SPACE,
-        seeds = [b"token_heir", owner.key().as_ref(), heir.key().as_ref(), token_mint.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"nft_heir", owner.key().as_ref(), nft_heir.heir.as_ref(), &tranche_seed(nft_heir.tranche)],
+        bump = nft_heir.bump,
+        has_one = owner,
+        close = owner
     )]
-    pub token_heir: Account<'info, TokenHeir>,
+    pub nft_heir: Account<'info, NftHeir>,
     
     #[account(
         mut,
@@ -314,96 +4249,162 @@ pub struct AddTokenHeir<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
     
-    /// CHECK: This is the heir's public key
-    pub heir: AccountInfo<'info>,
-    
-    pub token_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTokenBasketHeir<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_basket_heir", owner.key().as_ref(), token_basket_heir.heir.as_ref(), &tranche_seed(token_basket_heir.tranche)],
+        bump = token_basket_heir.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub token_basket_heir: Account<'info, TokenBasketHeir>,
     
     #[account(
-        init_if_needed,
-        payer = owner,
-        associated_token::mint = token_mint,
-        associated_token::authority = token_heir
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub user_profile: Account<'info, UserProfile>,
     
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateSolActivity<'info> {
+pub struct CancelStakeHeir<'info> {
     #[account(
         mut,
-        seeds = [b"sol_heir", owner.key().as_ref(), sol_heir.heir.as_ref()],
-        bump = sol_heir.bump,
-        has_one = owner
+        seeds = [b"stake_heir", owner.key().as_ref(), stake_heir.heir.as_ref(), stake_account.key().as_ref()],
+        bump = stake_heir.bump,
+        has_one = owner,
+        has_one = stake_account,
+        close = owner
     )]
-    pub sol_heir: Account<'info, SolHeir>,
+    pub stake_heir: Account<'info, StakeHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
     
+    #[account(mut)]
     pub owner: Signer<'info>,
+    
+    /// CHECK: The designated stake account; pinned by `has_one = stake_account`
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+    
+    pub clock: Sysvar<'info, Clock>,
+    pub stake_program: Program<'info, Stake>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateTokenActivity<'info> {
+pub struct CancelProgramHeir<'info> {
     #[account(
         mut,
-        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_heir.token_mint.as_ref()],
-        bump = token_heir.bump,
-        has_one = owner
+        seeds = [b"program_heir", owner.key().as_ref(), program_heir.heir.as_ref(), upgradeable_program.key().as_ref()],
+        bump = program_heir.bump,
+        has_one = owner,
+        has_one = upgradeable_program,
+        close = owner
     )]
-    pub token_heir: Account<'info, TokenHeir>,
+    pub program_heir: Account<'info, ProgramHeir>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
     
+    #[account(mut)]
     pub owner: Signer<'info>,
+    
+    /// CHECK: The designated program; pinned by `has_one = upgradeable_program`
+    pub upgradeable_program: AccountInfo<'info>,
+    
+    /// CHECK: Derived from the designated program; the loader checks the authority
+    #[account(mut, address = get_program_data_address(upgradeable_program.key))]
+    pub program_data: UncheckedAccount<'info>,
+    
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = ProgramData::owner())]
+    pub upgradeable_loader: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimSolInheritance<'info> {
+pub struct CloseClaimedSolHeir<'info> {
     #[account(
         mut,
-        seeds = [b"sol_heir", sol_heir.owner.as_ref(), heir.key().as_ref()],
-        bump = sol_heir.bump
+        seeds = [b"sol_heir", owner.key().as_ref(), sol_heir.heir.as_ref(), &tranche_seed(sol_heir.tranche)],
+        bump = sol_heir.bump,
+        has_one = owner,
+        close = owner
     )]
     pub sol_heir: Account<'info, SolHeir>,
     
+    /// Data-less vault holding the escrowed lamports
+    #[account(
+        mut,
+        seeds = [b"sol_vault", sol_heir.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// CHECK: Receives rent; pinned by `has_one = owner`
     #[account(mut)]
-    pub heir: Signer<'info>,
+    pub owner: UncheckedAccount<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimTokenInheritance<'info> {
+pub struct CloseClaimedTokenHeir<'info> {
     #[account(
         mut,
-        seeds = [b"token_heir", token_heir.owner.as_ref(), heir.key().as_ref(), token_mint.key().as_ref()],
-        bump = token_heir.bump
+        seeds = [b"token_heir", owner.key().as_ref(), token_heir.heir.as_ref(), token_heir.token_mint.as_ref(), &tranche_seed(token_heir.tranche)],
+        bump = token_heir.bump,
+        has_one = owner,
+        constraint = token_heir.custody == TokenCustody::Escrow @ ErrorCode::WrongTokenCustody,
+        close = owner
     )]
     pub token_heir: Account<'info, TokenHeir>,
     
-    #[account(mut)]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    
     #[account(
-        init_if_needed,
-        payer = heir,
-        associated_token::mint = token_mint,
-        associated_token::authority = heir
+        mut,
+        seeds = [b"user_profile", owner.key().as_ref()],
+        bump = user_profile.bump
     )]
-    pub heir_token_account: Account<'info, TokenAccount>,
-    
-    pub token_mint: Account<'info, Mint>,
+    pub user_profile: Account<'info, UserProfile>,
     
+    /// CHECK: Receives rent; pinned by `has_one = owner`
     #[account(mut)]
-    pub heir: Signer<'info>,
+    pub owner: UncheckedAccount<'info>,
     
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        associated_token::mint = token_heir.token_mint,
+        associated_token::authority = token_heir,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 // ===============================================
@@ -415,10 +4416,38 @@ pub struct UserProfile {
     pub owner: Pubkey,
     pub total_inheritances: u32,
     pub bump: u8,
+    /// Estate-wide proof of life, updated by `heartbeat` and every owner instruction
+    pub last_activity: i64,
+    /// Delay between a claim request and its finalization, during which the owner can veto
+    pub claim_notice_period_seconds: i64,
+    /// Share of each bequest paid to whoever cranks the claim
+    pub crank_bounty_bps: u16,
 }
 
 impl UserProfile {
-    pub const SPACE: usize = 8 + 32 + 4 + 1;
+    pub const SPACE: usize = 8 + 32 + 4 + 1 + 8 + 8 + 2;
+    
+    /// Record owner activity now, vetoing any pending claim request
+    pub fn record_activity(&mut self) -> Result<()> {
+        self.last_activity = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+    
+    /// Effective claim notice period (migrated profiles start at zero)
+    pub fn claim_notice_period(&self) -> i64 {
+        self.claim_notice_period_seconds.max(MIN_CLAIM_NOTICE_PERIOD_SECONDS)
+    }
+    
+    /// Crank bounty owed out of a bequest of `amount`
+    pub fn crank_bounty(&self, amount: u64) -> u64 {
+        (amount as u128 * self.crank_bounty_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+    
+    /// Effective last activity for a designation: the newer of the estate-wide
+    /// heartbeat and the designation's own (legacy) timestamp.
+    pub fn last_activity_for(&self, designation_last_activity: i64) -> i64 {
+        self.last_activity.max(designation_last_activity)
+    }
 }
 
 #[account]
@@ -430,10 +4459,14 @@ pub struct SolHeir {
     pub last_activity: i64,
     pub is_claimed: bool,
     pub bump: u8,
+    /// Index distinguishing several bequests from the same owner to the same heir
+    pub tranche: u16,
+    /// When the heir requested the claim (0 if no request is pending)
+    pub claim_requested_at: i64,
 }
 
 impl SolHeir {
-    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 1;
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 1 + 2 + 8;
 }
 
 #[account]
@@ -446,10 +4479,392 @@ pub struct TokenHeir {
     pub last_activity: i64,
     pub is_claimed: bool,
     pub bump: u8,
+    /// Index distinguishing several bequests of the same mint to the same heir
+    pub tranche: u16,
+    /// When the heir requested the claim (0 if no request is pending)
+    pub claim_requested_at: i64,
+    /// Where the bequeathed tokens are held until the claim
+    pub custody: TokenCustody,
+    /// Basis points of the owner's balance bequeathed (share custody only;
+    /// `amount` is unused then)
+    pub share_bps: u16,
 }
 
 impl TokenHeir {
-    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1;
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1 + 2 + 8 + 1 + 2;
+    
+    /// Designation kind reported in events, by custody mode
+    pub fn kind(&self) -> DesignationKind {
+        match self.custody {
+            TokenCustody::Escrow => DesignationKind::Token,
+            TokenCustody::Delegate => DesignationKind::DelegatedToken,
+            TokenCustody::Share => DesignationKind::TokenShare,
+        }
+    }
+    
+    /// Bequest reported in events: base units, or basis points for shares
+    pub fn bequest(&self) -> u64 {
+        match self.custody {
+            TokenCustody::Share => self.share_bps as u64,
+            _ => self.amount,
+        }
+    }
+}
+
+/// How a `TokenHeir` holds its tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TokenCustody {
+    /// In the designation's escrow ATA. Designations migrated from before
+    /// custody modes existed read as this variant.
+    Escrow,
+    /// In the owner's token account, with the designation approved as delegate
+    /// for up to `amount`
+    Delegate,
+    /// In the owner's token account, with the owner's `TokenEstate` approved as
+    /// delegate; the heir receives `share_bps` of the balance at the first claim
+    Share,
+}
+
+/// What a guardian quorum did, in `GuardianActionTaken`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GuardianActionKind {
+    AttestDeceased,
+    FreezeClaims,
+    UnfreezeClaims,
+}
+
+/// Kind of designation an event refers to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DesignationKind {
+    Sol,
+    Token,
+    DelegatedToken,
+    TokenShare,
+    NftBundle,
+    TokenBasket,
+    Stake,
+    Program,
+}
+
+/// Shared delegate for every share designation an owner made for one mint.
+/// Freezes the owner's balance at the first share claim so that every heir's
+/// share is computed from the same figure, whatever order they claim in.
+#[account]
+pub struct TokenEstate {
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    /// Basis points promised across the owner's open share designations
+    pub total_share_bps: u16,
+    /// Owner's balance when the first share was claimed
+    pub snapshot_balance: u64,
+    /// When the snapshot was taken (0 if never); owner activity since voids it
+    pub snapshot_taken_at: i64,
+    pub bump: u8,
+}
+
+impl TokenEstate {
+    pub const SPACE: usize = 8 + 32 + 32 + 2 + 8 + 8 + 1;
+}
+
+/// A bundle of NFTs bequeathed together to one heir. Each NFT sits in the
+/// designation's ATA for its mint.
+#[account]
+pub struct NftHeir {
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    /// The bundled mints, in the order claims and cancellations pass their accounts
+    pub mints: Vec<Pubkey>,
+    pub inactivity_period_seconds: i64,
+    pub last_activity: i64,
+    pub bump: u8,
+    /// Index distinguishing several bundles from the same owner to the same heir
+    pub tranche: u16,
+    /// When the heir requested the claim (0 if no request is pending)
+    pub claim_requested_at: i64,
+}
+
+impl NftHeir {
+    pub const SPACE: usize = 8 + 32 + 32 + 4 + 32 * MAX_NFTS_PER_BUNDLE + 8 + 8 + 1 + 2 + 8;
+}
+
+/// Several mints bequeathed together to one heir. Each entry's tokens sit in
+/// the basket's ATA for that mint.
+#[account]
+pub struct TokenBasketHeir {
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    /// The bequeathed mints, in the order claims and cancellations pass their accounts
+    pub entries: Vec<BasketEntry>,
+    pub inactivity_period_seconds: i64,
+    pub last_activity: i64,
+    pub bump: u8,
+    /// Index distinguishing several baskets from the same owner to the same heir
+    pub tranche: u16,
+    /// When the heir requested the claim (0 if no request is pending)
+    pub claim_requested_at: i64,
+}
+
+impl TokenBasketHeir {
+    pub const SPACE: usize = 8 + 32 + 32 + 4 + BasketEntry::SIZE * MAX_BASKET_ENTRIES + 8 + 8 + 1 + 2 + 8;
+}
+
+/// One mint of a `TokenBasketHeir`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BasketEntry {
+    pub mint: Pubkey,
+    /// Base units escrowed for the heir, net of any transfer fee
+    pub amount: u64,
+}
+
+impl BasketEntry {
+    pub const SIZE: usize = 32 + 8;
+}
+
+/// A native stake account whose staker and withdrawer authorities are held
+/// by this designation until the heir claims it
+#[account]
+pub struct StakeHeir {
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub stake_account: Pubkey,
+    pub inactivity_period_seconds: i64,
+    pub last_activity: i64,
+    pub bump: u8,
+    /// When the heir requested the claim (0 if no request is pending)
+    pub claim_requested_at: i64,
+}
+
+impl StakeHeir {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 8;
+}
+
+/// A BPF-upgradeable program whose upgrade authority is held by this
+/// designation until the heir claims it
+#[account]
+pub struct ProgramHeir {
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub upgradeable_program: Pubkey,
+    pub inactivity_period_seconds: i64,
+    pub last_activity: i64,
+    pub bump: u8,
+    /// When the heir requested the claim (0 if no request is pending)
+    pub claim_requested_at: i64,
+}
+
+impl ProgramHeir {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 8;
+}
+
+/// What `add_token_heir` and `add_token_basket_heir` do with a mint whose issuer
+/// can freeze or drain the escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MintRiskPolicy {
+    /// Fail with an error naming the risk
+    Reject,
+    /// Accept the mint and emit `RiskyMintAccepted`
+    Warn,
+    /// Accept the mint silently
+    Allow,
+}
+
+/// Issuer powers over a mint that put an escrow at risk
+pub struct MintRisks {
+    /// The issuer can freeze the escrow account
+    pub freeze_authority: bool,
+    /// A Token-2022 permanent delegate can transfer or burn the escrowed tokens
+    pub permanent_delegate: bool,
+}
+
+impl MintRisks {
+    /// Read the issuer powers from a SPL Token or Token-2022 mint
+    pub fn inspect(mint: &InterfaceAccount<Mint>) -> Result<Self> {
+        let info = mint.to_account_info();
+        let permanent_delegate = if *info.owner == token_interface::spl_token_2022::ID {
+            let data = info.try_borrow_data()?;
+            let state = StateWithExtensions::<token_interface::spl_token_2022::state::Mint>::unpack(&data)?;
+            state
+                .get_extension::<PermanentDelegate>()
+                .is_ok_and(|ext| Option::<Pubkey>::from(ext.delegate).is_some())
+        } else {
+            false
+        };
+        
+        Ok(Self {
+            freeze_authority: mint.freeze_authority.is_some(),
+            permanent_delegate,
+        })
+    }
+    
+    pub fn any(&self) -> bool {
+        self.freeze_authority || self.permanent_delegate
+    }
+}
+
+/// A secondary key allowed to record heartbeats for an owner
+#[account]
+pub struct LivenessKey {
+    pub owner: Pubkey,
+    pub key: Pubkey,
+    /// Unix timestamp after which the key stops working (0 = never)
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl LivenessKey {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct GuardianSet {
+    pub owner: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    /// Number of guardian signatures required to attest or freeze
+    pub threshold: u8,
+    /// When a guardian quorum last attested the owner's death (0 if never)
+    pub deceased_attested_at: i64,
+    /// Set by a guardian quorum to block all claims on the estate
+    pub frozen: bool,
+    pub bump: u8,
+}
+
+impl GuardianSet {
+    pub const SPACE: usize = 8 + 32 + 4 + 32 * MAX_GUARDIANS + 1 + 8 + 1 + 1;
+    
+    /// Whether at least `threshold` distinct guardians signed among `signers`
+    pub fn has_quorum(&self, signers: &[AccountInfo]) -> bool {
+        self.signed_by(signers).len() >= self.threshold as usize
+    }
+    
+    /// Guardians among `signers` that signed the transaction
+    pub fn signed_by(&self, signers: &[AccountInfo]) -> Vec<Pubkey> {
+        self.guardians
+            .iter()
+            .filter(|guardian| signers.iter().any(|info| info.is_signer && info.key == *guardian))
+            .copied()
+            .collect()
+    }
+}
+
+// ===============================================
+// EVENTS
+// ===============================================
+
+/// A token or token basket designation was created under `MintRiskPolicy::Warn`
+/// for a mint whose issuer can freeze or drain the escrow
+#[event]
+pub struct RiskyMintAccepted {
+    /// The `TokenHeir` or `TokenBasketHeir` escrowing the mint
+    pub token_heir: Pubkey,
+    pub token_mint: Pubkey,
+    pub freeze_authority: bool,
+    pub permanent_delegate: bool,
+}
+
+/// A designation was created. NFT bundles and token baskets emit one event
+/// per mint.
+///
+/// In this and the following events, `mint` is `None` for SOL, stake and
+/// program designations. `amount` is in lamports or token base units. Shares
+/// report basis points, stake designations report the stake account's
+/// lamports, and program designations report 0.
+#[event]
+pub struct HeirAdded {
+    pub designation: Pubkey,
+    pub kind: DesignationKind,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub inactivity_period_seconds: i64,
+    pub timestamp: i64,
+}
+
+/// The owner showed activity other than adding or cancelling a designation.
+/// The designation fields are `None` for estate-wide heartbeats and settings.
+/// For per-designation updates they carry the designation's new amount.
+#[event]
+pub struct ActivityUpdated {
+    pub owner: Pubkey,
+    pub designation: Option<Pubkey>,
+    pub heir: Option<Pubkey>,
+    pub mint: Option<Pubkey>,
+    pub amount: Option<u64>,
+    pub last_activity: i64,
+}
+
+/// A claim request started the owner's notice period
+#[event]
+pub struct ClaimRequested {
+    pub designation: Pubkey,
+    pub kind: DesignationKind,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub requested_at: i64,
+    pub finalizable_at: i64,
+}
+
+/// A claim was paid out; `amount` is what the heir received
+#[event]
+pub struct Claimed {
+    pub designation: Pubkey,
+    pub kind: DesignationKind,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// The owner cancelled a designation; `amount` is what returned to the owner
+#[event]
+pub struct Cancelled {
+    pub designation: Pubkey,
+    pub kind: DesignationKind,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// A guardian quorum attested the owner's death or froze or unfroze claims;
+/// `guardians` are the members who signed
+#[event]
+pub struct GuardianActionTaken {
+    pub owner: Pubkey,
+    pub action: GuardianActionKind,
+    pub guardians: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// A designation claimed before claims closed their accounts was closed and
+/// its rent returned to the owner; `amount` is what the heir was paid
+#[event]
+pub struct ClaimedDesignationClosed {
+    pub designation: Pubkey,
+    pub kind: DesignationKind,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// A designation was grown to the current layout; `escrow_moved` is the legacy
+/// SOL escrow moved from the account into its vault
+#[event]
+pub struct HeirAccountMigrated {
+    pub designation: Pubkey,
+    pub kind: DesignationKind,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub escrow_moved: u64,
+    pub timestamp: i64,
 }
 
 // ===============================================
@@ -478,4 +4893,66 @@ pub enum ErrorCode {
     OwnerStillActive,
     #[msg("Inheritance already claimed.")]
     AlreadyClaimed,
+    #[msg("Insufficient escrow balance - cancel the designation to withdraw everything.")]
+    InsufficientEscrowBalance,
+    #[msg("Account is not a SOL or token heir designation.")]
+    InvalidHeirAccount,
+    #[msg("Inheritance has not been claimed yet.")]
+    NotClaimed,
+    #[msg("Escrow account still holds tokens.")]
+    EscrowNotEmpty,
+    #[msg("Claim notice period must be between 1 and 30 days.")]
+    InvalidNoticePeriod,
+    #[msg("A claim request is already pending.")]
+    ClaimAlreadyRequested,
+    #[msg("No claim has been requested.")]
+    ClaimNotRequested,
+    #[msg("Claim request was vetoed by owner activity.")]
+    ClaimVetoed,
+    #[msg("Claim notice period has not elapsed.")]
+    NoticePeriodActive,
+    #[msg("Guardian set must have 1 to 10 distinct guardians other than the owner.")]
+    InvalidGuardianSet,
+    #[msg("Guardian threshold must be between 1 and the number of guardians.")]
+    InvalidGuardianThreshold,
+    #[msg("Not enough guardian signatures.")]
+    GuardianQuorumNotMet,
+    #[msg("Claims are frozen by the owner's guardians.")]
+    ClaimsFrozen,
+    #[msg("Expiry must be in the future, or 0 for no expiry.")]
+    InvalidExpiry,
+    #[msg("Liveness key has expired.")]
+    LivenessKeyExpired,
+    #[msg("Mint has a freeze authority that could freeze the escrow.")]
+    MintHasFreezeAuthority,
+    #[msg("Mint has a permanent delegate that could drain the escrow.")]
+    MintHasPermanentDelegate,
+    #[msg("Escrow account is frozen by the mint's freeze authority.")]
+    EscrowFrozen,
+    #[msg("NFT bundle must list 1 to 10 distinct mints, each with its accounts.")]
+    InvalidNftBundle,
+    #[msg("Mint is not an NFT - it must have 0 decimals and a supply of 1.")]
+    NotAnNft,
+    #[msg("Token basket must list 1 to 8 distinct mints, each with an amount and its accounts.")]
+    InvalidTokenBasket,
+    #[msg("Account is not an initialized stake account.")]
+    InvalidStakeAccount,
+    #[msg("Stake account lockup is in force - its withdrawer cannot change without the custodian.")]
+    StakeLockupInForce,
+    #[msg("Owner token account already has a delegate - it can back only one delegated designation.")]
+    TokenAccountAlreadyDelegated,
+    #[msg("Owner revoked or reassigned the designation's token allowance - nothing can be claimed.")]
+    DelegateAllowanceRevoked,
+    #[msg("Instruction does not match the designation's custody mode (escrow, delegate or share).")]
+    WrongTokenCustody,
+    #[msg("Share must be 1 to 10000 basis points, and an owner's shares of one mint cannot exceed 10000.")]
+    InvalidShare,
+    #[msg("Account is not an upgradeable program with an upgrade authority.")]
+    InvalidUpgradeableProgram,
+    #[msg("Crank bounty cannot exceed 100 basis points.")]
+    InvalidCrankBounty,
+    #[msg("Mint has a transfer hook, which NFT bundles and token baskets do not support.")]
+    MintHasTransferHook,
+    #[msg("SOL bequest must be at least the rent-exempt minimum of an empty account.")]
+    BequestBelowRentExemption,
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("2p2Jz126WeWBndwBKWbDCxTa2aBW7DtgC4gyZTKPnofC");
+
+/// Minimal Token-2022 transfer hook for tests. Every transfer of a hooked mint
+/// must carry the mint's `counter` PDA, which the hook increments.
+#[program]
+pub mod transfer_hook_mock {
+    use super::*;
+
+    /// Create the mint's extra-account-metas list (naming `counter`) and the counter itself
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+        msg!("Transfer hook initialized for mint: {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    /// Called by Token-2022 on every `transfer_checked` of a hooked mint
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.transfers += 1;
+
+        msg!("Transfer hook executed: {} tokens, transfer #{}", amount, counter.transfers);
+        Ok(())
+    }
+}
+
+fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal { bytes: b"counter".to_vec() },
+            // Index 1 of the execute instruction is the mint
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        true,
+    )?])
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    /// CHECK: Initialized by the handler as a TLV extra-account-metas list
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(1)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Counter::SPACE,
+        seeds = [b"counter", mint.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// CHECK: Only used to derive the extra-account-metas address
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    /// CHECK: Source token account, validated by Token-2022
+    pub source_token: UncheckedAccount<'info>,
+    /// CHECK: Hooked mint, validated by Token-2022
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: Destination token account, validated by Token-2022
+    pub destination_token: UncheckedAccount<'info>,
+    /// CHECK: Source owner or delegate, validated by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: The mint's extra-account-metas list
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"counter", mint.key().as_ref()], bump)]
+    pub counter: Account<'info, Counter>,
+}
+
+#[account]
+pub struct Counter {
+    pub transfers: u64,
+}
+
+impl Counter {
+    pub const SPACE: usize = 8 + 8;
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+declare_id!("2Vjmjs3AaDccFk3a3V3aLmMa8BpBaBo8w8tG7D1hsuc4");
+
+/// Placeholder program for tests. It is deployed upgradeable with the test
+/// wallet as upgrade authority, which the suite then bequeaths.
+#[program]
+pub mod upgrade_dummy {
+    use super::*;
+
+    pub fn ping(_ctx: Context<Ping>) -> Result<()> {
+        msg!("Pong");
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Ping {}
@@ -0,0 +1,231 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  airdrop,
+  findUserProfilePda,
+  findSolHeirPda,
+  findSolVaultPda,
+  findTokenHeirPda,
+  initializeUser,
+  addSolHeir,
+} from "./helpers";
+
+describe("Adjust escrowed amounts", () => {
+  const inactivityPeriod = new anchor.BN(30 * 24 * 60 * 60); // 30 days
+
+  let owner: Keypair;
+  let heir: Keypair;
+  let stranger: Keypair;
+
+  let userProfilePda: PublicKey;
+  let solHeirPda: PublicKey;
+  let solVaultPda: PublicKey;
+  let tokenHeirPda: PublicKey;
+
+  let tokenMint: PublicKey;
+  let ownerTokenAccount: PublicKey;
+  let escrowTokenAccount: PublicKey;
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+    stranger = Keypair.generate();
+
+    await airdrop([owner, heir, stranger], 10);
+
+    tokenMint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
+    ownerTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      tokenMint,
+      owner.publicKey
+    );
+    await mintTo(provider.connection, owner, tokenMint, ownerTokenAccount, owner.publicKey, 1_000_000_000);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+    solVaultPda = findSolVaultPda(solHeirPda);
+    tokenHeirPda = findTokenHeirPda(owner.publicKey, heir.publicKey, tokenMint);
+    escrowTokenAccount = getAssociatedTokenAddressSync(tokenMint, tokenHeirPda, true);
+
+    await initializeUser(owner);
+
+    await addSolHeir(owner, heir.publicKey, new anchor.BN(LAMPORTS_PER_SOL), inactivityPeriod);
+
+    await program.methods
+      .addTokenHeir(0, new anchor.BN(100_000_000), inactivityPeriod, { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenMint,
+        ownerTokenAccount,
+        escrowTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+  });
+
+  const solAccounts = (signer: PublicKey) => ({
+    solHeir: solHeirPda,
+    solVault: solVaultPda,
+    userProfile: userProfilePda,
+    owner: signer,
+    systemProgram: SystemProgram.programId,
+  });
+
+  const tokenAccounts = (signer: PublicKey) => ({
+    tokenHeir: tokenHeirPda,
+    userProfile: userProfilePda,
+    owner: signer,
+    tokenMint,
+    ownerTokenAccount,
+    escrowTokenAccount,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  it("Tops up a SOL escrow", async () => {
+    const escrowBefore = await provider.connection.getBalance(solVaultPda);
+
+    await program.methods
+      .increaseSolAmount(new anchor.BN(LAMPORTS_PER_SOL / 2))
+      .accountsPartial(solAccounts(owner.publicKey))
+      .signers([owner])
+      .rpc();
+
+    const solHeir = await program.account.solHeir.fetch(solHeirPda);
+    const escrowAfter = await provider.connection.getBalance(solVaultPda);
+    assert.equal(solHeir.amount.toNumber(), 1.5 * LAMPORTS_PER_SOL);
+    assert.equal(escrowAfter - escrowBefore, LAMPORTS_PER_SOL / 2);
+  });
+
+  it("Partially withdraws a SOL escrow", async () => {
+    const escrowBefore = await provider.connection.getBalance(solVaultPda);
+
+    await program.methods
+      .decreaseSolAmount(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial(solAccounts(owner.publicKey))
+      .signers([owner])
+      .rpc();
+
+    const solHeir = await program.account.solHeir.fetch(solHeirPda);
+    const escrowAfter = await provider.connection.getBalance(solVaultPda);
+    assert.equal(solHeir.amount.toNumber(), LAMPORTS_PER_SOL / 2);
+    assert.equal(escrowBefore - escrowAfter, LAMPORTS_PER_SOL);
+  });
+
+  it("Rejects withdrawing the whole SOL escrow", async () => {
+    try {
+      await program.methods
+        .decreaseSolAmount(new anchor.BN(LAMPORTS_PER_SOL / 2))
+        .accountsPartial(solAccounts(owner.publicKey))
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - withdrawal empties the escrow");
+    } catch (error) {
+      assert.ok(error.toString().includes("InsufficientEscrowBalance"));
+    }
+  });
+
+  it("Rejects a SOL top-up beyond the maximum", async () => {
+    try {
+      await program.methods
+        .increaseSolAmount(new anchor.BN("1000000000000000")) // 1M SOL on top of the escrow
+        .accountsPartial(solAccounts(owner.publicKey))
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - amount too large");
+    } catch (error) {
+      assert.ok(error.toString().includes("AmountTooLarge"));
+    }
+  });
+
+  it("Rejects SOL adjustments by someone other than the owner", async () => {
+    try {
+      await program.methods
+        .decreaseSolAmount(new anchor.BN(1))
+        .accountsPartial(solAccounts(stranger.publicKey))
+        .signers([stranger])
+        .rpc();
+
+      assert.fail("Should have failed - signer is not the owner");
+    } catch (error) {
+      assert.match(error.toString(), /ConstraintSeeds|ConstraintHasOne/);
+    }
+  });
+
+  it("Tops up and partially withdraws a token escrow", async () => {
+    await program.methods
+      .increaseTokenAmount(new anchor.BN(50_000_000))
+      .accountsPartial(tokenAccounts(owner.publicKey))
+      .signers([owner])
+      .rpc();
+
+    let tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda);
+    let escrow = await getAccount(provider.connection, escrowTokenAccount);
+    assert.equal(tokenHeir.amount.toNumber(), 150_000_000);
+    assert.equal(Number(escrow.amount), 150_000_000);
+
+    await program.methods
+      .decreaseTokenAmount(new anchor.BN(120_000_000))
+      .accountsPartial(tokenAccounts(owner.publicKey))
+      .signers([owner])
+      .rpc();
+
+    tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda);
+    escrow = await getAccount(provider.connection, escrowTokenAccount);
+    assert.equal(tokenHeir.amount.toNumber(), 30_000_000);
+    assert.equal(Number(escrow.amount), 30_000_000);
+  });
+
+  it("Rejects withdrawing more tokens than are escrowed", async () => {
+    try {
+      await program.methods
+        .decreaseTokenAmount(new anchor.BN(30_000_000))
+        .accountsPartial(tokenAccounts(owner.publicKey))
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - withdrawal empties the escrow");
+    } catch (error) {
+      assert.ok(error.toString().includes("InsufficientEscrowBalance"));
+    }
+  });
+
+  it("Rejects a token top-up beyond the maximum", async () => {
+    try {
+      await program.methods
+        .increaseTokenAmount(new anchor.BN("9223372036854775807")) // u64::MAX / 2
+        .accountsPartial(tokenAccounts(owner.publicKey))
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - amount too large");
+    } catch (error) {
+      assert.ok(error.toString().includes("AmountTooLarge"));
+    }
+  });
+});
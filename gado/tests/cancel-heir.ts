@@ -0,0 +1,268 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findSolHeirPda,
+  findSolVaultPda,
+  findTokenHeirPda,
+  initializeUser,
+  addSolHeir,
+  requestSolClaim,
+  finalizeSolClaim,
+} from "./helpers";
+
+describe("Cancel heir designations", () => {
+  const inactivityPeriod = new anchor.BN(7 * 24 * 60 * 60); // 7 days
+
+  let owner: Keypair;
+  let heir: Keypair;
+  let stranger: Keypair;
+
+  let userProfilePda: PublicKey;
+  let solHeirPda: PublicKey;
+  let solVaultPda: PublicKey;
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+    stranger = Keypair.generate();
+
+    await airdrop([owner, heir, stranger]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+    solVaultPda = findSolVaultPda(solHeirPda);
+
+    await initializeUser(owner);
+  });
+
+  const designate = (amount: anchor.BN, period: anchor.BN = inactivityPeriod) =>
+    addSolHeir(owner, heir.publicKey, amount, period);
+
+  it("Rejects cancellation by someone other than the owner", async () => {
+    await designate(new anchor.BN(LAMPORTS_PER_SOL));
+
+    try {
+      await program.methods
+        .cancelSolHeir()
+        .accountsPartial({
+          solHeir: solHeirPda,
+          solVault: solVaultPda,
+          userProfile: userProfilePda,
+          owner: stranger.publicKey,
+        })
+        .signers([stranger])
+        .rpc();
+
+      assert.fail("Should have failed - signer is not the owner");
+    } catch (error) {
+      // The stranger cannot produce the owner's PDA seeds, nor satisfy `has_one = owner`.
+      assert.match(error.toString(), /ConstraintSeeds|ConstraintHasOne|AccountNotInitialized/);
+    }
+
+    const solHeir = await program.account.solHeir.fetch(solHeirPda);
+    assert.equal(solHeir.isClaimed, false);
+  });
+
+  it("Cancels a SOL heir and refunds escrow plus rent to the owner", async () => {
+    const escrowBalance =
+      (await provider.connection.getBalance(solVaultPda)) +
+      (await provider.connection.getBalance(solHeirPda));
+    const ownerBalanceBefore = await provider.connection.getBalance(owner.publicKey);
+    const profileBefore = await program.account.userProfile.fetch(userProfilePda);
+
+    // Fee payer is the provider wallet, so the owner's balance change is exactly the refund.
+    await program.methods
+      .cancelSolHeir()
+      .accountsPartial({
+        solHeir: solHeirPda,
+        solVault: solVaultPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+      })
+      .signers([owner])
+      .rpc();
+
+    const ownerBalanceAfter = await provider.connection.getBalance(owner.publicKey);
+    const profileAfter = await program.account.userProfile.fetch(userProfilePda);
+
+    assert.equal(ownerBalanceAfter - ownerBalanceBefore, escrowBalance);
+    assert.isNull(await provider.connection.getAccountInfo(solHeirPda));
+    assert.isNull(await provider.connection.getAccountInfo(solVaultPda));
+    assert.equal(profileAfter.totalInheritances, profileBefore.totalInheritances - 1);
+  });
+
+  it("Rejects cancelling the same designation twice", async () => {
+    try {
+      await program.methods
+        .cancelSolHeir()
+        .accountsPartial({
+          solHeir: solHeirPda,
+          solVault: solVaultPda,
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - designation already closed");
+    } catch (error) {
+      assert.ok(error.toString().includes("AccountNotInitialized"));
+    }
+  });
+
+  it("Rejects cancelling a designation the heir already claimed", async () => {
+    // Successful claims close the designation, so nothing is left to cancel
+    await designate(new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN(1));
+    await sleep(3000);
+
+    await requestSolClaim(owner.publicKey, heir);
+    await sleep(1500);
+
+    await finalizeSolClaim(owner.publicKey, heir);
+
+    try {
+      await program.methods
+        .cancelSolHeir()
+        .accountsPartial({
+          solHeir: solHeirPda,
+          solVault: solVaultPda,
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - inheritance already claimed");
+    } catch (error) {
+      assert.ok(error.toString().includes("AccountNotInitialized"));
+    }
+  });
+
+  describe("Token heirs", () => {
+    const tokenAmount = new anchor.BN(250_000_000_000); // 250 tokens
+
+    let tokenMint: PublicKey;
+    let ownerTokenAccount: PublicKey;
+    let tokenHeirPda: PublicKey;
+    let escrowTokenAccount: PublicKey;
+
+    before(async () => {
+      tokenMint = await createMint(provider.connection, owner, owner.publicKey, null, 9);
+      ownerTokenAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        owner,
+        tokenMint,
+        owner.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        owner,
+        tokenMint,
+        ownerTokenAccount,
+        owner.publicKey,
+        1_000_000_000_000 // 1000 tokens
+      );
+
+      tokenHeirPda = findTokenHeirPda(owner.publicKey, heir.publicKey, tokenMint);
+      escrowTokenAccount = getAssociatedTokenAddressSync(tokenMint, tokenHeirPda, true);
+
+      await program.methods
+        .addTokenHeir(0, tokenAmount, inactivityPeriod, { reject: {} })
+        .accountsPartial({
+          tokenHeir: tokenHeirPda,
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+          heir: heir.publicKey,
+          tokenMint,
+          ownerTokenAccount,
+          escrowTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    });
+
+    it("Rejects token cancellation by someone other than the owner", async () => {
+      try {
+        await program.methods
+          .cancelTokenHeir()
+          .accountsPartial({
+            tokenHeir: tokenHeirPda,
+            userProfile: userProfilePda,
+            owner: stranger.publicKey,
+            tokenMint,
+            ownerTokenAccount,
+            escrowTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([stranger])
+          .rpc();
+
+        assert.fail("Should have failed - signer is not the owner");
+      } catch (error) {
+        assert.match(error.toString(), /ConstraintSeeds|ConstraintHasOne|AccountNotInitialized/);
+      }
+
+      const escrow = await getAccount(provider.connection, escrowTokenAccount);
+      assert.equal(escrow.amount.toString(), tokenAmount.toString());
+    });
+
+    it("Cancels a token heir, returns tokens and closes both accounts", async () => {
+      const ownerTokensBefore = await getAccount(provider.connection, ownerTokenAccount);
+      const ownerLamportsBefore = await provider.connection.getBalance(owner.publicKey);
+      const rentLocked =
+        (await provider.connection.getBalance(tokenHeirPda)) +
+        (await provider.connection.getBalance(escrowTokenAccount));
+      const profileBefore = await program.account.userProfile.fetch(userProfilePda);
+
+      await program.methods
+        .cancelTokenHeir()
+        .accountsPartial({
+          tokenHeir: tokenHeirPda,
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+          tokenMint,
+          ownerTokenAccount,
+          escrowTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+
+      const ownerTokensAfter = await getAccount(provider.connection, ownerTokenAccount);
+      const ownerLamportsAfter = await provider.connection.getBalance(owner.publicKey);
+      const profileAfter = await program.account.userProfile.fetch(userProfilePda);
+
+      assert.equal(
+        (ownerTokensAfter.amount - ownerTokensBefore.amount).toString(),
+        tokenAmount.toString()
+      );
+      assert.equal(ownerLamportsAfter - ownerLamportsBefore, rentLocked);
+      assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda));
+      assert.isNull(await provider.connection.getAccountInfo(escrowTokenAccount));
+      assert.equal(profileAfter.totalInheritances, profileBefore.totalInheritances - 1);
+    });
+  });
+});
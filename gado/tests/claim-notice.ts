@@ -0,0 +1,130 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findSolHeirPda,
+  initializeUser,
+  addSolHeir,
+  requestSolClaim,
+  finalizeSolClaim,
+} from "./helpers";
+
+describe("Claim notice period", () => {
+  const inactivityPeriod = new anchor.BN(1); // seconds
+  const noticePeriod = new anchor.BN(2);
+
+  let owner: Keypair;
+  let heir: Keypair;
+
+  let userProfilePda: PublicKey;
+  let solHeirPda: PublicKey;
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+
+    await initializeUser(owner);
+
+    await addSolHeir(owner, heir.publicKey, new anchor.BN(0.1 * LAMPORTS_PER_SOL), inactivityPeriod);
+  });
+
+  it("Lets the owner configure the notice period", async () => {
+    await program.methods
+      .setClaimNoticePeriod(noticePeriod)
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    assert.ok(profile.claimNoticePeriodSeconds.eq(noticePeriod));
+  });
+
+  it("Rejects a notice period outside the allowed range", async () => {
+    try {
+      await program.methods
+        .setClaimNoticePeriod(new anchor.BN(31 * 24 * 60 * 60))
+        .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+
+      assert.fail("Should have failed - notice period too long");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidNoticePeriod"));
+    }
+  });
+
+  it("Rejects finalizing without a request", async () => {
+    await sleep(2000);
+
+    try {
+      await finalizeSolClaim(owner.publicKey, heir);
+      assert.fail("Should have failed - no claim requested");
+    } catch (error) {
+      assert.ok(error.toString().includes("ClaimNotRequested"));
+    }
+  });
+
+  it("Rejects finalizing before the notice period elapses", async () => {
+    await requestSolClaim(owner.publicKey, heir);
+
+    const solHeir = await program.account.solHeir.fetch(solHeirPda);
+    assert.ok(solHeir.claimRequestedAt.gtn(0));
+
+    try {
+      await finalizeSolClaim(owner.publicKey, heir);
+      assert.fail("Should have failed - notice period still running");
+    } catch (error) {
+      assert.ok(error.toString().includes("NoticePeriodActive"));
+    }
+
+    try {
+      await requestSolClaim(owner.publicKey, heir);
+      assert.fail("Should have failed - request already pending");
+    } catch (error) {
+      assert.ok(error.toString().includes("ClaimAlreadyRequested"));
+    }
+  });
+
+  it("Vetoes a pending request when the owner checks in", async () => {
+    await sleep(1000);
+    await program.methods
+      .heartbeat()
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await sleep(2500);
+
+    try {
+      await finalizeSolClaim(owner.publicKey, heir);
+      assert.fail("Should have failed - claim was vetoed");
+    } catch (error) {
+      assert.ok(error.toString().includes("ClaimVetoed"));
+    }
+  });
+
+  it("Finalizes a fresh request once the notice period elapses", async () => {
+    await requestSolClaim(owner.publicKey, heir);
+    await sleep(3000);
+
+    const heirBefore = await provider.connection.getBalance(heir.publicKey);
+    await finalizeSolClaim(owner.publicKey, heir);
+    const heirAfter = await provider.connection.getBalance(heir.publicKey);
+
+    assert.equal(heirAfter - heirBefore, 0.1 * LAMPORTS_PER_SOL);
+    assert.isNull(await provider.connection.getAccountInfo(solHeirPda));
+  });
+});
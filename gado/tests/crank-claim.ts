@@ -0,0 +1,175 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findSolHeirPda,
+  findSolVaultPda,
+  findTokenHeirPda,
+  initializeUser,
+  addSolHeir,
+} from "./helpers";
+
+describe("Keeper-cranked claims", () => {
+  let owner: Keypair;
+  // Never signs: the heir does not know about the bequest
+  let heir: Keypair;
+  let cranker: Keypair;
+  let mint: PublicKey;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+  let solHeirPda: PublicKey;
+  let solVaultPda: PublicKey;
+  let tokenHeirPda: PublicKey;
+
+  const crankSolClaim = () =>
+    program.methods
+      .crankSolClaim()
+      .accountsPartial({
+        solHeir: solHeirPda,
+        solVault: solVaultPda,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        cranker: cranker.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([cranker])
+      .rpc();
+
+  const crankTokenClaim = () =>
+    program.methods
+      .crankTokenClaim()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        escrowTokenAccount: getAssociatedTokenAddressSync(mint, tokenHeirPda, true),
+        heirTokenAccount: getAssociatedTokenAddressSync(mint, heir.publicKey),
+        crankerTokenAccount: getAssociatedTokenAddressSync(mint, cranker.publicKey),
+        tokenMint: mint,
+        heir: heir.publicKey,
+        cranker: cranker.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([cranker])
+      .rpc();
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+    cranker = Keypair.generate();
+
+    await airdrop([owner, cranker]);
+
+    mint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
+    const ownerTokenAccount = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+    await mintTo(provider.connection, owner, mint, ownerTokenAccount, owner, 1_000_000);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+    solVaultPda = findSolVaultPda(solHeirPda);
+    tokenHeirPda = findTokenHeirPda(owner.publicKey, heir.publicKey, mint);
+
+    await initializeUser(owner);
+  });
+
+  it("Caps the bounty an owner can offer", async () => {
+    try {
+      await program.methods
+        .setCrankBounty(500)
+        .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      assert.fail("Should have failed - 5% bounty");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidCrankBounty"));
+    }
+  });
+
+  it("Sets a 1% bounty and designates SOL and tokens", async () => {
+    await program.methods
+      .setCrankBounty(100)
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    await addSolHeir(owner, heir.publicKey, new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN(1));
+
+    await program.methods
+      .addTokenHeir(0, new anchor.BN(1_000_000), new anchor.BN(1), { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenMint: mint,
+        ownerTokenAccount: getAssociatedTokenAddressSync(mint, owner.publicKey),
+        escrowTokenAccount: getAssociatedTokenAddressSync(mint, tokenHeirPda, true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    assert.equal(profile.crankBountyBps, 100);
+  });
+
+  it("Requests on the first crank and delivers SOL on the next", async () => {
+    await sleep(2000);
+    await crankSolClaim();
+
+    const solHeir = await program.account.solHeir.fetch(solHeirPda);
+    assert.ok(solHeir.claimRequestedAt.gtn(0));
+    assert.equal(await provider.connection.getBalance(heir.publicKey), 0);
+
+    await sleep(1500);
+    // The provider wallet pays the fee, so the cranker's gain is exactly the bounty
+    const crankerBefore = await provider.connection.getBalance(cranker.publicKey);
+    await crankSolClaim();
+
+    assert.equal(await provider.connection.getBalance(heir.publicKey), 0.99 * LAMPORTS_PER_SOL);
+    assert.equal(await provider.connection.getBalance(cranker.publicKey) - crankerBefore, 0.01 * LAMPORTS_PER_SOL);
+    assert.isNull(await provider.connection.getAccountInfo(solHeirPda));
+    assert.isNull(await provider.connection.getAccountInfo(solVaultPda));
+  });
+
+  it("Delivers tokens to the heir's ATA and pays the bounty in tokens", async () => {
+    await crankTokenClaim();
+    await sleep(1500);
+    await crankTokenClaim();
+
+    const heirAccount = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, heir.publicKey));
+    const crankerAccount = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, cranker.publicKey));
+    assert.equal(Number(heirAccount.amount), 990_000);
+    assert.equal(Number(crankerAccount.amount), 10_000);
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda));
+  });
+});
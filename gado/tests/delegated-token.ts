@@ -0,0 +1,191 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+  revoke,
+  transfer,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findTokenHeirPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Delegated token inheritance", () => {
+  let owner: Keypair;
+  let heirs: Keypair[];
+  let mints: PublicKey[];
+  let sink: PublicKey;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  const tokenHeirPda = (heir: PublicKey, mint: PublicKey) => findTokenHeirPda(owner.publicKey, heir, mint);
+  const ownerAta = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, owner.publicKey);
+
+  const addDelegatedTokenHeir = (heir: PublicKey, mint: PublicKey, amount: number) =>
+    program.methods
+      .addDelegatedTokenHeir(0, new anchor.BN(amount), new anchor.BN(1), { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heir, mint),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir,
+        tokenMint: mint,
+        ownerTokenAccount: ownerAta(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  const claim = async (heir: Keypair, mint: PublicKey) => {
+    const tokenHeir = tokenHeirPda(heir.publicKey, mint);
+    await sleep(2000);
+    await program.methods
+      .requestTokenClaim()
+      .accountsPartial({ tokenHeir, userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    await program.methods
+      .finalizeDelegatedTokenClaim()
+      .accountsPartial({
+        tokenHeir,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        ownerTokenAccount: ownerAta(mint),
+        heirTokenAccount: getAssociatedTokenAddressSync(mint, heir.publicKey),
+        tokenMint: mint,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([heir])
+      .rpc();
+  };
+
+  before(async () => {
+    owner = Keypair.generate();
+    heirs = [Keypair.generate(), Keypair.generate()];
+
+    await airdrop([owner, ...heirs]);
+
+    mints = [];
+    for (let i = 0; i < 2; i++) {
+      const mint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
+      const account = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+      await mintTo(provider.connection, owner, mint, account, owner, 1_000_000);
+      mints.push(mint);
+    }
+    sink = await createAssociatedTokenAccount(provider.connection, owner, mints[0], Keypair.generate().publicKey);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Approves the designation without moving the owner's tokens", async () => {
+    await addDelegatedTokenHeir(heirs[0].publicKey, mints[0], 600_000);
+
+    const account = await getAccount(provider.connection, ownerAta(mints[0]));
+    const tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda(heirs[0].publicKey, mints[0]));
+    assert.equal(Number(account.amount), 1_000_000);
+    assert.ok(account.delegate.equals(tokenHeirPda(heirs[0].publicKey, mints[0])));
+    assert.equal(Number(account.delegatedAmount), 600_000);
+    assert.deepEqual(tokenHeir.custody, { delegate: {} });
+  });
+
+  it("Refuses to replace another designation's allowance", async () => {
+    try {
+      await addDelegatedTokenHeir(heirs[1].publicKey, mints[0], 100_000);
+      assert.fail("Should have failed - token account already delegated");
+    } catch (error) {
+      assert.ok(error.toString().includes("TokenAccountAlreadyDelegated"));
+    }
+  });
+
+  it("Keeps escrow-only instructions away from delegated designations", async () => {
+    try {
+      await program.methods
+        .cancelTokenHeir()
+        .accountsPartial({
+          tokenHeir: tokenHeirPda(heirs[0].publicKey, mints[0]),
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+          tokenMint: mints[0],
+          ownerTokenAccount: ownerAta(mints[0]),
+          escrowTokenAccount: getAssociatedTokenAddressSync(mints[0], tokenHeirPda(heirs[0].publicKey, mints[0]), true),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+      assert.fail("Should have failed - designation is delegated");
+    } catch (error) {
+      assert.ok(error.toString().includes("WrongTokenCustody"));
+    }
+  });
+
+  it("Claims what remains when the owner spent part of the balance", async () => {
+    // The owner still uses the tokens while alive
+    await transfer(provider.connection, owner, ownerAta(mints[0]), sink, owner, 700_000);
+
+    await claim(heirs[0], mints[0]);
+
+    const received = await getAccount(provider.connection, getAssociatedTokenAddressSync(mints[0], heirs[0].publicKey));
+    assert.equal(Number(received.amount), 300_000);
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda(heirs[0].publicKey, mints[0])));
+  });
+
+  it("Fails clearly when the owner revoked the allowance", async () => {
+    await addDelegatedTokenHeir(heirs[1].publicKey, mints[1], 500_000);
+    await revoke(provider.connection, owner, ownerAta(mints[1]), owner);
+
+    try {
+      await claim(heirs[1], mints[1]);
+      assert.fail("Should have failed - allowance revoked");
+    } catch (error) {
+      assert.ok(error.toString().includes("DelegateAllowanceRevoked"));
+    }
+
+    const account = await getAccount(provider.connection, ownerAta(mints[1]));
+    assert.equal(Number(account.amount), 1_000_000);
+  });
+
+  it("Cancels a delegated designation", async () => {
+    await program.methods
+      .cancelDelegatedTokenHeir()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heirs[1].publicKey, mints[1]),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenMint: mints[1],
+        ownerTokenAccount: ownerAta(mints[1]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda(heirs[1].publicKey, mints[1])));
+  });
+});
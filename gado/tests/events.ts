@@ -0,0 +1,230 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  eventsOf,
+  airdrop,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findSolHeirPda,
+  findSolVaultPda,
+  findTokenHeirPda,
+} from "./helpers";
+
+describe("Events", () => {
+  let owner: Keypair;
+  let heir: Keypair;
+  let mint: PublicKey;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+  let solHeirPda: PublicKey;
+  let solVaultPda: PublicKey;
+  let tokenHeirPda: PublicKey;
+
+  const only = async (signature: string, name: string) => {
+    const events = (await eventsOf(signature)).filter(e => e.name.toLowerCase() === name.toLowerCase());
+    assert.lengthOf(events, 1, `expected one ${name} event`);
+    return events[0].data;
+  };
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    mint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
+    const ownerTokenAccount = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+    await mintTo(provider.connection, owner, mint, ownerTokenAccount, owner, 1_000_000);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+    solVaultPda = findSolVaultPda(solHeirPda);
+    tokenHeirPda = findTokenHeirPda(owner.publicKey, heir.publicKey, mint);
+  });
+
+  it("Emits ActivityUpdated when the profile is created", async () => {
+    const signature = await program.methods
+      .initializeUser()
+      .accountsPartial({
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+    const event = await only(signature, "ActivityUpdated");
+    assert.ok(event.owner.equals(owner.publicKey));
+    assert.isNull(event.designation);
+    assert.ok(event.lastActivity.gtn(0));
+  });
+
+  it("Emits HeirAdded with owner, heir, mint and amount", async () => {
+    let signature = await program.methods
+      .addSolHeir(0, new anchor.BN(LAMPORTS_PER_SOL / 2), new anchor.BN(1))
+      .accountsPartial({
+        solHeir: solHeirPda,
+        solVault: solVaultPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+    let event = await only(signature, "HeirAdded");
+    assert.ok(event.designation.equals(solHeirPda));
+    assert.deepEqual(event.kind, { sol: {} });
+    assert.ok(event.owner.equals(owner.publicKey));
+    assert.ok(event.heir.equals(heir.publicKey));
+    assert.isNull(event.mint);
+    assert.equal(event.amount.toNumber(), LAMPORTS_PER_SOL / 2);
+    assert.equal(event.inactivityPeriodSeconds.toNumber(), 1);
+
+    signature = await program.methods
+      .addTokenHeir(0, new anchor.BN(400_000), new anchor.BN(30 * 24 * 60 * 60), { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenMint: mint,
+        ownerTokenAccount: getAssociatedTokenAddressSync(mint, owner.publicKey),
+        escrowTokenAccount: getAssociatedTokenAddressSync(mint, tokenHeirPda, true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+    event = await only(signature, "HeirAdded");
+    assert.deepEqual(event.kind, { token: {} });
+    assert.ok(event.mint.equals(mint));
+    assert.equal(event.amount.toNumber(), 400_000);
+  });
+
+  it("Emits ActivityUpdated on a heartbeat", async () => {
+    const signature = await program.methods
+      .heartbeat()
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+    const event = await only(signature, "ActivityUpdated");
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    assert.ok(event.lastActivity.eq(profile.lastActivity));
+  });
+
+  it("Emits ClaimRequested and Claimed through a claim", async () => {
+    await sleep(2000);
+    let signature = await program.methods
+      .requestSolClaim()
+      .accountsPartial({ solHeir: solHeirPda, userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc({ commitment: "confirmed" });
+
+    const requested = await only(signature, "ClaimRequested");
+    assert.ok(requested.designation.equals(solHeirPda));
+    assert.ok(requested.heir.equals(heir.publicKey));
+    assert.ok(requested.finalizableAt.gt(requested.requestedAt));
+
+    await sleep(1500);
+    signature = await program.methods
+      .finalizeSolClaim()
+      .accountsPartial({
+        solHeir: solHeirPda,
+        solVault: solVaultPda,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([heir])
+      .rpc({ commitment: "confirmed" });
+
+    const claimed = await only(signature, "Claimed");
+    assert.deepEqual(claimed.kind, { sol: {} });
+    assert.equal(claimed.amount.toNumber(), LAMPORTS_PER_SOL / 2);
+    assert.ok(claimed.timestamp.gte(requested.finalizableAt));
+  });
+
+  it("Emits Cancelled with the amount returned", async () => {
+    const signature = await program.methods
+      .cancelTokenHeir()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenMint: mint,
+        ownerTokenAccount: getAssociatedTokenAddressSync(mint, owner.publicKey),
+        escrowTokenAccount: getAssociatedTokenAddressSync(mint, tokenHeirPda, true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+    const event = await only(signature, "Cancelled");
+    assert.ok(event.designation.equals(tokenHeirPda));
+    assert.deepEqual(event.kind, { token: {} });
+    assert.ok(event.mint.equals(mint));
+    assert.equal(event.amount.toNumber(), 400_000);
+  });
+
+  it("Emits GuardianActionTaken with the guardians that signed", async () => {
+    const guardians = [Keypair.generate(), Keypair.generate()];
+    await program.methods
+      .setGuardians(guardians.map(g => g.publicKey), 1)
+      .accountsPartial({
+        guardianSet: guardianSetPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+    const act = (method: "attestDeceased" | "freezeClaims" | "unfreezeClaims", signer: Keypair) =>
+      program.methods[method]()
+        .accountsPartial({ guardianSet: guardianSetPda })
+        .remainingAccounts([{ pubkey: signer.publicKey, isSigner: true, isWritable: false }])
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    let event = await only(await act("freezeClaims", guardians[0]), "GuardianActionTaken");
+    assert.ok(event.owner.equals(owner.publicKey));
+    assert.deepEqual(event.action, { freezeClaims: {} });
+    assert.lengthOf(event.guardians, 1);
+    assert.ok(event.guardians[0].equals(guardians[0].publicKey));
+
+    event = await only(await act("unfreezeClaims", guardians[1]), "GuardianActionTaken");
+    assert.deepEqual(event.action, { unfreezeClaims: {} });
+    assert.ok(event.guardians[0].equals(guardians[1].publicKey));
+
+    event = await only(await act("attestDeceased", guardians[0]), "GuardianActionTaken");
+    assert.deepEqual(event.action, { attestDeceased: {} });
+    const guardianSet = await program.account.guardianSet.fetch(guardianSetPda);
+    assert.ok(event.timestamp.eq(guardianSet.deceasedAttestedAt));
+  });
+});
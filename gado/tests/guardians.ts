@@ -0,0 +1,182 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findSolHeirPda,
+  initializeUser,
+  addSolHeir,
+  requestSolClaim,
+  finalizeSolClaim,
+} from "./helpers";
+
+describe("Guardian attestation", () => {
+  const bequest = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+  const inactivityPeriod = new anchor.BN(7 * 24 * 60 * 60); // 7 days
+
+  let owner: Keypair;
+  let heirs: Keypair[];
+  let guardians: Keypair[];
+  let stranger: Keypair;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+  let solHeirPdas: PublicKey[];
+
+  before(async () => {
+    owner = Keypair.generate();
+    heirs = [Keypair.generate(), Keypair.generate()];
+    guardians = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    stranger = Keypair.generate();
+
+    await airdrop([owner, stranger, ...heirs]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+    solHeirPdas = heirs.map(heir => findSolHeirPda(owner.publicKey, heir.publicKey));
+
+    await initializeUser(owner);
+
+    for (let i = 0; i < heirs.length; i++) {
+      await addSolHeir(owner, heirs[i].publicKey, bequest, inactivityPeriod);
+    }
+  });
+
+  const setGuardians = (keys: PublicKey[], threshold: number) =>
+    program.methods
+      .setGuardians(keys, threshold)
+      .accountsPartial({
+        guardianSet: guardianSetPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  const guardianAction = (
+    method: "attestDeceased" | "freezeClaims" | "unfreezeClaims",
+    signers: Keypair[]
+  ) =>
+    program.methods[method]()
+      .accountsPartial({ guardianSet: guardianSetPda })
+      .remainingAccounts(signers.map(s => ({ pubkey: s.publicKey, isSigner: true, isWritable: false })))
+      .signers(signers)
+      .rpc();
+
+  it("Rejects a threshold larger than the guardian set", async () => {
+    try {
+      await setGuardians(guardians.map(g => g.publicKey), 4);
+      assert.fail("Should have failed - threshold exceeds guardians");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidGuardianThreshold"));
+    }
+  });
+
+  it("Rejects duplicate guardians", async () => {
+    try {
+      await setGuardians([guardians[0].publicKey, guardians[0].publicKey], 1);
+      assert.fail("Should have failed - duplicate guardian");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidGuardianSet"));
+    }
+  });
+
+  it("Rejects the owner as their own guardian", async () => {
+    try {
+      await setGuardians([owner.publicKey, guardians[0].publicKey], 1);
+      assert.fail("Should have failed - owner cannot be a guardian");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidGuardianSet"));
+    }
+  });
+
+  it("Configures a 2-of-3 guardian set", async () => {
+    await setGuardians(guardians.map(g => g.publicKey), 2);
+
+    const guardianSet = await program.account.guardianSet.fetch(guardianSetPda);
+    assert.equal(guardianSet.threshold, 2);
+    assert.equal(guardianSet.guardians.length, 3);
+    assert.isFalse(guardianSet.frozen);
+    assert.ok(guardianSet.deceasedAttestedAt.eqn(0));
+  });
+
+  it("Rejects an attestation without a guardian quorum", async () => {
+    try {
+      // The stranger's signature does not count towards the threshold
+      await guardianAction("attestDeceased", [guardians[0], stranger]);
+      assert.fail("Should have failed - only one guardian signed");
+    } catch (error) {
+      assert.ok(error.toString().includes("GuardianQuorumNotMet"));
+    }
+  });
+
+  it("Keeps claims locked while the owner is active and unattested", async () => {
+    try {
+      await requestSolClaim(owner.publicKey, heirs[0]);
+      assert.fail("Should have failed - owner is still active");
+    } catch (error) {
+      assert.ok(error.toString().includes("OwnerStillActive"));
+    }
+  });
+
+  it("Blocks an attested claim while guardians have frozen claims", async () => {
+    // Attestations only count if they are newer than the owner's last activity
+    await sleep(1500);
+    await guardianAction("attestDeceased", [guardians[0], guardians[2]]);
+    await guardianAction("freezeClaims", [guardians[1], guardians[2]]);
+
+    const guardianSet = await program.account.guardianSet.fetch(guardianSetPda);
+    assert.ok(guardianSet.deceasedAttestedAt.gtn(0));
+    assert.isTrue(guardianSet.frozen);
+
+    for (const attempt of [
+      () => requestSolClaim(owner.publicKey, heirs[0]),
+      () => finalizeSolClaim(owner.publicKey, heirs[0]),
+    ]) {
+      try {
+        await attempt();
+        assert.fail("Should have failed - claims are frozen");
+      } catch (error) {
+        assert.ok(error.toString().includes("ClaimsFrozen"));
+      }
+    }
+  });
+
+  it("Lets the heir claim immediately once attested and unfrozen", async () => {
+    await guardianAction("unfreezeClaims", [guardians[0], guardians[1]]);
+
+    const heirBefore = await provider.connection.getBalance(heirs[0].publicKey);
+    await finalizeSolClaim(owner.publicKey, heirs[0]);
+    const heirAfter = await provider.connection.getBalance(heirs[0].publicKey);
+
+    assert.equal(heirAfter - heirBefore, bequest.toNumber());
+    assert.isNull(await provider.connection.getAccountInfo(solHeirPdas[0]));
+  });
+
+  it("Voids the attestation once the owner shows activity", async () => {
+    await sleep(1500);
+    await program.methods
+      .heartbeat()
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    try {
+      await requestSolClaim(owner.publicKey, heirs[1]);
+      assert.fail("Should have failed - owner is alive after all");
+    } catch (error) {
+      assert.ok(error.toString().includes("OwnerStillActive"));
+    }
+  });
+});
@@ -0,0 +1,155 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findSolHeirPda,
+  initializeUser,
+  addSolHeir,
+  requestSolClaim,
+  finalizeSolClaim,
+} from "./helpers";
+
+describe("Estate-wide heartbeat", () => {
+  const inactivityPeriod = new anchor.BN(2); // seconds
+
+  let owner: Keypair;
+  let heirs: Keypair[];
+  let stranger: Keypair;
+
+  let userProfilePda: PublicKey;
+  let solHeirPdas: PublicKey[];
+
+  before(async () => {
+    owner = Keypair.generate();
+    heirs = [Keypair.generate(), Keypair.generate()];
+    stranger = Keypair.generate();
+
+    await airdrop([owner, stranger, ...heirs]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    solHeirPdas = heirs.map(heir => findSolHeirPda(owner.publicKey, heir.publicKey));
+
+    await initializeUser(owner);
+
+    for (let i = 0; i < heirs.length; i++) {
+      await addSolHeir(owner, heirs[i].publicKey, new anchor.BN(0.1 * LAMPORTS_PER_SOL), inactivityPeriod);
+    }
+  });
+
+  it("Records a heartbeat on the user profile", async () => {
+    await sleep(1500);
+    const before = await program.account.userProfile.fetch(userProfilePda);
+
+    await program.methods
+      .heartbeat()
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const after = await program.account.userProfile.fetch(userProfilePda);
+    assert.ok(after.lastActivity.gt(before.lastActivity));
+  });
+
+  it("Rejects a heartbeat signed by someone other than the owner", async () => {
+    try {
+      await program.methods
+        .heartbeat()
+        .accountsPartial({ userProfile: userProfilePda, owner: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+
+      assert.fail("Should have failed - signer is not the owner");
+    } catch (error) {
+      assert.match(error.toString(), /ConstraintSeeds|ConstraintHasOne/);
+    }
+  });
+
+  it("A single heartbeat keeps every designation from being claimed", async () => {
+    // Both per-heir timestamps are now stale; only the heartbeat protects them.
+    await sleep(3000);
+    await program.methods
+      .heartbeat()
+      .accountsPartial({ userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    for (let i = 0; i < heirs.length; i++) {
+      try {
+        await requestSolClaim(owner.publicKey, heirs[i]);
+        assert.fail("Should have failed - owner is still active");
+      } catch (error) {
+        assert.ok(error.toString().includes("OwnerStillActive"));
+      }
+    }
+  });
+
+  it("Allows claims once the heartbeat lapses", async () => {
+    await sleep(3000);
+
+    for (let i = 0; i < heirs.length; i++) {
+      await requestSolClaim(owner.publicKey, heirs[i]);
+    }
+    await sleep(1500);
+
+    for (let i = 0; i < heirs.length; i++) {
+      await finalizeSolClaim(owner.publicKey, heirs[i]);
+      assert.isNull(await provider.connection.getAccountInfo(solHeirPdas[i]));
+    }
+  });
+
+  it("Migration never moves the heartbeat backwards", async () => {
+    const before = await program.account.userProfile.fetch(userProfilePda);
+    const sibling = Keypair.generate();
+    const siblingHeirPda = findSolHeirPda(owner.publicKey, sibling.publicKey);
+
+    // A designation whose own timestamp is newer than the last heartbeat
+    await addSolHeir(owner, sibling.publicKey, new anchor.BN(0.1 * LAMPORTS_PER_SOL), inactivityPeriod);
+    const siblingHeir = await program.account.solHeir.fetch(siblingHeirPda);
+
+    await program.methods
+      .migrateUserProfile()
+      .accountsPartial({
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        payer: stranger.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: siblingHeirPda, isSigner: false, isWritable: false }])
+      .signers([stranger])
+      .rpc();
+
+    const after = await program.account.userProfile.fetch(userProfilePda);
+    assert.ok(after.lastActivity.gte(before.lastActivity));
+    assert.ok(after.lastActivity.eq(siblingHeir.lastActivity));
+  });
+
+  it("Migration rejects accounts that are not heir designations", async () => {
+    try {
+      await program.methods
+        .migrateUserProfile()
+        .accountsPartial({
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+          payer: stranger.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: userProfilePda, isSigner: false, isWritable: false }])
+        .signers([stranger])
+        .rpc();
+
+      assert.fail("Should have failed - not a heir account");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidHeirAccount"));
+    }
+  });
+});
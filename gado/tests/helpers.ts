@@ -0,0 +1,114 @@
+// Setup shared by the program suites. Claim timings assume the program was built
+// with `short-claim-delay`, which the `test` script in Anchor.toml takes care of.
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Gado } from "../target/types/gado";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+
+export const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+export const program = anchor.workspace.Gado as Program<Gado>;
+
+export const sleep = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
+
+// Decode every event the program logged in a confirmed transaction
+export const eventsOf = async (signature: string) => {
+  const tx = await provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return Array.from(parser.parseLogs(tx.meta.logMessages));
+};
+
+export const airdrop = async (accounts: Keypair[], sol = 5) => {
+  for (const account of accounts) {
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(account.publicKey, sol * LAMPORTS_PER_SOL)
+    );
+  }
+};
+
+// Tranche 0 keeps the pre-tranche address; other tranches append the u16 LE index.
+export const trancheSeed = (tranche: number) => {
+  if (tranche === 0) return Buffer.alloc(0);
+  const seed = Buffer.alloc(2);
+  seed.writeUInt16LE(tranche);
+  return seed;
+};
+
+export const findPda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+export const findUserProfilePda = (owner: PublicKey) => findPda(Buffer.from("user_profile"), owner.toBuffer());
+
+export const findGuardianSetPda = (owner: PublicKey) => findPda(Buffer.from("guardian_set"), owner.toBuffer());
+
+export const findSolHeirPda = (owner: PublicKey, heir: PublicKey, tranche = 0) =>
+  findPda(Buffer.from("sol_heir"), owner.toBuffer(), heir.toBuffer(), trancheSeed(tranche));
+
+export const findSolVaultPda = (solHeir: PublicKey) => findPda(Buffer.from("sol_vault"), solHeir.toBuffer());
+
+export const findTokenHeirPda = (owner: PublicKey, heir: PublicKey, mint: PublicKey, tranche = 0) =>
+  findPda(Buffer.from("token_heir"), owner.toBuffer(), heir.toBuffer(), mint.toBuffer(), trancheSeed(tranche));
+
+export const initializeUser = (owner: Keypair) =>
+  program.methods
+    .initializeUser()
+    .accountsPartial({
+      userProfile: findUserProfilePda(owner.publicKey),
+      owner: owner.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+
+export const addSolHeir = (owner: Keypair, heir: PublicKey, amount: anchor.BN, period: anchor.BN, tranche = 0) => {
+  const solHeir = findSolHeirPda(owner.publicKey, heir, tranche);
+  return program.methods
+    .addSolHeir(tranche, amount, period)
+    .accountsPartial({
+      solHeir,
+      solVault: findSolVaultPda(solHeir),
+      userProfile: findUserProfilePda(owner.publicKey),
+      owner: owner.publicKey,
+      heir,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([owner])
+    .rpc();
+};
+
+export const requestSolClaim = (owner: PublicKey, heir: Keypair, tranche = 0) =>
+  program.methods
+    .requestSolClaim()
+    .accountsPartial({
+      solHeir: findSolHeirPda(owner, heir.publicKey, tranche),
+      userProfile: findUserProfilePda(owner),
+      guardianSet: findGuardianSetPda(owner),
+      heir: heir.publicKey,
+    })
+    .signers([heir])
+    .rpc();
+
+export const finalizeSolClaim = (owner: PublicKey, heir: Keypair, tranche = 0) => {
+  const solHeir = findSolHeirPda(owner, heir.publicKey, tranche);
+  return program.methods
+    .finalizeSolClaim()
+    .accountsPartial({
+      solHeir,
+      solVault: findSolVaultPda(solHeir),
+      userProfile: findUserProfilePda(owner),
+      guardianSet: findGuardianSetPda(owner),
+      owner,
+      heir: heir.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([heir])
+    .rpc();
+};
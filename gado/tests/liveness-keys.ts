@@ -0,0 +1,175 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findPda,
+  findUserProfilePda,
+  findSolHeirPda,
+  findSolVaultPda,
+  initializeUser,
+  addSolHeir,
+} from "./helpers";
+
+describe("Liveness keys", () => {
+  let owner: Keypair;
+  let heir: Keypair;
+  let hotKey: Keypair;
+  let shortLivedKey: Keypair;
+
+  let userProfilePda: PublicKey;
+  let solHeirPda: PublicKey;
+  let solVaultPda: PublicKey;
+
+  const livenessKeyPda = (key: PublicKey) =>
+    findPda(Buffer.from("liveness_key"), owner.publicKey.toBuffer(), key.toBuffer());
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+    hotKey = Keypair.generate();
+    shortLivedKey = Keypair.generate();
+
+    await airdrop([owner, heir, hotKey]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+    solVaultPda = findSolVaultPda(solHeirPda);
+
+    await initializeUser(owner);
+
+    await addSolHeir(owner, heir.publicKey, new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN(30 * 24 * 60 * 60));
+  });
+
+  const registerKey = (key: PublicKey, expiresAt: number) =>
+    program.methods
+      .registerLivenessKey(new anchor.BN(expiresAt))
+      .accountsPartial({
+        livenessKey: livenessKeyPda(key),
+        key,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  const delegatedHeartbeat = (key: Keypair) =>
+    program.methods
+      .delegatedHeartbeat()
+      .accountsPartial({
+        livenessKey: livenessKeyPda(key.publicKey),
+        userProfile: userProfilePda,
+        key: key.publicKey,
+      })
+      .signers([key])
+      .rpc();
+
+  it("Registers a liveness key without expiry", async () => {
+    await registerKey(hotKey.publicKey, 0);
+
+    const livenessKey = await program.account.livenessKey.fetch(livenessKeyPda(hotKey.publicKey));
+    assert.ok(livenessKey.owner.equals(owner.publicKey));
+    assert.ok(livenessKey.key.equals(hotKey.publicKey));
+    assert.ok(livenessKey.expiresAt.eqn(0));
+  });
+
+  it("Rejects an expiry in the past", async () => {
+    try {
+      await registerKey(shortLivedKey.publicKey, Math.floor(Date.now() / 1000) - 60);
+      assert.fail("Should have failed - expiry already passed");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidExpiry"));
+    }
+  });
+
+  it("Records a heartbeat signed by the liveness key", async () => {
+    await sleep(1500);
+    const before = await program.account.userProfile.fetch(userProfilePda);
+
+    await delegatedHeartbeat(hotKey);
+
+    const after = await program.account.userProfile.fetch(userProfilePda);
+    assert.ok(after.lastActivity.gt(before.lastActivity));
+  });
+
+  it("Rejects a heartbeat from an unregistered key", async () => {
+    try {
+      await delegatedHeartbeat(heir);
+      assert.fail("Should have failed - key is not registered");
+    } catch (error) {
+      assert.ok(error.toString().includes("AccountNotInitialized"));
+    }
+  });
+
+  it("Never lets a liveness key cancel, withdraw or redirect funds", async () => {
+    const attempts = [
+      program.methods.cancelSolHeir().accountsPartial({
+        solHeir: solHeirPda,
+        solVault: solVaultPda,
+        userProfile: userProfilePda,
+        owner: hotKey.publicKey,
+      }),
+      program.methods.decreaseSolAmount(new anchor.BN(1)).accountsPartial({
+        solHeir: solHeirPda,
+        solVault: solVaultPda,
+        userProfile: userProfilePda,
+        owner: hotKey.publicKey,
+        systemProgram: SystemProgram.programId,
+      }),
+    ];
+
+    for (const attempt of attempts) {
+      try {
+        await attempt.signers([hotKey]).rpc();
+        assert.fail("Should have failed - liveness key is not the owner");
+      } catch (error) {
+        assert.match(error.toString(), /ConstraintSeeds|ConstraintHasOne/);
+      }
+    }
+
+    const solHeir = await program.account.solHeir.fetch(solHeirPda);
+    assert.ok(solHeir.amount.eqn(LAMPORTS_PER_SOL));
+  });
+
+  it("Rejects a heartbeat from an expired key", async () => {
+    await registerKey(shortLivedKey.publicKey, Math.floor(Date.now() / 1000) + 2);
+    await sleep(4000);
+
+    try {
+      await delegatedHeartbeat(shortLivedKey);
+      assert.fail("Should have failed - key expired");
+    } catch (error) {
+      assert.ok(error.toString().includes("LivenessKeyExpired"));
+    }
+  });
+
+  it("Revokes a liveness key", async () => {
+    await program.methods
+      .revokeLivenessKey()
+      .accountsPartial({
+        livenessKey: livenessKeyPda(hotKey.publicKey),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+      })
+      .signers([owner])
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(livenessKeyPda(hotKey.publicKey)));
+
+    try {
+      await delegatedHeartbeat(hotKey);
+      assert.fail("Should have failed - key was revoked");
+    } catch (error) {
+      assert.ok(error.toString().includes("AccountNotInitialized"));
+    }
+  });
+});
@@ -0,0 +1,185 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  SystemProgram,
+  PublicKey,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  createMint,
+  createInitializeMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  freezeAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  eventsOf,
+  airdrop,
+  findUserProfilePda,
+  findTokenHeirPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Mint risk policy", () => {
+  const inactivityPeriod = new anchor.BN(30 * 24 * 60 * 60); // 30 days
+
+  let owner: Keypair;
+  let issuer: Keypair;
+  let heir: Keypair;
+  let userProfilePda: PublicKey;
+
+  const tokenHeirPda = (mint: PublicKey) => findTokenHeirPda(owner.publicKey, heir.publicKey, mint);
+
+  const addTokenHeir = (mint: PublicKey, tokenProgram: PublicKey, policy: any) =>
+    program.methods
+      .addTokenHeir(0, new anchor.BN(1_000_000), inactivityPeriod, policy)
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(mint),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenMint: mint,
+        ownerTokenAccount: getAssociatedTokenAddressSync(mint, owner.publicKey, false, tokenProgram),
+        escrowTokenAccount: getAssociatedTokenAddressSync(mint, tokenHeirPda(mint), true, tokenProgram),
+        tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc({ commitment: "confirmed" });
+
+  const fundOwner = async (mint: PublicKey, tokenProgram: PublicKey) => {
+    const account = await createAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      mint,
+      owner.publicKey,
+      undefined,
+      tokenProgram
+    );
+    await mintTo(provider.connection, owner, mint, account, issuer, 1_000_000_000, [], undefined, tokenProgram);
+  };
+
+  before(async () => {
+    owner = Keypair.generate();
+    issuer = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, issuer]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  describe("Freeze authority", () => {
+    let mint: PublicKey;
+
+    before(async () => {
+      mint = await createMint(provider.connection, issuer, issuer.publicKey, issuer.publicKey, 6);
+      await fundOwner(mint, TOKEN_PROGRAM_ID);
+    });
+
+    it("Rejects the mint under the reject policy", async () => {
+      try {
+        await addTokenHeir(mint, TOKEN_PROGRAM_ID, { reject: {} });
+        assert.fail("Should have failed - mint has a freeze authority");
+      } catch (error) {
+        assert.ok(error.toString().includes("MintHasFreezeAuthority"));
+      }
+    });
+
+    it("Accepts the mint with a warning event under the warn policy", async () => {
+      const signature = await addTokenHeir(mint, TOKEN_PROGRAM_ID, { warn: {} });
+
+      const events = await eventsOf(signature);
+      const warning = events.find(e => e.name.toLowerCase() === "riskymintaccepted");
+
+      assert.ok(warning, "RiskyMintAccepted event not emitted");
+      assert.ok(warning.data.tokenMint.equals(mint));
+      assert.isTrue(warning.data.freezeAuthority);
+      assert.isFalse(warning.data.permanentDelegate);
+    });
+
+    it("Reports a frozen escrow clearly", async () => {
+      const escrow = getAssociatedTokenAddressSync(mint, tokenHeirPda(mint), true);
+      await freezeAccount(provider.connection, issuer, escrow, mint, issuer);
+
+      try {
+        await program.methods
+          .cancelTokenHeir()
+          .accountsPartial({
+            tokenHeir: tokenHeirPda(mint),
+            userProfile: userProfilePda,
+            owner: owner.publicKey,
+            tokenMint: mint,
+            ownerTokenAccount: getAssociatedTokenAddressSync(mint, owner.publicKey),
+            escrowTokenAccount: escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([owner])
+          .rpc();
+
+        assert.fail("Should have failed - escrow is frozen");
+      } catch (error) {
+        assert.ok(error.toString().includes("EscrowFrozen"));
+      }
+    });
+  });
+
+  describe("Permanent delegate", () => {
+    let mint: PublicKey;
+
+    before(async () => {
+      const mintKeypair = Keypair.generate();
+      mint = mintKeypair.publicKey;
+      const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: issuer.publicKey,
+            newAccountPubkey: mint,
+            space: mintLen,
+            lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializePermanentDelegateInstruction(mint, issuer.publicKey, TOKEN_2022_PROGRAM_ID),
+          createInitializeMintInstruction(mint, 6, issuer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+        ),
+        [issuer, mintKeypair]
+      );
+      await fundOwner(mint, TOKEN_2022_PROGRAM_ID);
+    });
+
+    it("Rejects the mint under the reject policy", async () => {
+      try {
+        await addTokenHeir(mint, TOKEN_2022_PROGRAM_ID, { reject: {} });
+        assert.fail("Should have failed - mint has a permanent delegate");
+      } catch (error) {
+        assert.ok(error.toString().includes("MintHasPermanentDelegate"));
+      }
+    });
+
+    it("Accepts the mint silently under the allow policy", async () => {
+      const signature = await addTokenHeir(mint, TOKEN_2022_PROGRAM_ID, { allow: {} });
+
+      const events = await eventsOf(signature);
+      assert.notOk(events.find(e => e.name.toLowerCase() === "riskymintaccepted"));
+
+      const tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda(mint));
+      assert.equal(tokenHeir.amount.toNumber(), 1_000_000);
+    });
+  });
+});
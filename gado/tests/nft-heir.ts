@@ -0,0 +1,191 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  AccountMeta,
+  Keypair,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  trancheSeed,
+  findPda,
+  findUserProfilePda,
+  findGuardianSetPda,
+  initializeUser,
+} from "./helpers";
+
+describe("NFT bundles", () => {
+  let owner: Keypair;
+  let heir: Keypair;
+  let nfts: PublicKey[];
+  let fungibleMint: PublicKey;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  const nftHeirPda = (tranche: number) =>
+    findPda(Buffer.from("nft_heir"), owner.publicKey.toBuffer(), heir.publicKey.toBuffer(), trancheSeed(tranche));
+
+  // One [mint, first, second] triple per NFT, as the bundle instructions expect
+  const nftAccounts = (mints: PublicKey[], first: (mint: PublicKey) => PublicKey, second: (mint: PublicKey) => PublicKey) =>
+    mints.flatMap((mint): AccountMeta[] => [
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: first(mint), isSigner: false, isWritable: true },
+      { pubkey: second(mint), isSigner: false, isWritable: true },
+    ]);
+  const ownerAta = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, owner.publicKey);
+  const heirAta = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, heir.publicKey);
+  const escrowAta = (tranche: number) => (mint: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, nftHeirPda(tranche), true);
+
+  const addNftHeir = (tranche: number, mints: PublicKey[], period: number) =>
+    program.methods
+      .addNftHeir(tranche, new anchor.BN(period))
+      .accountsPartial({
+        nftHeir: nftHeirPda(tranche),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(nftAccounts(mints, ownerAta, escrowAta(tranche)))
+      .signers([owner])
+      .rpc();
+
+  const finalizeNftClaim = (mints: PublicKey[]) =>
+    program.methods
+      .finalizeNftClaim()
+      .accountsPartial({
+        nftHeir: nftHeirPda(0),
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(nftAccounts(mints, escrowAta(0), heirAta))
+      .signers([heir])
+      .rpc();
+
+  const createOwnedMint = async (decimals: number, supply: number) => {
+    const mint = await createMint(provider.connection, owner, owner.publicKey, null, decimals);
+    const account = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+    await mintTo(provider.connection, owner, mint, account, owner, supply);
+    return mint;
+  };
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    nfts = [];
+    for (let i = 0; i < 3; i++) {
+      nfts.push(await createOwnedMint(0, 1));
+    }
+    fungibleMint = await createOwnedMint(0, 2);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Rejects a mint that is not an NFT", async () => {
+    try {
+      await addNftHeir(0, [nfts[0], fungibleMint], 1);
+      assert.fail("Should have failed - supply is 2");
+    } catch (error) {
+      assert.ok(error.toString().includes("NotAnNft"));
+    }
+  });
+
+  it("Rejects a bundle listing the same NFT twice", async () => {
+    try {
+      await addNftHeir(0, [nfts[0], nfts[0]], 1);
+      assert.fail("Should have failed - duplicate mint");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidNftBundle"));
+    }
+  });
+
+  it("Escrows a bundle of NFTs for one heir", async () => {
+    await addNftHeir(0, [nfts[0], nfts[1]], 1);
+
+    const nftHeir = await program.account.nftHeir.fetch(nftHeirPda(0));
+    assert.ok(nftHeir.heir.equals(heir.publicKey));
+    assert.deepEqual(nftHeir.mints.map(m => m.toBase58()), [nfts[0].toBase58(), nfts[1].toBase58()]);
+
+    for (const mint of [nfts[0], nfts[1]]) {
+      const escrow = await getAccount(provider.connection, escrowAta(0)(mint));
+      assert.equal(Number(escrow.amount), 1);
+    }
+  });
+
+  it("Refuses to finalize with part of the bundle missing", async () => {
+    await sleep(2000);
+    await program.methods
+      .requestNftClaim()
+      .accountsPartial({ nftHeir: nftHeirPda(0), userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    try {
+      await finalizeNftClaim([nfts[0]]);
+      assert.fail("Should have failed - second NFT missing");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidNftBundle"));
+    }
+  });
+
+  it("Delivers every NFT of the bundle in one claim", async () => {
+    await finalizeNftClaim([nfts[0], nfts[1]]);
+
+    for (const mint of [nfts[0], nfts[1]]) {
+      const received = await getAccount(provider.connection, heirAta(mint));
+      assert.equal(Number(received.amount), 1);
+      assert.isNull(await provider.connection.getAccountInfo(escrowAta(0)(mint)));
+    }
+    assert.isNull(await provider.connection.getAccountInfo(nftHeirPda(0)));
+  });
+
+  it("Returns the NFTs to the owner on cancel", async () => {
+    await addNftHeir(1, [nfts[2]], 30 * 24 * 60 * 60);
+
+    await program.methods
+      .cancelNftHeir()
+      .accountsPartial({
+        nftHeir: nftHeirPda(1),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(nftAccounts([nfts[2]], escrowAta(1), ownerAta))
+      .signers([owner])
+      .rpc();
+
+    const returned = await getAccount(provider.connection, ownerAta(nfts[2]));
+    assert.equal(Number(returned.amount), 1);
+    assert.isNull(await provider.connection.getAccountInfo(escrowAta(1)(nfts[2])));
+    assert.isNull(await provider.connection.getAccountInfo(nftHeirPda(1)));
+  });
+});
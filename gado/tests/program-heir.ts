@@ -0,0 +1,147 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { UpgradeDummy } from "../target/types/upgrade_dummy";
+import {
+  BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+  Keypair,
+  ParsedAccountData,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findPda,
+  findUserProfilePda,
+  findGuardianSetPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Program upgrade authority inheritance", () => {
+  // Deployed upgradeable by `anchor test`, with the provider wallet as upgrade authority
+  const dummy = anchor.workspace.UpgradeDummy as Program<UpgradeDummy>;
+
+  const owner = (provider.wallet as anchor.Wallet).payer;
+  let heirs: Keypair[];
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  const [programData] = PublicKey.findProgramAddressSync(
+    [dummy.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  );
+
+  const programHeirPda = (heir: PublicKey) =>
+    findPda(Buffer.from("program_heir"), owner.publicKey.toBuffer(), heir.toBuffer(), dummy.programId.toBuffer());
+
+  const upgradeAuthority = async () => {
+    const account = await provider.connection.getParsedAccountInfo(programData);
+    return (account.value.data as ParsedAccountData).parsed.info.authority;
+  };
+
+  const addProgramHeir = (heir: PublicKey, period: number) =>
+    program.methods
+      .addProgramHeir(new anchor.BN(period))
+      .accountsPartial({
+        programHeir: programHeirPda(heir),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir,
+        upgradeableProgram: dummy.programId,
+        programData,
+        upgradeableLoader: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  before(async () => {
+    heirs = [Keypair.generate(), Keypair.generate()];
+
+    await airdrop(heirs);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    // Other suites may already have set up the provider wallet
+    if (!(await provider.connection.getAccountInfo(userProfilePda))) {
+      await initializeUser(owner);
+    }
+  });
+
+  it("Takes custody of the upgrade authority", async () => {
+    assert.equal(await upgradeAuthority(), owner.publicKey.toBase58());
+
+    await addProgramHeir(heirs[0].publicKey, 30 * 24 * 60 * 60);
+
+    assert.equal(await upgradeAuthority(), programHeirPda(heirs[0].publicKey).toBase58());
+    const programHeir = await program.account.programHeir.fetch(programHeirPda(heirs[0].publicKey));
+    assert.ok(programHeir.upgradeableProgram.equals(dummy.programId));
+  });
+
+  it("Rejects a program the owner can no longer upgrade", async () => {
+    try {
+      await addProgramHeir(heirs[1].publicKey, 1);
+      assert.fail("Should have failed - the first designation holds the authority");
+    } catch (error) {
+      assert.ok(error.toString().includes("Unauthorized"));
+    }
+  });
+
+  it("Lets the owner reclaim the authority before any claim", async () => {
+    await program.methods
+      .cancelProgramHeir()
+      .accountsPartial({
+        programHeir: programHeirPda(heirs[0].publicKey),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        upgradeableProgram: dummy.programId,
+        programData,
+        upgradeableLoader: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    assert.equal(await upgradeAuthority(), owner.publicKey.toBase58());
+    assert.isNull(await provider.connection.getAccountInfo(programHeirPda(heirs[0].publicKey)));
+  });
+
+  it("Hands the upgrade authority to the heir on claim", async () => {
+    const heir = heirs[1];
+    const programHeir = programHeirPda(heir.publicKey);
+    await addProgramHeir(heir.publicKey, 1);
+
+    await sleep(2000);
+    await program.methods
+      .requestProgramClaim()
+      .accountsPartial({ programHeir, userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    await program.methods
+      .finalizeProgramClaim()
+      .accountsPartial({
+        programHeir,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        upgradeableProgram: dummy.programId,
+        programData,
+        heir: heir.publicKey,
+        upgradeableLoader: BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+      })
+      .signers([heir])
+      .rpc();
+
+    assert.equal(await upgradeAuthority(), heir.publicKey.toBase58());
+    assert.isNull(await provider.connection.getAccountInfo(programHeir));
+
+    // The program itself is untouched
+    await dummy.methods.ping().rpc();
+  });
+});
@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findSolHeirPda,
+  findSolVaultPda,
+  initializeUser,
+  addSolHeir,
+  requestSolClaim,
+  finalizeSolClaim,
+} from "./helpers";
+
+describe("SOL inheritance claims", () => {
+  const bequest = new anchor.BN(0.25 * LAMPORTS_PER_SOL);
+  const inactivityPeriod = new anchor.BN(1); // seconds
+
+  let owner: Keypair;
+  let heir: Keypair;
+
+  let userProfilePda: PublicKey;
+  let solHeirPda: PublicKey;
+  let solVaultPda: PublicKey;
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    solHeirPda = findSolHeirPda(owner.publicKey, heir.publicKey);
+    solVaultPda = findSolVaultPda(solHeirPda);
+
+    await initializeUser(owner);
+
+    await addSolHeir(owner, heir.publicKey, bequest, inactivityPeriod);
+  });
+
+  it("Escrows the bequest in a data-less, system-owned vault", async () => {
+    const vault = await provider.connection.getAccountInfo(solVaultPda);
+    const reserve = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    assert.ok(vault.owner.equals(SystemProgram.programId));
+    assert.equal(vault.data.length, 0);
+    assert.equal(vault.lamports, bequest.toNumber() + reserve);
+  });
+
+  it("Delivers the escrowed SOL to the heir once the owner is inactive", async () => {
+    await sleep(3000);
+
+    const heirBefore = await provider.connection.getBalance(heir.publicKey);
+    const ownerBefore = await provider.connection.getBalance(owner.publicKey);
+    const rentLocked =
+      (await provider.connection.getBalance(solHeirPda)) +
+      (await provider.connection.getMinimumBalanceForRentExemption(0));
+    const profileBefore = await program.account.userProfile.fetch(userProfilePda);
+
+    await requestSolClaim(owner.publicKey, heir);
+    await sleep(1500);
+
+    await finalizeSolClaim(owner.publicKey, heir);
+
+    const heirAfter = await provider.connection.getBalance(heir.publicKey);
+    const ownerAfter = await provider.connection.getBalance(owner.publicKey);
+    const profileAfter = await program.account.userProfile.fetch(userProfilePda);
+
+    // The provider wallet pays the fee, so the heir receives exactly the bequest.
+    assert.equal(heirAfter - heirBefore, bequest.toNumber());
+    // Rent and the vault reserve go back to the owner who paid them.
+    assert.equal(ownerAfter - ownerBefore, rentLocked);
+    assert.isNull(await provider.connection.getAccountInfo(solHeirPda));
+    assert.isNull(await provider.connection.getAccountInfo(solVaultPda));
+    assert.equal(profileAfter.totalInheritances, profileBefore.totalInheritances - 1);
+  });
+
+  it("Rejects a second claim", async () => {
+    try {
+      await finalizeSolClaim(owner.publicKey, heir);
+
+      assert.fail("Should have failed - inheritance already claimed");
+    } catch (error) {
+      // The designation was closed by the successful claim
+      assert.ok(error.toString().includes("AccountNotInitialized"));
+    }
+  });
+
+  it("Refuses to close a designation that has not been claimed", async () => {
+    const otherHeir = Keypair.generate();
+    const otherHeirPda = findSolHeirPda(owner.publicKey, otherHeir.publicKey);
+
+    await program.methods
+      .addSolHeir(0, bequest, new anchor.BN(7 * 24 * 60 * 60))
+      .accountsPartial({
+        solHeir: otherHeirPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: otherHeir.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    try {
+      await program.methods
+        .closeClaimedSolHeir()
+        .accountsPartial({
+          solHeir: otherHeirPda,
+          userProfile: userProfilePda,
+          owner: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail("Should have failed - designation not claimed");
+    } catch (error) {
+      assert.ok(error.toString().includes("NotClaimed"));
+    }
+  });
+});
@@ -0,0 +1,188 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Authorized,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  Lockup,
+  ParsedAccountData,
+  StakeAuthorizationLayout,
+  StakeProgram,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  PublicKey,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findPda,
+  findUserProfilePda,
+  findGuardianSetPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Stake account inheritance", () => {
+  let owner: Keypair;
+  let heir: Keypair;
+  let votePubkey: PublicKey;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  const stakeHeirPda = (stakeAccount: PublicKey) =>
+    findPda(Buffer.from("stake_heir"), owner.publicKey.toBuffer(), heir.publicKey.toBuffer(), stakeAccount.toBuffer());
+
+  // Create a stake account controlled by the owner and delegate it to the local validator
+  const createDelegatedStake = async () => {
+    const stakeAccount = Keypair.generate();
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction()
+        .add(
+          StakeProgram.createAccount({
+            fromPubkey: owner.publicKey,
+            stakePubkey: stakeAccount.publicKey,
+            authorized: new Authorized(owner.publicKey, owner.publicKey),
+            lockup: new Lockup(0, 0, PublicKey.default),
+            lamports: 2 * LAMPORTS_PER_SOL,
+          })
+        )
+        .add(
+          StakeProgram.delegate({
+            stakePubkey: stakeAccount.publicKey,
+            authorizedPubkey: owner.publicKey,
+            votePubkey,
+          })
+        ),
+      [owner, stakeAccount]
+    );
+    return stakeAccount.publicKey;
+  };
+
+  const stakeInfo = async (stakeAccount: PublicKey) => {
+    const account = await provider.connection.getParsedAccountInfo(stakeAccount);
+    return (account.value.data as ParsedAccountData).parsed.info;
+  };
+
+  const addStakeHeir = (stakeAccount: PublicKey, period: number) =>
+    program.methods
+      .addStakeHeir(new anchor.BN(period))
+      .accountsPartial({
+        stakeHeir: stakeHeirPda(stakeAccount),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        stakeAccount,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        stakeProgram: StakeProgram.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir], 10);
+
+    const { current } = await provider.connection.getVoteAccounts();
+    votePubkey = new PublicKey(current[0].votePubkey);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Rejects a stake account the owner does not control", async () => {
+    const stakeAccount = await createDelegatedStake();
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        StakeProgram.authorize({
+          stakePubkey: stakeAccount,
+          authorizedPubkey: owner.publicKey,
+          newAuthorizedPubkey: heir.publicKey,
+          stakeAuthorizationType: StakeAuthorizationLayout.Withdrawer,
+        })
+      ),
+      [owner]
+    );
+
+    try {
+      await addStakeHeir(stakeAccount, 1);
+      assert.fail("Should have failed - owner is not the withdrawer");
+    } catch (error) {
+      assert.ok(error.toString().includes("Unauthorized"));
+    }
+  });
+
+  it("Hands a delegated stake account to the heir without unstaking", async () => {
+    const stakeAccount = await createDelegatedStake();
+    const stakeHeir = stakeHeirPda(stakeAccount);
+
+    await addStakeHeir(stakeAccount, 1);
+
+    let info = await stakeInfo(stakeAccount);
+    assert.equal(info.meta.authorized.staker, stakeHeir.toBase58());
+    assert.equal(info.meta.authorized.withdrawer, stakeHeir.toBase58());
+
+    await sleep(2000);
+    await program.methods
+      .requestStakeClaim()
+      .accountsPartial({ stakeHeir, userProfile: userProfilePda, guardianSet: guardianSetPda, stakeAccount, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    await program.methods
+      .finalizeStakeClaim()
+      .accountsPartial({
+        stakeHeir,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        stakeAccount,
+        heir: heir.publicKey,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        stakeProgram: StakeProgram.programId,
+      })
+      .signers([heir])
+      .rpc();
+
+    info = await stakeInfo(stakeAccount);
+    assert.equal(info.meta.authorized.staker, heir.publicKey.toBase58());
+    assert.equal(info.meta.authorized.withdrawer, heir.publicKey.toBase58());
+    assert.equal(info.stake.delegation.voter, votePubkey.toBase58());
+    assert.equal(info.stake.delegation.deactivationEpoch, "18446744073709551615");
+    assert.isNull(await provider.connection.getAccountInfo(stakeHeir));
+  });
+
+  it("Returns both authorities to the owner on cancel", async () => {
+    const stakeAccount = await createDelegatedStake();
+    await addStakeHeir(stakeAccount, 30 * 24 * 60 * 60);
+
+    await program.methods
+      .cancelStakeHeir()
+      .accountsPartial({
+        stakeHeir: stakeHeirPda(stakeAccount),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        stakeAccount,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        stakeProgram: StakeProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    const info = await stakeInfo(stakeAccount);
+    assert.equal(info.meta.authorized.staker, owner.publicKey.toBase58());
+    assert.equal(info.meta.authorized.withdrawer, owner.publicKey.toBase58());
+    assert.isNull(await provider.connection.getAccountInfo(stakeHeirPda(stakeAccount)));
+  });
+});
@@ -0,0 +1,212 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  SystemProgram,
+  PublicKey,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMintLen,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findTokenHeirPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Token-2022 inheritance", () => {
+  const feeBasisPoints = 100; // 1% transfer fee
+
+  let owner: Keypair;
+  let heirs: Keypair[];
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+  let tokenMint: PublicKey;
+  let ownerTokenAccount: PublicKey;
+
+  const tokenHeirPda = (heir: PublicKey) => findTokenHeirPda(owner.publicKey, heir, tokenMint);
+  const escrowFor = (heir: PublicKey) =>
+    getAssociatedTokenAddressSync(tokenMint, tokenHeirPda(heir), true, TOKEN_2022_PROGRAM_ID);
+
+  const addTokenHeir = (heir: PublicKey, amount: number, period: number) =>
+    program.methods
+      .addTokenHeir(0, new anchor.BN(amount), new anchor.BN(period), { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heir),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir,
+        tokenMint,
+        ownerTokenAccount,
+        escrowTokenAccount: escrowFor(heir),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  before(async () => {
+    owner = Keypair.generate();
+    heirs = [Keypair.generate(), Keypair.generate()];
+
+    await airdrop([owner, ...heirs]);
+
+    // Token-2022 mint with a transfer fee extension
+    const mintKeypair = Keypair.generate();
+    tokenMint = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: owner.publicKey,
+          newAccountPubkey: tokenMint,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          tokenMint,
+          owner.publicKey,
+          owner.publicKey,
+          feeBasisPoints,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(tokenMint, 6, owner.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [owner, mintKeypair]
+    );
+
+    ownerTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      tokenMint,
+      owner.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      tokenMint,
+      ownerTokenAccount,
+      owner.publicKey,
+      1_000_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Escrows Token-2022 tokens and records the amount received after fees", async () => {
+    await addTokenHeir(heirs[0].publicKey, 100_000_000, 30 * 24 * 60 * 60);
+
+    const escrow = await getAccount(provider.connection, escrowFor(heirs[0].publicKey), undefined, TOKEN_2022_PROGRAM_ID);
+    const tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda(heirs[0].publicKey));
+
+    assert.equal(Number(escrow.amount), 99_000_000);
+    assert.equal(tokenHeir.amount.toNumber(), 99_000_000);
+  });
+
+  it("Records top-ups net of the transfer fee", async () => {
+    await program.methods
+      .increaseTokenAmount(new anchor.BN(10_000_000))
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heirs[0].publicKey),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenMint,
+        ownerTokenAccount,
+        escrowTokenAccount: escrowFor(heirs[0].publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    const tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda(heirs[0].publicKey));
+    assert.equal(tokenHeir.amount.toNumber(), 99_000_000 + 9_900_000);
+  });
+
+  it("Cancels and closes an escrow holding withheld fees", async () => {
+    await program.methods
+      .cancelTokenHeir()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heirs[0].publicKey),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenMint,
+        ownerTokenAccount,
+        escrowTokenAccount: escrowFor(heirs[0].publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(escrowFor(heirs[0].publicKey)));
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda(heirs[0].publicKey)));
+  });
+
+  it("Delivers Token-2022 tokens to the heir on claim", async () => {
+    const heir = heirs[1];
+    const tokenHeir = tokenHeirPda(heir.publicKey);
+    const heirTokenAccount = getAssociatedTokenAddressSync(tokenMint, heir.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+    await addTokenHeir(heir.publicKey, 50_000_000, 1);
+    await sleep(2000);
+
+    await program.methods
+      .requestTokenClaim()
+      .accountsPartial({ tokenHeir, userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    await program.methods
+      .finalizeTokenClaim()
+      .accountsPartial({
+        tokenHeir,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        escrowTokenAccount: escrowFor(heir.publicKey),
+        heirTokenAccount,
+        tokenMint,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([heir])
+      .rpc();
+
+    // 49.5M arrived in escrow; the 1% fee applies again on the way out
+    const received = await getAccount(provider.connection, heirTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(Number(received.amount), 49_005_000);
+    assert.isNull(await provider.connection.getAccountInfo(escrowFor(heir.publicKey)));
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeir));
+  });
+});
@@ -0,0 +1,179 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  AccountMeta,
+  Keypair,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  trancheSeed,
+  findPda,
+  findUserProfilePda,
+  findGuardianSetPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Token baskets", () => {
+  let owner: Keypair;
+  let heir: Keypair;
+  let mints: PublicKey[];
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  const basketPda = (tranche: number) =>
+    findPda(Buffer.from("token_basket_heir"), owner.publicKey.toBuffer(), heir.publicKey.toBuffer(), trancheSeed(tranche));
+
+  // One [mint, first, second] triple per basket entry, as the basket instructions expect
+  const entryAccounts = (entryMints: PublicKey[], first: (mint: PublicKey) => PublicKey, second: (mint: PublicKey) => PublicKey) =>
+    entryMints.flatMap((mint): AccountMeta[] => [
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: first(mint), isSigner: false, isWritable: true },
+      { pubkey: second(mint), isSigner: false, isWritable: true },
+    ]);
+  const ownerAta = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, owner.publicKey);
+  const heirAta = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, heir.publicKey);
+  const escrowAta = (tranche: number) => (mint: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, basketPda(tranche), true);
+
+  const addBasket = (tranche: number, entryMints: PublicKey[], amounts: number[], period: number) =>
+    program.methods
+      .addTokenBasketHeir(tranche, amounts.map(amount => new anchor.BN(amount)), new anchor.BN(period), { reject: {} })
+      .accountsPartial({
+        tokenBasketHeir: basketPda(tranche),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(entryAccounts(entryMints, ownerAta, escrowAta(tranche)))
+      .signers([owner])
+      .rpc();
+
+  const balance = async (account: PublicKey) => Number((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    mints = [];
+    for (const decimals of [6, 9, 5]) {
+      const mint = await createMint(provider.connection, owner, owner.publicKey, null, decimals);
+      const account = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+      await mintTo(provider.connection, owner, mint, account, owner, 1_000_000_000);
+      mints.push(mint);
+    }
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Rejects a basket whose amounts do not match its entries", async () => {
+    try {
+      await addBasket(0, mints, [100, 200], 1);
+      assert.fail("Should have failed - three mints, two amounts");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidTokenBasket"));
+    }
+  });
+
+  it("Rejects a basket listing the same mint twice", async () => {
+    try {
+      await addBasket(0, [mints[0], mints[0]], [100, 200], 1);
+      assert.fail("Should have failed - duplicate mint");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidTokenBasket"));
+    }
+  });
+
+  it("Escrows every entry of the basket in one instruction", async () => {
+    await addBasket(0, mints, [100_000, 200_000, 300_000], 1);
+
+    const basket = await program.account.tokenBasketHeir.fetch(basketPda(0));
+    assert.deepEqual(
+      basket.entries.map(entry => [entry.mint.toBase58(), entry.amount.toNumber()]),
+      [
+        [mints[0].toBase58(), 100_000],
+        [mints[1].toBase58(), 200_000],
+        [mints[2].toBase58(), 300_000],
+      ]
+    );
+    assert.equal(await balance(escrowAta(0)(mints[1])), 200_000);
+  });
+
+  it("Pays out the whole basket in one claim", async () => {
+    await sleep(2000);
+    await program.methods
+      .requestTokenBasketClaim()
+      .accountsPartial({ tokenBasketHeir: basketPda(0), userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    await program.methods
+      .finalizeTokenBasketClaim()
+      .accountsPartial({
+        tokenBasketHeir: basketPda(0),
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(entryAccounts(mints, escrowAta(0), heirAta))
+      .signers([heir])
+      .rpc();
+
+    assert.deepEqual(
+      await Promise.all(mints.map(mint => balance(heirAta(mint)))),
+      [100_000, 200_000, 300_000]
+    );
+    for (const mint of mints) {
+      assert.isNull(await provider.connection.getAccountInfo(escrowAta(0)(mint)));
+    }
+    assert.isNull(await provider.connection.getAccountInfo(basketPda(0)));
+  });
+
+  it("Returns every entry to the owner on cancel", async () => {
+    const before = await balance(ownerAta(mints[0]));
+    await addBasket(1, [mints[0], mints[2]], [50_000, 60_000], 30 * 24 * 60 * 60);
+
+    await program.methods
+      .cancelTokenBasketHeir()
+      .accountsPartial({
+        tokenBasketHeir: basketPda(1),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(entryAccounts([mints[0], mints[2]], escrowAta(1), ownerAta))
+      .signers([owner])
+      .rpc();
+
+    assert.equal(await balance(ownerAta(mints[0])), before);
+    assert.isNull(await provider.connection.getAccountInfo(escrowAta(1)(mints[2])));
+    assert.isNull(await provider.connection.getAccountInfo(basketPda(1)));
+  });
+});
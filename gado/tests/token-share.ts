@@ -0,0 +1,179 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  SystemProgram,
+  PublicKey,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findPda,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findTokenHeirPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Token share bequests", () => {
+  let owner: Keypair;
+  let heirs: Keypair[];
+  let mints: PublicKey[];
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  const tokenHeirPda = (heir: PublicKey, mint: PublicKey) => findTokenHeirPda(owner.publicKey, heir, mint);
+  const tokenEstatePda = (mint: PublicKey) =>
+    findPda(Buffer.from("token_estate"), owner.publicKey.toBuffer(), mint.toBuffer());
+  const ownerAta = (mint: PublicKey) => getAssociatedTokenAddressSync(mint, owner.publicKey);
+  const heirAta = (heir: PublicKey, mint: PublicKey) => getAssociatedTokenAddressSync(mint, heir);
+
+  const addTokenShareHeir = (heir: PublicKey, mint: PublicKey, shareBps: number) =>
+    program.methods
+      .addTokenShareHeir(0, shareBps, new anchor.BN(1), { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heir, mint),
+        tokenEstate: tokenEstatePda(mint),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir,
+        tokenMint: mint,
+        ownerTokenAccount: ownerAta(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  const requestClaim = (heir: Keypair, mint: PublicKey) =>
+    program.methods
+      .requestTokenClaim()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heir.publicKey, mint),
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        heir: heir.publicKey,
+      })
+      .signers([heir])
+      .rpc();
+
+  const finalizeClaim = (heir: Keypair, mint: PublicKey) =>
+    program.methods
+      .finalizeTokenShareClaim()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heir.publicKey, mint),
+        tokenEstate: tokenEstatePda(mint),
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        ownerTokenAccount: ownerAta(mint),
+        heirTokenAccount: heirAta(heir.publicKey, mint),
+        tokenMint: mint,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([heir])
+      .rpc();
+
+  const balance = async (account: PublicKey) => Number((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    owner = Keypair.generate();
+    heirs = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+
+    await airdrop([owner, ...heirs]);
+
+    mints = [];
+    for (let i = 0; i < 2; i++) {
+      const mint = await createMint(provider.connection, owner, owner.publicKey, null, 6);
+      const account = await createAssociatedTokenAccount(provider.connection, owner, mint, owner.publicKey);
+      await mintTo(provider.connection, owner, mint, account, owner, 1_000_000);
+      mints.push(mint);
+    }
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Approves the shared estate as delegate for several shares", async () => {
+    await addTokenShareHeir(heirs[0].publicKey, mints[0], 5_000);
+    await addTokenShareHeir(heirs[1].publicKey, mints[0], 3_000);
+
+    const account = await getAccount(provider.connection, ownerAta(mints[0]));
+    const estate = await program.account.tokenEstate.fetch(tokenEstatePda(mints[0]));
+    assert.equal(Number(account.amount), 1_000_000);
+    assert.ok(account.delegate.equals(tokenEstatePda(mints[0])));
+    assert.equal(estate.totalShareBps, 8_000);
+
+    const tokenHeir = await program.account.tokenHeir.fetch(tokenHeirPda(heirs[1].publicKey, mints[0]));
+    assert.deepEqual(tokenHeir.custody, { share: {} });
+    assert.equal(tokenHeir.shareBps, 3_000);
+  });
+
+  it("Refuses shares adding up to more than the whole balance", async () => {
+    try {
+      await addTokenShareHeir(heirs[2].publicKey, mints[0], 2_500);
+      assert.fail("Should have failed - 105% promised");
+    } catch (error) {
+      assert.ok(error.toString().includes("InvalidShare"));
+    }
+  });
+
+  it("Pays every share from the balance at the first claim", async () => {
+    await sleep(2000);
+    await requestClaim(heirs[0], mints[0]);
+    await requestClaim(heirs[1], mints[0]);
+    await sleep(1500);
+
+    // The second heir claims first; the order must not change either share
+    await finalizeClaim(heirs[1], mints[0]);
+    await finalizeClaim(heirs[0], mints[0]);
+
+    assert.equal(await balance(heirAta(heirs[1].publicKey, mints[0])), 300_000);
+    assert.equal(await balance(heirAta(heirs[0].publicKey, mints[0])), 500_000);
+    assert.equal(await balance(ownerAta(mints[0])), 200_000);
+
+    const estate = await program.account.tokenEstate.fetch(tokenEstatePda(mints[0]));
+    assert.equal(estate.snapshotBalance.toNumber(), 1_000_000);
+    assert.equal(estate.totalShareBps, 0);
+  });
+
+  it("Revokes the estate's allowance when the last share is cancelled", async () => {
+    await addTokenShareHeir(heirs[2].publicKey, mints[1], 10_000);
+
+    await program.methods
+      .cancelTokenShareHeir()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda(heirs[2].publicKey, mints[1]),
+        tokenEstate: tokenEstatePda(mints[1]),
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        tokenMint: mints[1],
+        ownerTokenAccount: ownerAta(mints[1]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    const account = await getAccount(provider.connection, ownerAta(mints[1]));
+    assert.isNull(account.delegate);
+    assert.equal(Number(account.amount), 1_000_000);
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda(heirs[2].publicKey, mints[1])));
+  });
+});
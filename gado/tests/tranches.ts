@@ -0,0 +1,107 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findSolHeirPda,
+  initializeUser,
+  addSolHeir,
+  requestSolClaim,
+  finalizeSolClaim,
+} from "./helpers";
+
+describe("Tranches", () => {
+  let owner: Keypair;
+  let heir: Keypair;
+  let userProfilePda: PublicKey;
+
+  // Tranche 0 releases much later than tranche 7
+  const tranches = [
+    { index: 0, amount: new anchor.BN(0.2 * LAMPORTS_PER_SOL), period: new anchor.BN(7 * 24 * 60 * 60) },
+    { index: 7, amount: new anchor.BN(0.3 * LAMPORTS_PER_SOL), period: new anchor.BN(1) },
+  ];
+
+  const solHeirPda = (tranche: number) => findSolHeirPda(owner.publicKey, heir.publicKey, tranche);
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+
+    await initializeUser(owner);
+  });
+
+  it("Creates several SOL tranches for the same heir", async () => {
+    for (const { index, amount, period } of tranches) {
+      await addSolHeir(owner, heir.publicKey, amount, period, index);
+    }
+
+    for (const { index, amount } of tranches) {
+      const solHeir = await program.account.solHeir.fetch(solHeirPda(index));
+      assert.equal(solHeir.tranche, index);
+      assert.ok(solHeir.amount.eq(amount));
+    }
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    assert.equal(profile.totalInheritances, tranches.length);
+  });
+
+  it("Rejects re-using a tranche index", async () => {
+    try {
+      await addSolHeir(owner, heir.publicKey, new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN(1), 7);
+
+      assert.fail("Should have failed - tranche already exists");
+    } catch (error) {
+      assert.ok(error.toString().includes("already in use"));
+    }
+  });
+
+  it("Refreshes one tranche's activity without touching the other", async () => {
+    await sleep(1500);
+    const before = await program.account.solHeir.fetch(solHeirPda(0));
+
+    await program.methods
+      .updateSolActivity()
+      .accountsPartial({ solHeir: solHeirPda(0), userProfile: userProfilePda, owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const refreshed = await program.account.solHeir.fetch(solHeirPda(0));
+    const untouched = await program.account.solHeir.fetch(solHeirPda(7));
+    assert.ok(refreshed.lastActivity.gt(before.lastActivity));
+    assert.ok(untouched.lastActivity.lt(refreshed.lastActivity));
+  });
+
+  it("Claims the short tranche while the long one stays locked", async () => {
+    await sleep(2000);
+
+    try {
+      await requestSolClaim(owner.publicKey, heir, 0);
+      assert.fail("Should have failed - long tranche still locked");
+    } catch (error) {
+      assert.ok(error.toString().includes("OwnerStillActive"));
+    }
+
+    await requestSolClaim(owner.publicKey, heir, 7);
+    await sleep(1500);
+
+    const heirBefore = await provider.connection.getBalance(heir.publicKey);
+    await finalizeSolClaim(owner.publicKey, heir, 7);
+    const heirAfter = await provider.connection.getBalance(heir.publicKey);
+
+    assert.equal(heirAfter - heirBefore, tranches[1].amount.toNumber());
+    assert.isNull(await provider.connection.getAccountInfo(solHeirPda(7)));
+    assert.isNotNull(await provider.connection.getAccountInfo(solHeirPda(0)));
+  });
+});
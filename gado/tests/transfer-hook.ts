@@ -0,0 +1,208 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TransferHookMock } from "../target/types/transfer_hook_mock";
+import {
+  AccountMeta,
+  Keypair,
+  SystemProgram,
+  PublicKey,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMintLen,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  provider,
+  program,
+  sleep,
+  airdrop,
+  findUserProfilePda,
+  findGuardianSetPda,
+  findTokenHeirPda,
+  initializeUser,
+} from "./helpers";
+
+describe("Transfer-hook mints", () => {
+  const hookProgram = anchor.workspace.TransferHookMock as Program<TransferHookMock>;
+
+  const bequest = 40_000_000;
+
+  let owner: Keypair;
+  let heir: Keypair;
+
+  let userProfilePda: PublicKey;
+  let guardianSetPda: PublicKey;
+  let tokenHeirPda: PublicKey;
+  let tokenMint: PublicKey;
+  let ownerTokenAccount: PublicKey;
+  let escrowTokenAccount: PublicKey;
+  let counterPda: PublicKey;
+  let hookAccounts: AccountMeta[];
+
+  before(async () => {
+    owner = Keypair.generate();
+    heir = Keypair.generate();
+
+    await airdrop([owner, heir]);
+
+    // Token-2022 mint whose transfers run the mock hook
+    const mintKeypair = Keypair.generate();
+    tokenMint = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: owner.publicKey,
+          newAccountPubkey: tokenMint,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(tokenMint, owner.publicKey, hookProgram.programId, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(tokenMint, 6, owner.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [owner, mintKeypair]
+    );
+
+    const [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), tokenMint.toBuffer()],
+      hookProgram.programId
+    );
+    [counterPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("counter"), tokenMint.toBuffer()],
+      hookProgram.programId
+    );
+    await hookProgram.methods
+      .initializeExtraAccountMetaList()
+      .accountsPartial({
+        extraAccountMetaList,
+        counter: counterPda,
+        mint: tokenMint,
+        payer: owner.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    // What every transfer of this mint must forward as remaining accounts
+    hookAccounts = [
+      { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+      { pubkey: counterPda, isSigner: false, isWritable: true },
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+    ];
+
+    ownerTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      tokenMint,
+      owner.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      tokenMint,
+      ownerTokenAccount,
+      owner.publicKey,
+      1_000_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    userProfilePda = findUserProfilePda(owner.publicKey);
+    guardianSetPda = findGuardianSetPda(owner.publicKey);
+    tokenHeirPda = findTokenHeirPda(owner.publicKey, heir.publicKey, tokenMint);
+    escrowTokenAccount = getAssociatedTokenAddressSync(tokenMint, tokenHeirPda, true, TOKEN_2022_PROGRAM_ID);
+
+    await initializeUser(owner);
+  });
+
+  const addTokenHeir = (remainingAccounts: AccountMeta[]) =>
+    program.methods
+      .addTokenHeir(0, new anchor.BN(bequest), new anchor.BN(1), { reject: {} })
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        owner: owner.publicKey,
+        heir: heir.publicKey,
+        tokenMint,
+        ownerTokenAccount,
+        escrowTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([owner])
+      .rpc();
+
+  it("Rejects a deposit that omits the hook accounts", async () => {
+    try {
+      await addTokenHeir([]);
+      assert.fail("Should have failed - hook accounts missing");
+    } catch (error) {
+      assert.notInclude(error.toString(), "Should have failed");
+    }
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda));
+  });
+
+  it("Deposits when the hook accounts are forwarded", async () => {
+    await addTokenHeir(hookAccounts);
+
+    const escrow = await getAccount(provider.connection, escrowTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    const counter = await hookProgram.account.counter.fetch(counterPda);
+    assert.equal(Number(escrow.amount), bequest);
+    assert.equal(counter.transfers.toNumber(), 1);
+  });
+
+  it("Delivers hooked tokens to the heir on claim", async () => {
+    const heirTokenAccount = getAssociatedTokenAddressSync(tokenMint, heir.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    await sleep(2000);
+
+    await program.methods
+      .requestTokenClaim()
+      .accountsPartial({ tokenHeir: tokenHeirPda, userProfile: userProfilePda, guardianSet: guardianSetPda, heir: heir.publicKey })
+      .signers([heir])
+      .rpc();
+    await sleep(1500);
+
+    await program.methods
+      .finalizeTokenClaim()
+      .accountsPartial({
+        tokenHeir: tokenHeirPda,
+        userProfile: userProfilePda,
+        guardianSet: guardianSetPda,
+        owner: owner.publicKey,
+        escrowTokenAccount,
+        heirTokenAccount,
+        tokenMint,
+        heir: heir.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(hookAccounts)
+      .signers([heir])
+      .rpc();
+
+    const received = await getAccount(provider.connection, heirTokenAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    const counter = await hookProgram.account.counter.fetch(counterPda);
+    assert.equal(Number(received.amount), bequest);
+    assert.equal(counter.transfers.toNumber(), 2);
+    assert.isNull(await provider.connection.getAccountInfo(tokenHeirPda));
+  });
+});